@@ -0,0 +1,119 @@
+// Generates the daily lookup table used by the `table` feature.
+// The covered years can be set with MOON_PHASE_TABLE_START and
+// MOON_PHASE_TABLE_END (inclusive), which default to the year of the build
+// and the two after it. The build time is taken from SOURCE_DATE_EPOCH if
+// set, for reproducible builds. Every year takes about 8.8 KB of flash
+// (a row of six f32 per day), so the default is about 26 KB.
+// With the `ffi` feature, also generates the C header into OUT_DIR, which a
+// test in src/ffi.rs compares with include/moon_phase.h, and with `node`
+// sets up the linking of the Node.js addon.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[path = "src/elements.rs"]
+mod elements;
+
+use elements::Elements;
+
+// Year (UTC) of the build, from SOURCE_DATE_EPOCH or the clock.
+fn build_year() -> i64 {
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let secs = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("SOURCE_DATE_EPOCH must be unix seconds, got {:?}", value)),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the clock is before 1970")
+            .as_secs() as i64,
+    };
+    let days = secs.div_euclid(86400);
+    let mut year = 1970 + days.div_euclid(365);
+    while days_from_year(year) > days {
+        year -= 1;
+    }
+    year
+}
+
+fn year_from_env(key: &str, default: i64) -> i64 {
+    println!("cargo:rerun-if-env-changed={}", key);
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a year, got {:?}", key, value)),
+        Err(_) => default,
+    }
+}
+
+// Days since 1970-01-01 of January 1st of the given (proleptic Gregorian) year.
+fn days_from_year(year: i64) -> i64 {
+    let y = year - 1;
+    365 * (y - 1969) + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400) - 477
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/elements.rs");
+    if env::var_os("CARGO_FEATURE_TABLE").is_some() {
+        table();
+    }
+    #[cfg(feature="ffi")]
+    header();
+    #[cfg(feature="node")]
+    napi_build::setup();
+}
+
+// The C declarations of src/ffi.rs, see cbindgen.toml.
+#[cfg(feature="ffi")]
+fn header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).to_path_buf();
+    let config = cbindgen::Config::from_file(dir.join("cbindgen.toml")).unwrap();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(dir.join("src").join("ffi.rs"))
+        .generate()
+        .expect("can't generate the C header")
+        .write_to_file(Path::new(&env::var("OUT_DIR").unwrap()).join("moon_phase.h"));
+}
+
+fn table() {
+    let year = build_year();
+    let start = year_from_env("MOON_PHASE_TABLE_START", year);
+    let end = year_from_env("MOON_PHASE_TABLE_END", year + 2);
+    assert!(start <= end, "MOON_PHASE_TABLE_START must not be after MOON_PHASE_TABLE_END");
+    let first_day = days_from_year(start);
+    // One extra row so the last day can be interpolated as well.
+    let days = days_from_year(end + 1) - first_day + 1;
+
+    let mut out = String::new();
+    writeln!(out, "const TABLE_START_SECS: i64 = {};", first_day * 86400).unwrap();
+    // Some of the values happen to look like well known constants.
+    writeln!(out, "#[allow(clippy::approx_constant)]").unwrap();
+    writeln!(out, "static TABLE: [[f32; 6]; {}] = [", days).unwrap();
+    for day in first_day..first_day + days {
+        let j_date = day as f64 + 2440587.5;
+        let e = Elements::<f64>::new(j_date);
+        writeln!(
+            out,
+            "    [{:?}, {:?}, {:?}, {:?}, {:?}, {:?}],",
+            e.phase.rem_euclid(1.) as f32,
+            e.illumination as f32,
+            e.distance as f32,
+            e.latitude as f32,
+            e.longitude.rem_euclid(360.) as f32,
+            e.fraction as f32,
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("table.rs");
+    fs::write(path, out).unwrap();
+}
@@ -0,0 +1,77 @@
+/* C API of the moon-phase crate, see src/ffi.rs. */
+
+#ifndef MOON_PHASE_H
+#define MOON_PHASE_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum {
+  MOON_PHASE_NAME_NEW,
+  MOON_PHASE_NAME_WAXING_CRESCENT,
+  MOON_PHASE_NAME_FIRST_QUARTER,
+  MOON_PHASE_NAME_WAXING_GIBBOUS,
+  MOON_PHASE_NAME_FULL,
+  MOON_PHASE_NAME_WANING_GIBBOUS,
+  MOON_PHASE_NAME_LAST_QUARTER,
+  MOON_PHASE_NAME_WANING_CRESCENT,
+} MoonPhaseName;
+
+typedef enum {
+  MOON_ZODIAC_PISCES,
+  MOON_ZODIAC_ARIES,
+  MOON_ZODIAC_TAURUS,
+  MOON_ZODIAC_GEMINI,
+  MOON_ZODIAC_CANCER,
+  MOON_ZODIAC_LEO,
+  MOON_ZODIAC_VIRGO,
+  MOON_ZODIAC_LIBRA,
+  MOON_ZODIAC_SCORPIO,
+  MOON_ZODIAC_SAGITTARIUS,
+  MOON_ZODIAC_CAPRICORN,
+  MOON_ZODIAC_AQUARIUS,
+} MoonZodiac;
+
+typedef struct {
+  double julian_date;
+  double phase;
+  double age;
+  double illumination;
+  double distance;
+  double latitude;
+  double longitude;
+  MoonPhaseName phase_name;
+  MoonZodiac zodiac;
+} MoonPhase;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+MoonPhase moon_phase_from_secs(double secs);
+
+MoonPhase moon_phase_from_millis(int64_t millis);
+
+MoonPhase moon_phase_now(void);
+
+bool moon_phase_try_from_secs(double secs, MoonPhase *out);
+
+const char *moon_phase_name(uint32_t name);
+
+const char *moon_phase_emoji(uint32_t name);
+
+const char *moon_phase_zodiac_name(uint32_t zodiac);
+
+char *moon_phase_to_json(const MoonPhase *moon_phase);
+
+void moon_phase_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MOON_PHASE_H */
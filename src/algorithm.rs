@@ -0,0 +1,235 @@
+// The different models `MoonPhase` can be calculated with, from the cheapest
+// to the most accurate.
+
+use crate::calendar::civil_from_days;
+use crate::elements::ut_to_tt;
+use crate::ephemeris::{Ephemeris, MoonPosition};
+use crate::{julian_date_from_seconds, phase_name, Elements, MoonPhase, Zodiac};
+use crate::{MOON_SYNODIC_PERIOD, TAU};
+
+const J2000: f64 = 2451545.0;
+
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Algorithm {
+    // Sky & Telescope's algorithm by Bradley E. Schaefer, used by `new` and
+    // `from_secs`. Positions are good to a few degrees.
+    #[default]
+    Simple,
+    // John Conway's mental arithmetic rule for the age, good to about a day.
+    // Everything but the phase and age comes from `Simple`.
+    ConwayApprox,
+    // The largest periodic terms of the lunar theory as given by Jean Meeus,
+    // with the phase from the elongation of the sun. Good to about 0.1 degrees.
+    // Same as `with_ephemeris` with `Meeus`.
+    Meeus,
+    // The truncated ELP-2000/82 series, see the `elp` module.
+    #[cfg(feature="high-precision")]
+    HighPrecision,
+}
+
+// Estimated maximum errors of a `MoonPhase`. These are comparisons against
+// the more accurate models over 1900 to 2100 with some margin, not rigorous
+// bounds, and grow for dates further away.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Accuracy {
+    pub phase_time: f64,   // Error of the time of a given phase in minutes
+    pub longitude: f64,    // Degrees
+    pub latitude: f64,     // Degrees
+    pub distance: f64,     // Earth radii
+}
+
+impl Algorithm {
+    pub fn accuracy(self, j_date: f64) -> Accuracy {
+        let accuracy = match self {
+            Algorithm::Simple => Accuracy {
+                phase_time: 1500.,
+                longitude: 1.5,
+                latitude: 0.9,
+                distance: 0.3,
+            },
+            Algorithm::ConwayApprox => Accuracy {
+                phase_time: 4500.,
+                longitude: 1.5,
+                latitude: 0.9,
+                distance: 0.3,
+            },
+            Algorithm::Meeus => Accuracy {
+                phase_time: 25.,
+                longitude: 0.2,
+                latitude: 0.2,
+                distance: 0.16,
+            },
+            // Limited by the position of the sun rather than the moon.
+            #[cfg(feature="high-precision")]
+            Algorithm::HighPrecision => Accuracy {
+                phase_time: 2.,
+                longitude: 0.003,
+                latitude: 0.001,
+                distance: 0.002,
+            },
+        };
+        // The mean motions drift away from reality outside of the range the
+        // models were fitted to, roughly linearly with time.
+        let centuries = ((j_date - J2000) / 36525.).abs();
+        let scale = 1. + (centuries - 1.).max(0.);
+        Accuracy {
+            phase_time: accuracy.phase_time * scale,
+            longitude: accuracy.longitude * scale,
+            latitude: accuracy.latitude * scale,
+            distance: accuracy.distance * scale,
+        }
+    }
+}
+
+impl MoonPhase {
+    // Error estimate for this `MoonPhase`, given the algorithm it was
+    // calculated with.
+    pub fn accuracy(&self, algorithm: Algorithm) -> Accuracy {
+        algorithm.accuracy(self.j_date)
+    }
+
+    pub fn with_algorithm(secs: i64, algorithm: Algorithm) -> Self {
+        Self::from_secs_float_with_algorithm(secs as f64, algorithm)
+    }
+
+    pub fn from_secs_float_with_algorithm(secs: f64, algorithm: Algorithm) -> Self {
+        let j_date = julian_date_from_seconds(secs);
+        match algorithm {
+            Algorithm::Simple => Self::_new(j_date),
+            Algorithm::ConwayApprox => conway(secs, j_date),
+            Algorithm::Meeus => Self::from_ephemeris(j_date, &Meeus),
+            #[cfg(feature="high-precision")]
+            Algorithm::HighPrecision => Self::from_ephemeris(j_date, &crate::elp::Elp),
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn conway(secs: f64, j_date: f64) -> MoonPhase {
+    let (year, month, day) = civil_from_days((secs / 86400.).floor() as i64);
+    let mut r = (year.rem_euclid(100) % 19) as f64;
+    if r > 9. {
+        r -= 19.;
+    }
+    r = (r * 11.) % 30. + month as f64 + day as f64;
+    if month < 3 {
+        r += 2.;
+    }
+    r -= if year < 2000 { 4. } else { 8.3 };
+    let age = (r + 0.5).floor().rem_euclid(30.);
+
+    let phase = age / MOON_SYNODIC_PERIOD;
+    let illumination = (1. - (TAU * phase).cos()) / 2.;
+    let elements = Elements::<f64>::new(j_date);
+    MoonPhase {
+        j_date,
+        phase,
+        age,
+        fraction: illumination,
+        illumination,
+        distance: elements.distance,
+        latitude: elements.latitude,
+        longitude: elements.longitude,
+        phase_name: phase_name(phase),
+        zodiac_name: Zodiac::from_long(elements.longitude),
+    }
+}
+
+// `Ephemeris` with only the largest terms of the lunar theory.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Meeus;
+
+impl Ephemeris for Meeus {
+    fn moon(&self, j_date: f64) -> MoonPosition {
+        let (longitude, latitude, distance) = meeus(ut_to_tt(j_date));
+        MoonPosition {
+            longitude,
+            latitude,
+            distance,
+        }
+    }
+}
+
+// Returns longitude and latitude in degrees and the distance in km, for a
+// Julian date in TT.
+fn meeus(j_date: f64) -> (f64, f64, f64) {
+    let t = (j_date - J2000) / 36525.;
+    let l = 218.3164477 + 481267.88123421 * t;
+    let d = (297.8501921 + 445267.1114034 * t).to_radians();
+    let m = (357.5291092 + 35999.0502909 * t).to_radians();
+    let m_moon = (134.9633964 + 477198.8675055 * t).to_radians();
+    let f = (93.2720950 + 483202.0175233 * t).to_radians();
+
+    let longitude = l
+        + 6.288774 * m_moon.sin()
+        + 1.274027 * (2. * d - m_moon).sin()
+        + 0.658314 * (2. * d).sin()
+        + 0.213618 * (2. * m_moon).sin()
+        - 0.185116 * m.sin()
+        - 0.114332 * (2. * f).sin()
+        + 0.058793 * (2. * d - 2. * m_moon).sin()
+        + 0.057066 * (2. * d - m - m_moon).sin()
+        + 0.053322 * (2. * d + m_moon).sin()
+        + 0.045758 * (2. * d - m).sin();
+    let latitude = 5.128122 * f.sin()
+        + 0.280602 * (m_moon + f).sin()
+        + 0.277693 * (m_moon - f).sin()
+        + 0.173237 * (2. * d - f).sin();
+    let distance = 385000.56
+        - 20905.355 * m_moon.cos()
+        - 3699.111 * (2. * d - m_moon).cos()
+        - 2955.968 * (2. * d).cos()
+        - 569.925 * (2. * m_moon).cos()
+        + 246.158 * (2. * d - 2. * m_moon).cos();
+    (longitude.rem_euclid(360.), latitude, distance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn simple_is_default() {
+        assert_eq!(
+            MoonPhase::with_algorithm(1642291200, Algorithm::default()),
+            MoonPhase::from_secs(1642291200)
+        );
+    }
+
+    #[test]
+    fn meeus_example() {
+        // Example 47.a: 1992 April 12, 0h TD
+        let (longitude, latitude, distance) = meeus(2448724.5);
+        assert!((longitude - 133.162655).abs() < 0.2, "{}", longitude);
+        assert!((latitude - -3.229126).abs() < 0.1, "{}", latitude);
+        assert!((distance - 368409.7).abs() < 300., "{}", distance);
+    }
+
+    #[test]
+    fn accuracy() {
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        let simple = moon_phase.accuracy(Algorithm::Simple);
+        let meeus = moon_phase.accuracy(Algorithm::Meeus);
+        assert!(meeus.phase_time < simple.phase_time);
+        assert!(meeus.longitude < simple.longitude);
+        // 1000 years before J2000.
+        let old = Algorithm::Meeus.accuracy(J2000 - 365250.);
+        assert!((old.longitude - 10. * meeus.longitude).abs() < 1e-9);
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        // Tolerance in days; Conway's rule also ignores the time of day.
+        let algorithms = [(Algorithm::Simple, 1.5), (Algorithm::ConwayApprox, 2.5)];
+        for secs in (900_000_000..1_700_000_000).step_by(86_400 * 5) {
+            let reference = MoonPhase::with_algorithm(secs, Algorithm::Meeus);
+            for (algorithm, tolerance) in &algorithms {
+                let moon_phase = MoonPhase::with_algorithm(secs, *algorithm);
+                let diff = (moon_phase.age - reference.age).rem_euclid(MOON_SYNODIC_PERIOD);
+                let diff = diff.min(MOON_SYNODIC_PERIOD - diff);
+                assert!(diff < *tolerance, "Failed for {} with {:?}", secs, algorithm);
+            }
+        }
+    }
+}
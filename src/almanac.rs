@@ -0,0 +1,397 @@
+// Ready made tables for calendars and almanacs. The values of a day are taken
+// at local noon, the phase events are the exact instants of the new, full and
+// quarter moons on that local day. Everything is computed with `Meeus`.
+// Eclipses are not covered. `month_table` and `year_table` render the results
+// as plain text or Markdown, `localized_month_table` in other languages.
+
+#[cfg(feature="chrono")]
+use chrono::{offset::TimeZone, Duration, NaiveDate};
+
+use crate::algorithm::Meeus;
+use crate::calendar::{days_from_civil, format_utc};
+use crate::ephemeris::Ephemeris;
+use crate::i18n::Language;
+use crate::events::{primary_phases, zero_crossings};
+use crate::naming::PrimaryPhase;
+use crate::{julian_date_from_seconds, MoonPhase, Phase, Zodiac};
+
+// Full and new moons closer than this count as supermoons, in km.
+const SUPERMOON_DISTANCE: f64 = 360000.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PhaseEvent {
+    pub phase: PrimaryPhase,
+    pub secs: f64,  // Unix seconds
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Day {
+    pub date: (i32, u32, u32),  // Local (year, month, day)
+    pub phase_name: Phase,
+    pub illumination: f64,      // 0 - 1
+    pub age: f64,               // Days
+    pub zodiac: Zodiac,
+    pub events: Vec<PhaseEvent>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum BlueMoonKind {
+    Monthly,   // Second full moon in a calendar month
+    Seasonal,  // Third of four full moons between an equinox and a solstice
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BlueMoon {
+    pub kind: BlueMoonKind,
+    pub secs: f64,  // Unix seconds of the full moon
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Supermoon {
+    pub phase: PrimaryPhase,  // New or Full
+    pub secs: f64,            // Unix seconds
+    pub distance: f64,        // km
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Layout {
+    // Columns aligned with spaces, for terminals and fixed width displays.
+    #[default]
+    Text,
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Year {
+    pub events: Vec<PhaseEvent>,
+    pub supermoons: Vec<Supermoon>,
+    pub blue_moons: Vec<BlueMoon>,
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    (days_from_civil(next_year as i64, next_month, 1) - days_from_civil(year as i64, month, 1)) as u32
+}
+
+// `day_start` gives the unix seconds of local midnight for a day since
+// 1970-01-01.
+fn month_with(year: i32, month: u32, day_start: impl Fn(i64) -> f64) -> Vec<Day> {
+    let first = days_from_civil(year as i64, month, 1);
+    let length = days_in_month(year, month) as i64;
+    let starts: Vec<f64> = (first..=first + length).map(day_start).collect();
+    let events = primary_phases(starts[0], starts[length as usize], &Meeus);
+    (0..length as usize)
+        .map(|i| {
+            let noon = starts[i] + (starts[i + 1] - starts[i]) / 2.;
+            let moon_phase = MoonPhase::from_secs_float_with_ephemeris(noon, &Meeus);
+            Day {
+                date: (year, month, i as u32 + 1),
+                phase_name: moon_phase.phase_name,
+                illumination: moon_phase.illumination,
+                age: moon_phase.age,
+                zodiac: moon_phase.zodiac_name,
+                events: events
+                    .iter()
+                    .filter(|(_, secs)| starts[i] <= *secs && *secs < starts[i + 1])
+                    .map(|(phase, secs)| PhaseEvent {
+                        phase: *phase,
+                        secs: *secs,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+// The new, full and quarter moons from `from` up to `until` (unix seconds),
+// in order.
+pub fn events(from: f64, until: f64) -> Vec<PhaseEvent> {
+    primary_phases(from, until, &Meeus)
+        .into_iter()
+        .map(|(phase, secs)| PhaseEvent { phase, secs })
+        .collect()
+}
+
+fn year_with(year: i32, day_start: impl Fn(i64) -> f64) -> Year {
+    let month_starts: Vec<f64> = (0..=12)
+        .map(|i| day_start(days_from_civil(year as i64 + i / 12, i as u32 % 12 + 1, 1)))
+        .collect();
+    let (start, end) = (month_starts[0], month_starts[12]);
+    let events = events(start, end);
+
+    let supermoons = events
+        .iter()
+        .filter(|event| event.phase == PrimaryPhase::New || event.phase == PrimaryPhase::Full)
+        .map(|event| Supermoon {
+            phase: event.phase,
+            secs: event.secs,
+            distance: Meeus.moon(julian_date_from_seconds(event.secs)).distance,
+        })
+        .filter(|supermoon| supermoon.distance < SUPERMOON_DISTANCE)
+        .collect();
+
+    let full_moons = |from: f64, until: f64| -> Vec<f64> {
+        primary_phases(from, until, &Meeus)
+            .into_iter()
+            .filter(|(phase, _)| *phase == PrimaryPhase::Full)
+            .map(|(_, secs)| secs)
+            .collect()
+    };
+    let mut blue_moons = Vec::new();
+    for month in month_starts.windows(2) {
+        if let [_, second, ..] = full_moons(month[0], month[1])[..] {
+            blue_moons.push(BlueMoon {
+                kind: BlueMoonKind::Monthly,
+                secs: second,
+            });
+        }
+    }
+    // Equinoxes and solstices from a season before the year on.
+    let sun = |secs| Meeus.sun_longitude(julian_date_from_seconds(secs));
+    let seasons = zero_crossings(|secs| 4. * sun(secs), start - 100. * 86400., end, 86400.);
+    for season in seasons.windows(2) {
+        if let [_, _, third, _] = full_moons(season[0], season[1])[..] {
+            if start <= third && third < end {
+                blue_moons.push(BlueMoon {
+                    kind: BlueMoonKind::Seasonal,
+                    secs: third,
+                });
+            }
+        }
+    }
+    blue_moons.sort_by(|a, b| a.secs.partial_cmp(&b.secs).unwrap());
+
+    Year {
+        events,
+        supermoons,
+        blue_moons,
+    }
+}
+
+// Unix seconds of local midnight for a day since 1970-01-01 in `tz`.
+// Midnights skipped by a daylight saving change are taken an hour later.
+#[cfg(feature="chrono")]
+fn local_midnight<Tz: TimeZone>(days: i64, tz: &Tz) -> f64 {
+    let local = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+        + Duration::days(days);
+    let time = tz
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .unwrap_or_else(|| tz.from_utc_datetime(&local));
+    time.timestamp() as f64
+}
+
+// All phase events, supermoons and blue moons of `year` in the time zone
+// `tz`, which decides the calendar months of the blue moons.
+#[cfg(feature="chrono")]
+pub fn year<Tz: TimeZone>(year: i32, tz: &Tz) -> Year {
+    year_with(year, |days| local_midnight(days, tz))
+}
+
+// All phase events, supermoons and blue moons of `year` in a time zone
+// `utc_offset` seconds ahead of UTC.
+#[cfg(not(feature="chrono"))]
+pub fn year(year: i32, utc_offset: i32) -> Year {
+    year_with(year, |days| (days * 86400 - utc_offset as i64) as f64)
+}
+
+// One entry for every day of `month` (1 - 12) in `year`, in the time zone
+// `tz`. Midnights skipped by a daylight saving change are taken an hour
+// later.
+#[cfg(feature="chrono")]
+pub fn month<Tz: TimeZone>(year: i32, month: u32, tz: &Tz) -> Vec<Day> {
+    month_with(year, month, |days| local_midnight(days, tz))
+}
+
+// One entry for every day of `month` (1 - 12) in `year`, in a time zone
+// `utc_offset` seconds ahead of UTC.
+#[cfg(not(feature="chrono"))]
+pub fn month(year: i32, month: u32, utc_offset: i32) -> Vec<Day> {
+    month_with(year, month, |days| (days * 86400 - utc_offset as i64) as f64)
+}
+
+fn table(header: &[&str], rows: &[Vec<String>], layout: Layout) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|title| title.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        match layout {
+            Layout::Text => padded.join("  ").trim_end().to_string(),
+            Layout::Markdown => format!("| {} |", padded.join(" | ")),
+        }
+    };
+    let header: Vec<String> = header.iter().map(|title| title.to_string()).collect();
+    let mut lines = vec![line(&header)];
+    let rules: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    lines.push(match layout {
+        Layout::Text => rules.join("  "),
+        Layout::Markdown => format!("|-{}-|", rules.join("-|-")),
+    });
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n") + "\n"
+}
+
+// The days of `month` as a table with one row per day.
+pub fn month_table(days: &[Day], layout: Layout) -> String {
+    localized_month_table(days, layout, Language::English)
+}
+
+// `month_table` with the headers and names in `language`.
+pub fn localized_month_table(days: &[Day], layout: Layout, language: Language) -> String {
+    let rows: Vec<Vec<String>> = days
+        .iter()
+        .map(|day| {
+            let events: Vec<&str> = day.events.iter().map(|event| language.primary_phase(event.phase)).collect();
+            let age = format!("{:.1}", day.age);
+            vec![
+                format!("{:04}-{:02}-{:02}", day.date.0, day.date.1, day.date.2),
+                format!("{} {}", day.phase_name.emoji(), language.phase(day.phase_name)),
+                format!("{:.0}%", day.illumination * 100.),
+                if language.decimal_comma() { age.replace('.', ",") } else { age },
+                language.zodiac(day.zodiac).to_string(),
+                events.join(", "),
+            ]
+        })
+        .collect();
+    let header = match language {
+        Language::English => ["Date", "Phase", "Illumination", "Age", "Zodiac", "Events"],
+        Language::German => ["Datum", "Phase", "Beleuchtung", "Alter", "Tierkreis", "Ereignisse"],
+        Language::French => ["Date", "Phase", "Illumination", "Âge", "Zodiaque", "Événements"],
+        Language::Spanish => ["Fecha", "Fase", "Iluminación", "Edad", "Zodiaco", "Eventos"],
+    };
+    table(&header, &rows, layout)
+}
+
+// The phase events of `year` as a table, with times in UTC and supermoons
+// and blue moons noted.
+pub fn year_table(year: &Year, layout: Layout) -> String {
+    let rows: Vec<Vec<String>> = year
+        .events
+        .iter()
+        .map(|event| {
+            let mut notes = Vec::new();
+            if year.supermoons.iter().any(|supermoon| supermoon.secs == event.secs) {
+                notes.push("Supermoon");
+            }
+            for blue_moon in year.blue_moons.iter().filter(|blue_moon| blue_moon.secs == event.secs) {
+                notes.push(match blue_moon.kind {
+                    BlueMoonKind::Monthly => "Blue moon",
+                    BlueMoonKind::Seasonal => "Seasonal blue moon",
+                });
+            }
+            let time = format_utc(event.secs);
+            vec![
+                time[..10].to_string(),
+                time[11..16].to_string(),
+                event.phase.to_string(),
+                notes.join(", "),
+            ]
+        })
+        .collect();
+    table(&["Date", "UTC", "Phase", "Notes"], &rows, layout)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(feature="chrono")]
+    fn january_2022() -> Vec<Day> {
+        month(2022, 1, &chrono::Utc)
+    }
+
+    #[cfg(not(feature="chrono"))]
+    fn january_2022() -> Vec<Day> {
+        month(2022, 1, 0)
+    }
+
+    #[cfg(feature="chrono")]
+    fn utc_year(number: i32) -> Year {
+        year(number, &chrono::Utc)
+    }
+
+    #[cfg(not(feature="chrono"))]
+    fn utc_year(number: i32) -> Year {
+        year(number, 0)
+    }
+
+    #[test]
+    fn years() {
+        // 2023 had a blue moon on 2023-08-31T01:36:00+00:00, also a supermoon.
+        let summary = utc_year(2023);
+        assert!((49..=51).contains(&summary.events.len()));
+        assert_eq!(summary.blue_moons.len(), 1);
+        let blue_moon = summary.blue_moons[0];
+        assert_eq!(blue_moon.kind, BlueMoonKind::Monthly);
+        assert!((blue_moon.secs - 1693445760.).abs() < 1800., "Failed for {:?}", blue_moon);
+        assert!(summary.supermoons.iter().any(|s| (s.secs - blue_moon.secs).abs() < 1.));
+        // Seasonal blue moon on 2024-08-19T18:26:00+00:00.
+        let blue_moons = utc_year(2024).blue_moons;
+        assert_eq!(blue_moons.len(), 1);
+        assert_eq!(blue_moons[0].kind, BlueMoonKind::Seasonal);
+        assert!((blue_moons[0].secs - 1724091960.).abs() < 1800., "Failed for {:?}", blue_moons[0]);
+    }
+
+    #[test]
+    fn days() {
+        let days = january_2022();
+        assert_eq!(days.len(), 31);
+        assert_eq!(days[30].date, (2022, 1, 31));
+        // New moon on 2022-01-02T18:33:00+00:00, full moon on the 17th.
+        assert_eq!(days[1].events.len(), 1);
+        assert_eq!(days[1].events[0].phase, PrimaryPhase::New);
+        assert_eq!(days[16].events[0].phase, PrimaryPhase::Full);
+        assert_eq!(days.iter().map(|day| day.events.len()).sum::<usize>(), 4);
+        assert!(days[16].illumination > 0.99);
+        assert!(days[2].age < 1.5);
+        let moon_phase = MoonPhase::from_secs_float_with_ephemeris(1641038400., &Meeus);
+        assert_eq!(days[0].zodiac, moon_phase.zodiac_name);
+        assert_eq!(days_in_month(2024, 2), 29);
+    }
+
+    #[test]
+    fn tables() {
+        let days = january_2022();
+        let text = month_table(&days, Layout::Text);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 33);
+        assert!(lines[0].starts_with("Date        Phase"), "Failed for {}", lines[0]);
+        assert!(lines[1].starts_with("----------  ---"));
+        assert!(lines[18].starts_with("2022-01-17  🌕 Full Moon"), "Failed for {}", lines[18]);
+        assert!(lines[18].ends_with("  Full Moon"));
+        let markdown = month_table(&days, Layout::Markdown);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert!(lines[0].starts_with("| Date       | Phase"), "Failed for {}", lines[0]);
+        assert!(lines[1].starts_with("|------------|---"));
+        assert!(lines.iter().all(|line| line.starts_with("| ") || line.starts_with("|-")));
+        assert!(lines.iter().all(|line| line.ends_with(" |") || line.ends_with("-|")));
+        let german = localized_month_table(&days, Layout::Text, Language::German);
+        let lines: Vec<&str> = german.lines().collect();
+        assert!(lines[0].starts_with("Datum       Phase"), "Failed for {}", lines[0]);
+        assert!(lines[18].starts_with("2022-01-17  🌕 Vollmond"), "Failed for {}", lines[18]);
+        assert!(lines[18].ends_with("  Vollmond"));
+
+        let markdown = year_table(&utc_year(2023), Layout::Markdown);
+        let blue_moon = markdown.lines().find(|line| line.contains("2023-08-31")).unwrap();
+        assert!(blue_moon.contains("Supermoon, Blue moon"), "Failed for {}", blue_moon);
+    }
+
+    #[test]
+    #[cfg(feature="chrono")]
+    fn time_zone() {
+        // The full moon at 23:48 UTC is on the 18th in Berlin.
+        let berlin = chrono::FixedOffset::east_opt(3600).unwrap();
+        let days = month(2022, 1, &berlin);
+        assert!(days[16].events.is_empty());
+        assert_eq!(days[17].events[0].phase, PrimaryPhase::Full);
+    }
+}
@@ -0,0 +1,165 @@
+// Aspects between the sun and the moon: moments at which the difference of
+// their ecliptic longitudes (the elongation) is one of the classic angles.
+
+use crate::ephemeris::Ephemeris;
+use crate::events::{wrap_180, zero_crossings};
+use crate::sun::SunPosition;
+use crate::{julian_date_from_seconds, MoonPhase};
+
+// The moon moves about 13 degrees a day relative to the sun, so at most 1.1
+// degrees per step.
+const STEP_SECS: f64 = 7200.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Aspect {
+    Conjunction,  // 0 degrees
+    Sextile,      // 60 degrees
+    Square,       // 90 degrees
+    Trine,        // 120 degrees
+    Opposition,   // 180 degrees
+}
+
+impl Aspect {
+    pub const ALL: [Aspect; 5] = [
+        Aspect::Conjunction,
+        Aspect::Sextile,
+        Aspect::Square,
+        Aspect::Trine,
+        Aspect::Opposition,
+    ];
+
+    // The angle between sun and moon, in degrees.
+    pub fn angle(self) -> f64 {
+        match self {
+            Aspect::Conjunction => 0.,
+            Aspect::Sextile => 60.,
+            Aspect::Square => 90.,
+            Aspect::Trine => 120.,
+            Aspect::Opposition => 180.,
+        }
+    }
+}
+
+// How far from the exact angle, in degrees, an aspect still counts.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Orbs {
+    pub conjunction: f64,
+    pub sextile: f64,
+    pub square: f64,
+    pub trine: f64,
+    pub opposition: f64,
+}
+
+impl Default for Orbs {
+    fn default() -> Self {
+        Orbs {
+            conjunction: 8.,
+            sextile: 6.,
+            square: 8.,
+            trine: 8.,
+            opposition: 8.,
+        }
+    }
+}
+
+impl Orbs {
+    pub fn get(&self, aspect: Aspect) -> f64 {
+        match aspect {
+            Aspect::Conjunction => self.conjunction,
+            Aspect::Sextile => self.sextile,
+            Aspect::Square => self.square,
+            Aspect::Trine => self.trine,
+            Aspect::Opposition => self.opposition,
+        }
+    }
+}
+
+// The instant an aspect is exact.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AspectEvent {
+    pub aspect: Aspect,
+    pub secs: f64,        // Unix seconds
+    pub elongation: f64,  // 0 - 360, e.g. 90 for the waxing and 270 for the waning square
+}
+
+impl MoonPhase {
+    // The aspect the moon currently forms with the sun within `orbs`, and how
+    // far from exact it is in degrees. Uses the `sun` module for the sun.
+    pub fn sun_aspect(&self, orbs: &Orbs) -> Option<(Aspect, f64)> {
+        let elongation = self.longitude - SunPosition::new(self.j_date).longitude;
+        Aspect::ALL.iter().find_map(|aspect| {
+            // Aspects count in both directions, e.g. 90 and 270 degrees.
+            let offset = wrap_180(elongation - aspect.angle())
+                .abs()
+                .min(wrap_180(elongation + aspect.angle()).abs());
+            if offset <= orbs.get(*aspect) {
+                Some((*aspect, offset))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+// All exact sun - moon aspects in from..until (unix seconds), in order.
+pub fn sun_aspects<E: Ephemeris + ?Sized>(from: f64, until: f64, ephemeris: &E) -> Vec<AspectEvent> {
+    let elongation = |secs| {
+        let j_date = julian_date_from_seconds(secs);
+        ephemeris.moon(j_date).longitude - ephemeris.sun_longitude(j_date)
+    };
+    let mut events = Vec::new();
+    for aspect in &Aspect::ALL {
+        let mut angles = vec![aspect.angle()];
+        if aspect.angle() % 180. != 0. {
+            angles.push(360. - aspect.angle());
+        }
+        for angle in angles {
+            for secs in zero_crossings(|secs| elongation(secs) - angle, from, until, STEP_SECS) {
+                events.push(AspectEvent {
+                    aspect: *aspect,
+                    secs,
+                    elongation: angle,
+                });
+            }
+        }
+    }
+    events.sort_by(|a, b| a.secs.partial_cmp(&b.secs).unwrap());
+    events
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    #[test]
+    fn sun_aspects_in_month() {
+        // January 2022, with the new moon on 2022-01-02T18:33:00+00:00 and
+        // the full moon on 2022-01-17T23:48:00+00:00.
+        let from = 1640995200.;
+        let events = sun_aspects(from, from + 31. * 86400., &Meeus);
+        // Eight aspects per lunation, plus the start of the next.
+        assert!((8..=10).contains(&events.len()), "{:?}", events);
+        let new = events.iter().find(|e| e.aspect == Aspect::Conjunction).unwrap();
+        assert!((new.secs - 1641148380.).abs() < 1800., "{:?}", new);
+        let full = events.iter().find(|e| e.aspect == Aspect::Opposition).unwrap();
+        assert!((full.secs - 1642463280.).abs() < 1800., "{:?}", full);
+        let squares: Vec<_> = events.iter().filter(|e| e.aspect == Aspect::Square).collect();
+        assert_eq!(squares[0].elongation, 90.);
+        assert_eq!(squares[1].elongation, 270.);
+        for pair in events.windows(2) {
+            assert!(pair[0].secs < pair[1].secs);
+        }
+    }
+
+    #[test]
+    fn sun_aspect() {
+        let orbs = Orbs::default();
+        // 2022-01-17T23:48:00+00:00, full moon.
+        let (aspect, offset) = MoonPhase::from_secs(1642463280).sun_aspect(&orbs).unwrap();
+        assert_eq!(aspect, Aspect::Opposition);
+        assert!(offset < 8.);
+        let tight = Orbs { opposition: 0., ..orbs };
+        assert_eq!(MoonPhase::from_secs(1642463280).sun_aspect(&tight), None);
+    }
+}
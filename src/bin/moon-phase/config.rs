@@ -0,0 +1,102 @@
+// Defaults for the command line options, read from a TOML file:
+//
+//     format = "plain"         # Like --format
+//     timezone = "+01:00"      # Like --tz, or a name such as "Europe/Berlin"
+//     hemisphere = "south"     # Which side of the moon is lit, from the latitude if left out
+//     locale = "de"            # Like --locale
+//
+//     [observer]               # Like --latitude and --longitude
+//     latitude = 52.52
+//     longitude = 13.40
+//     elevation = 34           # Meters
+//
+// The file is `--config`, `$MOON_PHASE_CONFIG`, or else
+// `moon-phase/config.toml` in `$XDG_CONFIG_HOME` or `~/.config`. A missing
+// file in the default place is the same as an empty one.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::output::Format;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub format: Option<Format>,
+    pub timezone: Option<String>,
+    pub hemisphere: Option<String>,
+    pub locale: Option<String>,
+    pub observer: Option<ObserverConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ObserverConfig {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub elevation: f64,
+}
+
+fn default_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("moon-phase").join("config.toml"))
+}
+
+pub fn parse(text: &str) -> Result<Config, String> {
+    toml::from_str(text).map_err(|error| error.to_string())
+}
+
+// `path` is the --config option.
+pub fn load(path: Option<PathBuf>) -> Result<Config, String> {
+    let (path, required) = match path.or_else(|| env::var_os("MOON_PHASE_CONFIG").map(PathBuf::from)) {
+        Some(path) => (path, true),
+        None => match default_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(&text).map_err(|error| format!("{}: {}", path.display(), error)),
+        Err(_) if !required && !path.exists() => Ok(Config::default()),
+        Err(error) => Err(format!("{}: {}", path.display(), error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config() {
+        let config = parse(
+            "format = \"json\"\n\
+             timezone = \"+01:00\"\n\
+             # Comment\n\
+             [observer]\n\
+             latitude = 52.52\n\
+             longitude = 13.4\n",
+        )
+        .unwrap();
+        assert_eq!(config.format, Some(Format::Json));
+        assert_eq!(config.timezone.as_deref(), Some("+01:00"));
+        assert_eq!(config.hemisphere, None);
+        assert_eq!(
+            config.observer,
+            Some(ObserverConfig {
+                latitude: 52.52,
+                longitude: 13.4,
+                elevation: 0.,
+            })
+        );
+        assert_eq!(parse(""), Ok(Config::default()));
+        assert!(parse("colour = \"red\"").is_err());
+        assert!(parse("format = \"xml\"").is_err());
+    }
+}
@@ -0,0 +1,502 @@
+// The moon-phase command. Times are read as RFC 3339, a plain date, unix
+// seconds or in words, see `query`. Times without an offset, calendar days
+// and the plain output are in the time zone of `--tz`, the config file or
+// `$TZ`, UTC if none is set. The other formats always give UTC. The
+// language of the plain output comes from `--locale`, the config file or
+// `$LC_ALL`, `$LC_MESSAGES` and `$LANG`. An English locale in the
+// environment keeps the RFC 3339 times, only `--locale en` spells them out.
+//
+// Exits with 1 when `is` finds the moon isn't in the state asked about, and
+// with 2 on errors.
+
+mod config;
+#[cfg(feature="mqtt")]
+mod mqtt;
+mod output;
+mod predicate;
+mod query;
+#[cfg(feature="tui")]
+mod tui;
+mod watch;
+mod zone;
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use moon_phase::almanac::{self, Layout};
+use moon_phase::i18n::Language;
+use moon_phase::{Hemisphere, Observer, PrimaryPhase};
+
+use crate::output::{render, Format, Output};
+use crate::predicate::State;
+use crate::query::{find_phase, parse_duration, parse_moment, parse_query, Query};
+use crate::zone::{parse_tz_variable, parse_zone, Zone};
+
+#[derive(Parser)]
+#[command(name = "moon-phase", version, about = "Phase and position of the moon")]
+struct Cli {
+    #[arg(long, global = true, value_enum, help = "How to print the results [default: plain]")]
+    format: Option<Format>,
+    #[arg(long, global = true, help = "Config file instead of ~/.config/moon-phase/config.toml")]
+    config: Option<PathBuf>,
+    #[arg(long, global = true, requires = "longitude", allow_hyphen_values = true, help = "Latitude of the observer in degrees, north positive")]
+    latitude: Option<f64>,
+    #[arg(long, global = true, requires = "latitude", allow_hyphen_values = true, help = "Longitude of the observer in degrees, east positive")]
+    longitude: Option<f64>,
+    #[arg(long, global = true, value_enum, help = "Which side of the moon is lit, from the latitude if left out")]
+    hemisphere: Option<HemisphereArg>,
+    #[arg(
+        long,
+        global = true,
+        help = "Time zone for times and calendar days, an offset like +01:00 or a name like Europe/Berlin [default: $TZ or UTC]"
+    )]
+    tz: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "Language of the plain output, en, de, fr, es or C for RFC 3339 times [default: $LC_ALL, $LC_MESSAGES or $LANG unless English]"
+    )]
+    locale: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "The moon right now")]
+    Now,
+    #[command(about = "The moon at a given time")]
+    At {
+        #[arg(help = "RFC 3339, YYYY-MM-DD[THH:MM[:SS]] in the time zone, unix seconds or e.g. \"tomorrow night\"")]
+        time: String,
+    },
+    #[command(about = "When the next new, full or quarter moon is")]
+    Next {
+        phase: PhaseArg,
+        #[arg(long, help = "Search from this time instead of now")]
+        after: Option<String>,
+    },
+    #[command(about = "Every day of a month")]
+    Calendar {
+        #[arg(help = "YYYY-MM, the current month if left out")]
+        month: Option<String>,
+        #[arg(long, help = "Markdown table instead of plain text")]
+        markdown: bool,
+    },
+    #[command(about = "New, full and quarter moons in a range of time")]
+    Events {
+        from: String,
+        until: String,
+    },
+    #[command(about = "Answers e.g. \"next full moon after 2025-12-24\" or \"tomorrow night\"")]
+    Query {
+        #[arg(required = true, trailing_var_arg = true)]
+        words: Vec<String>,
+    },
+    #[command(about = "Exits with 0 if the moon is in a state and 1 if not, e.g. moon-phase is full --within 12h")]
+    Is {
+        state: State,
+        #[arg(long, help = "New, full or quarter moon at most this long before or after, e.g. 12h or 2d")]
+        within: Option<String>,
+        #[arg(long, help = "Check at this time instead of now")]
+        at: Option<String>,
+    },
+    #[command(about = "Keeps running and prints the moon whenever the phase changes, and every new, full or quarter moon")]
+    Watch {
+        #[arg(long, default_value_t = 60., help = "Seconds between checks")]
+        interval: f64,
+        #[arg(
+            long,
+            help = "Shell command to run on every change, with MOON_PHASE_KIND (phase or event), MOON_PHASE_NAME, MOON_PHASE_EMOJI and MOON_PHASE_TIME set"
+        )]
+        exec: Option<String>,
+        #[cfg(feature="mqtt")]
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Also publish to an MQTT broker, mqtt://[user:password@]host[:port][/topic]"
+        )]
+        mqtt: Option<String>,
+    },
+    #[cfg(feature="tui")]
+    #[command(about = "Interactive viewer, move through time with the arrow keys")]
+    Tui {
+        #[arg(help = "Time to start at, now if left out")]
+        time: Option<String>,
+    },
+    #[command(about = "Prints a completion script, e.g. moon-phase completions bash > /etc/bash_completion.d/moon-phase")]
+    Completions {
+        shell: Shell,
+    },
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum PhaseArg {
+    New,
+    FirstQuarter,
+    Full,
+    LastQuarter,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum HemisphereArg {
+    North,
+    South,
+}
+
+// Options and config file taken together.
+struct Settings {
+    format: Format,
+    zone: Zone,
+    observer: Option<Observer>,
+    hemisphere: Hemisphere,
+    language: Option<Language>,  // None for English with RFC 3339 times
+}
+
+impl Settings {
+    fn new(cli: &Cli, config: config::Config) -> Result<Self, String> {
+        let observer = match (cli.latitude, cli.longitude, config.observer) {
+            (Some(latitude), Some(longitude), _) => Some(Observer::new(latitude, longitude)),
+            (_, _, Some(observer)) => {
+                Some(Observer::new(observer.latitude, observer.longitude).with_elevation(observer.elevation))
+            }
+            _ => None,
+        };
+        let hemisphere = match (cli.hemisphere, config.hemisphere.as_deref()) {
+            (Some(HemisphereArg::North), _) => Hemisphere::North,
+            (Some(HemisphereArg::South), _) => Hemisphere::South,
+            (None, Some(hemisphere)) => match hemisphere.to_lowercase().as_str() {
+                "north" => Hemisphere::North,
+                "south" => Hemisphere::South,
+                _ => return Err(format!("unknown hemisphere '{}', expected north or south", hemisphere)),
+            },
+            (None, None) => observer.map_or(Hemisphere::North, |observer| observer.hemisphere()),
+        };
+        let language = match cli.locale.as_deref().or(config.locale.as_deref()) {
+            Some(locale) => parse_locale(locale)?,
+            None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|name| env::var(name).ok())
+                .find(|locale| !locale.is_empty())
+                .and_then(|locale| environment_language(&locale)),
+        };
+        Ok(Settings {
+            format: cli.format.or(config.format).unwrap_or_default(),
+            zone: match cli.tz.as_deref().or(config.timezone.as_deref()) {
+                Some(zone) => parse_zone(zone)?,
+                None => env::var("TZ").ok().and_then(|zone| parse_tz_variable(&zone)).unwrap_or_default(),
+            },
+            observer,
+            hemisphere,
+            language,
+        })
+    }
+}
+
+// A language tag such as "de" or "fr_FR.UTF-8", or "C" for none.
+fn parse_locale(text: &str) -> Result<Option<Language>, String> {
+    if text == "C" || text == "POSIX" {
+        return Ok(None);
+    }
+    Language::from_tag(text)
+        .map(Some)
+        .ok_or_else(|| format!("unknown locale '{}', expected en, de, fr, es or C", text))
+}
+
+// The language of a locale from the environment, None for English, which
+// is the default rather than a choice.
+fn environment_language(locale: &str) -> Option<Language> {
+    Language::from_tag(locale).filter(|language| *language != Language::English)
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).unwrap()
+}
+
+// "UTC", "Z", "+01:00", "-0330" or "+5".
+fn parse_offset(text: &str) -> Result<FixedOffset, String> {
+    let error = || format!("can't read the time zone '{}', expected UTC or an offset like +01:00", text);
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("utc") || text == "Z" {
+        return Ok(utc());
+    }
+    let sign = match text.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(error()),
+    };
+    let digits: String = text[1..].chars().filter(|c| *c != ':').collect();
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.as_str(), "0"),
+        4 => digits.split_at(2),
+        _ => return Err(error()),
+    };
+    let hours: i32 = hours.parse().map_err(|_| error())?;
+    let minutes: i32 = minutes.parse().map_err(|_| error())?;
+    if minutes >= 60 {
+        return Err(error());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(error)
+}
+
+impl From<PhaseArg> for PrimaryPhase {
+    fn from(phase: PhaseArg) -> Self {
+        match phase {
+            PhaseArg::New => PrimaryPhase::New,
+            PhaseArg::FirstQuarter => PrimaryPhase::FirstQuarter,
+            PhaseArg::Full => PrimaryPhase::Full,
+            PhaseArg::LastQuarter => PrimaryPhase::LastQuarter,
+        }
+    }
+}
+
+// Unix seconds of an absolute time given on the command line, in `zone`
+// unless it has an offset.
+fn parse_time(text: &str, zone: &Zone) -> Result<f64, String> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.timestamp_micros() as f64 / 1_000_000.);
+    }
+    for format in &["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(text, format) {
+            return Ok(local_secs(&time, zone));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(local_secs(&date.and_hms_opt(0, 0, 0).unwrap(), zone));
+    }
+    text.trim_start_matches('@')
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite())
+        .ok_or_else(|| format!("can't read the time '{}'", text))
+}
+
+// Local times skipped by a daylight saving change are taken an hour later,
+// repeated ones the first time.
+fn local_secs(time: &NaiveDateTime, zone: &Zone) -> f64 {
+    zone.from_local_datetime(time)
+        .earliest()
+        .or_else(|| zone.from_local_datetime(&(*time + Duration::hours(1))).earliest())
+        .map_or_else(|| Utc.from_utc_datetime(time).timestamp(), |time| time.timestamp()) as f64
+}
+
+// RFC 3339 in `zone`, with Z for UTC.
+fn format_local(secs: f64, zone: &Zone) -> String {
+    if *zone == Zone::Fixed(utc()) {
+        return format_time(secs);
+    }
+    zone.timestamp_opt(secs.round() as i64, 0)
+        .single()
+        .map_or_else(|| secs.to_string(), |time| time.format("%Y-%m-%dT%H:%M:%S%:z").to_string())
+}
+
+// The date written out in `language`, then the time and the offset, with
+// UTC for UTC.
+fn format_long(secs: f64, zone: &Zone, language: Language) -> String {
+    let time = match zone.timestamp_opt(secs.round() as i64, 0).single() {
+        Some(time) => time,
+        None => return secs.to_string(),
+    };
+    let date = language.date(time.year(), time.month(), time.day(), time.weekday().num_days_from_monday() as usize);
+    let offset = if *zone == Zone::Fixed(utc()) { "UTC".to_string() } else { time.format("%:z").to_string() };
+    format!("{}, {} {}", date, time.format("%H:%M"), offset)
+}
+
+// RFC 3339 in UTC.
+fn format_time(secs: f64) -> String {
+    Utc.timestamp_opt(secs.round() as i64, 0)
+        .single()
+        .map_or_else(|| secs.to_string(), |time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+fn now() -> f64 {
+    Utc::now().timestamp_micros() as f64 / 1_000_000.
+}
+
+// Month and year of "YYYY-MM".
+fn parse_month(text: &str) -> Result<(i32, u32), String> {
+    NaiveDate::parse_from_str(&format!("{}-01", text.trim()), "%Y-%m-%d")
+        .map(|date| (date.year(), date.month()))
+        .map_err(|_| format!("can't read the month '{}', expected YYYY-MM", text))
+}
+
+// False when `is` finds the moon isn't in the state asked about.
+fn run(cli: Cli) -> Result<bool, String> {
+    if let Command::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "moon-phase", &mut io::stdout());
+        return Ok(true);
+    }
+    let settings = Settings::new(&cli, config::load(cli.config.clone())?)?;
+    let zone = &settings.zone;
+    let output = match cli.command {
+        Command::Now => Output::Moon(now()),
+        Command::At { time } => Output::Moon(parse_moment(&time, now(), zone)?),
+        Command::Next { phase, after } => {
+            let from = after.map_or_else(|| Ok(now()), |after| parse_moment(&after, now(), zone))?;
+            Output::Event(find_phase(phase.into(), true, from).ok_or("no such phase in the next 40 days")?)
+        }
+        Command::Calendar { month, markdown } => {
+            let (year, month) = match month {
+                Some(month) => parse_month(&month)?,
+                None => {
+                    let today = Utc::now().with_timezone(zone);
+                    (today.year(), today.month())
+                }
+            };
+            Output::Calendar {
+                days: almanac::month(year, month, zone),
+                layout: if markdown { Layout::Markdown } else { Layout::Text },
+            }
+        }
+        Command::Events { from, until } => {
+            Output::Events(almanac::events(parse_moment(&from, now(), zone)?, parse_moment(&until, now(), zone)?))
+        }
+        Command::Query { words } => match parse_query(&words.join(" "), now(), zone)? {
+            Query::Moment(secs) => Output::Moon(secs),
+            Query::Phase { phase, forward, from } => {
+                Output::Event(find_phase(phase, forward, from).ok_or("no such phase within 40 days")?)
+            }
+        },
+        Command::Is { state, within, at } => {
+            let secs = at.map_or_else(|| Ok(now()), |at| parse_moment(&at, now(), zone))?;
+            let within = within.map(|within| parse_duration(&within)).transpose()?;
+            return predicate::check(state, secs, within, settings.observer);
+        }
+        Command::Watch {
+            interval,
+            exec,
+            #[cfg(feature="mqtt")]
+            mqtt,
+        } => {
+            if interval.is_nan() || interval < 1. {
+                return Err("the interval has to be at least a second".to_string());
+            }
+            #[cfg(feature="mqtt")]
+            {
+                let broker = mqtt.map(|url| mqtt::parse_url(&url)).transpose()?;
+                let publisher = broker.as_ref().map(mqtt::Publisher::connect);
+                return watch::watch(&settings, interval, exec.as_deref(), publisher.as_ref()).map(|_| true);
+            }
+            #[cfg(not(feature="mqtt"))]
+            return watch::watch(&settings, interval, exec.as_deref()).map(|_| true);
+        }
+        #[cfg(feature="tui")]
+        Command::Tui { time } => {
+            let secs = time.map_or_else(|| Ok(now()), |time| parse_moment(&time, now(), zone))?;
+            return tui::tui(&settings, secs).map(|_| true);
+        }
+        Command::Completions { .. } => unreachable!(),
+    };
+    print!("{}", render(&output, &settings));
+    Ok(true)
+}
+
+fn main() {
+    match run(Cli::parse()) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("moon-phase: {}", message);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn times() {
+        let utc = Zone::default();
+        assert_eq!(parse_time("2022-01-17T23:48:00Z", &utc), Ok(1642463280.));
+        assert_eq!(parse_time("2022-01-18T00:48:00+01:00", &utc), Ok(1642463280.));
+        assert_eq!(parse_time("2022-01-17T23:48", &utc), Ok(1642463280.));
+        assert_eq!(parse_time("2022-01-17", &utc), Ok(1642377600.));
+        assert_eq!(parse_time("@1642463280", &utc), Ok(1642463280.));
+        assert!(parse_time("someday", &utc).is_err());
+        assert_eq!(format_time(1642463280.), "2022-01-17T23:48:00Z");
+        let berlin = parse_zone("+01:00").unwrap();
+        assert_eq!(parse_time("2022-01-18T00:48", &berlin), Ok(1642463280.));
+        assert_eq!(parse_time("2022-01-17T23:48:00Z", &berlin), Ok(1642463280.));
+        assert_eq!(format_local(1642463280., &berlin), "2022-01-18T00:48:00+01:00");
+        assert_eq!(format_local(1642463280., &utc), "2022-01-17T23:48:00Z");
+        // Summer time in Berlin, and 02:30 skipped when it starts.
+        let berlin = parse_zone("Europe/Berlin").unwrap();
+        assert_eq!(parse_time("2022-07-01", &berlin), Ok(1656626400.));
+        assert_eq!(format_local(1656633600., &berlin), "2022-07-01T02:00:00+02:00");
+        assert_eq!(format_local(1642463280., &berlin), "2022-01-18T00:48:00+01:00");
+        assert_eq!(parse_time("2022-03-27T02:30", &berlin), parse_time("2022-03-27T03:30", &berlin));
+        assert_eq!(format_long(1642463280., &berlin, Language::German), "Dienstag, 18. Januar 2022, 00:48 +01:00");
+        assert_eq!(format_long(1642463280., &utc, Language::English), "Monday, January 17, 2022, 23:48 UTC");
+        assert_eq!(parse_month("2024-02"), Ok((2024, 2)));
+        assert!(parse_month("2024-13").is_err());
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("UTC"), Ok(utc()));
+        assert_eq!(parse_offset("-03:30").unwrap().local_minus_utc(), -12600);
+        assert_eq!(parse_offset("+0530").unwrap().local_minus_utc(), 19800);
+        assert_eq!(parse_offset("+5").unwrap().local_minus_utc(), 18000);
+        assert!(parse_offset("Europe/Berlin").is_err());
+        assert!(parse_offset("+01:75").is_err());
+    }
+
+    #[test]
+    fn settings() {
+        let cli = Cli::parse_from(["moon-phase", "now", "--latitude", "-33.9", "--longitude", "151.2"]);
+        let config = config::parse("format = \"json\"\ntimezone = \"+10:00\"").unwrap();
+        let settings = Settings::new(&cli, config).unwrap();
+        assert_eq!(settings.format, Format::Json);
+        assert_eq!(settings.zone, parse_zone("+10:00").unwrap());
+        assert_eq!(settings.hemisphere, Hemisphere::South);
+        let cli = Cli::parse_from(["moon-phase", "--format", "csv", "--hemisphere", "north", "now"]);
+        let config = config::parse("format = \"json\"\n[observer]\nlatitude = -33.9\nlongitude = 151.2").unwrap();
+        let settings = Settings::new(&cli, config).unwrap();
+        assert_eq!(settings.format, Format::Csv);
+        assert_eq!(settings.hemisphere, Hemisphere::North);
+        assert_eq!(settings.observer, Some(Observer::new(-33.9, 151.2)));
+        let cli = Cli::parse_from(["moon-phase", "calendar", "--tz", "Australia/Sydney"]);
+        let config = config::parse("timezone = \"+10:00\"").unwrap();
+        assert_eq!(Settings::new(&cli, config).unwrap().zone, parse_zone("Australia/Sydney").unwrap());
+        let cli = Cli::parse_from(["moon-phase", "now", "--locale", "fr_FR.UTF-8"]);
+        let config = config::parse("locale = \"de\"").unwrap();
+        assert_eq!(Settings::new(&cli, config).unwrap().language, Some(Language::French));
+        let cli = Cli::parse_from(["moon-phase", "now", "--locale", "C"]);
+        assert_eq!(Settings::new(&cli, config::Config::default()).unwrap().language, None);
+        let cli = Cli::parse_from(["moon-phase", "now", "--locale", "en"]);
+        assert_eq!(Settings::new(&cli, config::Config::default()).unwrap().language, Some(Language::English));
+        let cli = Cli::parse_from(["moon-phase", "now", "--locale", "tlh"]);
+        assert!(Settings::new(&cli, config::Config::default()).is_err());
+        assert_eq!(environment_language("en_US.UTF-8"), None);
+        assert_eq!(environment_language("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(environment_language("C.UTF-8"), None);
+        let cli = Cli::parse_from(["moon-phase", "now", "--tz", "Mars/Olympus_Mons"]);
+        assert!(Settings::new(&cli, config::Config::default()).is_err());
+    }
+
+    #[test]
+    fn arguments() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn completions() {
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut Cli::command(), "moon-phase", &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("calendar"));
+        assert!(script.contains("first-quarter"), "Failed for {}", script);
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Zsh, &mut Cli::command(), "moon-phase", &mut script);
+        assert!(String::from_utf8(script).unwrap().contains("last-quarter"));
+    }
+}
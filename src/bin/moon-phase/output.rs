@@ -0,0 +1,350 @@
+// What the subcommands print, in the format picked with `--format`. The
+// fields of the moon are named as in `MoonPhase::to_json`, the other formats
+// use the same names. With an observer, the moon also gets its altitude.
+// With a language set, the plain and emoji output are in that language, with
+// dates written out; the other formats don't change.
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use moon_phase::almanac::{self, Day, Layout, PhaseEvent};
+use moon_phase::ephemeris::EARTH_RADIUS;
+use moon_phase::i18n::Language;
+use moon_phase::observer::Limb;
+use moon_phase::{json_string, phase_key, zodiac_key, MoonPhase, Phase, JSON_SCHEMA_VERSION};
+
+use crate::{format_local, format_long, format_time, Settings};
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Plain,
+    Json,
+    Yaml,
+    Csv,
+    Emoji,
+}
+
+pub enum Output {
+    Moon(f64),  // Unix seconds
+    Event(PhaseEvent),
+    Events(Vec<PhaseEvent>),
+    Calendar { days: Vec<Day>, layout: Layout },
+}
+
+enum Value {
+    Text(String),
+    Number(f64),
+    List(Vec<String>),
+}
+
+type Record = Vec<(&'static str, Value)>;
+
+fn moon_record(secs: f64, settings: &Settings) -> Record {
+    let moon_phase = MoonPhase::from_secs_float(secs);
+    let mut record = vec![
+        ("schema_version", Value::Number(JSON_SCHEMA_VERSION as f64)),
+        ("timestamp", Value::Text(format_time(secs))),
+        ("julian_date", Value::Number(moon_phase.j_date)),
+        ("phase", Value::Number(moon_phase.phase)),
+        ("age_days", Value::Number(moon_phase.age)),
+        ("illumination", Value::Number(moon_phase.illumination)),
+        ("illumination_percent", Value::Number(moon_phase.illumination * 100.)),
+        ("phase_name", Value::Text(phase_key(moon_phase.phase_name).to_string())),
+        ("emoji", Value::Text(moon_phase.phase_name.emoji().to_string())),
+        ("distance_km", Value::Number(moon_phase.distance * EARTH_RADIUS)),
+        ("distance_earth_radii", Value::Number(moon_phase.distance)),
+        ("latitude", Value::Number(moon_phase.latitude)),
+        ("longitude", Value::Number(moon_phase.longitude)),
+        ("zodiac", Value::Text(zodiac_key(moon_phase.zodiac_name).to_string())),
+    ];
+    if let Some(observer) = settings.observer {
+        record.push(("altitude", Value::Number(observer.moon_altitude(&moon_phase))));
+    }
+    record
+}
+
+fn event_record(event: &PhaseEvent) -> Record {
+    let phase = Phase::from(event.phase);
+    vec![
+        ("timestamp", Value::Text(format_time(event.secs))),
+        ("phase_name", Value::Text(phase_key(phase).to_string())),
+        ("emoji", Value::Text(phase.emoji().to_string())),
+    ]
+}
+
+fn day_record(day: &Day) -> Record {
+    vec![
+        ("date", Value::Text(format!("{:04}-{:02}-{:02}", day.date.0, day.date.1, day.date.2))),
+        ("phase_name", Value::Text(phase_key(day.phase_name).to_string())),
+        ("emoji", Value::Text(day.phase_name.emoji().to_string())),
+        ("illumination", Value::Number(day.illumination)),
+        ("age_days", Value::Number(day.age)),
+        ("zodiac", Value::Text(zodiac_key(day.zodiac).to_string())),
+        (
+            "events",
+            Value::List(day.events.iter().map(|event| phase_key(event.phase.into()).to_string()).collect()),
+        ),
+    ]
+}
+
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+// JSON, which YAML reads as well.
+fn json_value(value: &Value, separator: &str) -> String {
+    match value {
+        Value::Text(text) => json_string(text),
+        Value::Number(value) => number(*value),
+        Value::List(items) => format!("[{}]", items.iter().map(|item| json_string(item)).collect::<Vec<_>>().join(separator)),
+    }
+}
+
+fn json(record: &Record) -> String {
+    let fields: Vec<String> = record
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_value(value, ",")))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn yaml(record: &Record, indent: &str) -> String {
+    record
+        .iter()
+        .map(|(key, value)| format!("{}{}: {}\n", indent, key, json_value(value, ", ")))
+        .collect()
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn csv(records: &[Record]) -> String {
+    let mut csv = String::new();
+    if let Some(first) = records.first() {
+        csv += &first.iter().map(|(key, _)| *key).collect::<Vec<_>>().join(",");
+        csv.push('\n');
+    }
+    for record in records {
+        let fields: Vec<String> = record
+            .iter()
+            .map(|(_, value)| match value {
+                Value::Text(text) => csv_field(text),
+                Value::Number(value) => if value.is_finite() { value.to_string() } else { String::new() },
+                Value::List(items) => csv_field(&items.join(";")),
+            })
+            .collect();
+        csv += &fields.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+// A single record is written as an object, several as a list.
+fn structured(records: &[Record], single: bool, format: Format) -> String {
+    match format {
+        Format::Json if single => json(&records[0]) + "\n",
+        Format::Json => {
+            let objects: Vec<String> = records.iter().map(json).collect();
+            format!("[{}]\n", objects.join(",\n"))
+        }
+        Format::Yaml if single => yaml(&records[0], ""),
+        Format::Yaml if records.is_empty() => "[]\n".to_string(),
+        Format::Yaml => records.iter().map(|record| "-".to_string() + &yaml(record, "  ")[1..]).collect(),
+        _ => csv(records),
+    }
+}
+
+// Time, illumination, age, days, distance, zodiac, lit side, left, right
+// and altitude.
+fn labels(language: Language) -> [&'static str; 10] {
+    match language {
+        Language::English => ["Time", "Illumination", "Age", "days", "Distance", "Zodiac", "Lit side", "left", "right", "Altitude"],
+        Language::German => ["Zeit", "Beleuchtung", "Alter", "Tage", "Entfernung", "Tierkreis", "Helle Seite", "links", "rechts", "Höhe"],
+        Language::French => ["Heure", "Illumination", "Âge", "jours", "Distance", "Zodiaque", "Côté éclairé", "gauche", "droite", "Hauteur"],
+        Language::Spanish => ["Hora", "Iluminación", "Edad", "días", "Distancia", "Zodiaco", "Lado iluminado", "izquierda", "derecha", "Altura"],
+    }
+}
+
+// RFC 3339 without a language, written out with one.
+fn plain_time(secs: f64, settings: &Settings) -> String {
+    match settings.language {
+        Some(language) => format_long(secs, &settings.zone, language),
+        None => format_local(secs, &settings.zone),
+    }
+}
+
+fn plain_moon(secs: f64, settings: &Settings) -> String {
+    let moon_phase = MoonPhase::from_secs_float(secs);
+    let language = settings.language.unwrap_or_default();
+    let labels = labels(language);
+    let [time, illumination, age, days, distance, zodiac, lit_side, left, right, altitude] = labels;
+    // Values lined up two spaces after the longest label.
+    let width = labels.iter().map(|label| label.chars().count() + 3).max().unwrap_or(0);
+    let number = |value: f64, decimals: usize| {
+        let number = format!("{:.*}", decimals, value);
+        if language.decimal_comma() { number.replace('.', ",") } else { number }
+    };
+    let line = |label: &str, value: String| format!("{:<width$}{}\n", label.to_string() + ":", value, width = width);
+    let mut plain = format!("{} {}\n", moon_phase.phase_name.emoji(), language.phase(moon_phase.phase_name));
+    plain += &line(time, plain_time(secs, settings));
+    plain += &line(illumination, number(moon_phase.illumination * 100., 1) + "%");
+    plain += &line(age, format!("{} {}", number(moon_phase.age, 1), days));
+    plain += &line(distance, number(moon_phase.distance * EARTH_RADIUS, 0) + " km");
+    plain += &line(zodiac, language.zodiac(moon_phase.zodiac_name).to_string());
+    let lit = match moon_phase.lit_limb(settings.hemisphere) {
+        Limb::Left => left,
+        Limb::Right => right,
+    };
+    plain += &line(lit_side, lit.to_string());
+    if let Some(observer) = settings.observer {
+        plain += &line(altitude, number(observer.moon_altitude(&moon_phase), 1) + "°");
+    }
+    plain
+}
+
+fn plain_event(event: &PhaseEvent, settings: &Settings) -> String {
+    let language = settings.language.unwrap_or_default();
+    let emoji = Phase::from(event.phase).emoji();
+    format!("{}  {} {}\n", plain_time(event.secs, settings), emoji, language.primary_phase(event.phase))
+}
+
+fn emoji_event(event: &PhaseEvent, settings: &Settings) -> String {
+    format!("{} {}\n", Phase::from(event.phase).emoji(), plain_time(event.secs, settings))
+}
+
+pub fn render(output: &Output, settings: &Settings) -> String {
+    render_as(output, settings, settings.format)
+}
+
+// `render` in another format than that of the settings.
+pub fn render_as(output: &Output, settings: &Settings, format: Format) -> String {
+    match (output, format) {
+        (Output::Moon(secs), Format::Plain) => plain_moon(*secs, settings),
+        (Output::Moon(secs), Format::Emoji) => format!("{}\n", MoonPhase::from_secs_float(*secs).phase_name.emoji()),
+        (Output::Moon(secs), _) => structured(&[moon_record(*secs, settings)], true, format),
+        (Output::Event(event), Format::Plain) => plain_event(event, settings),
+        (Output::Event(event), Format::Emoji) => emoji_event(event, settings),
+        (Output::Event(event), _) => structured(&[event_record(event)], true, format),
+        (Output::Events(events), Format::Plain) => events.iter().map(|event| plain_event(event, settings)).collect(),
+        (Output::Events(events), Format::Emoji) => events.iter().map(|event| emoji_event(event, settings)).collect(),
+        (Output::Events(events), _) => structured(&events.iter().map(event_record).collect::<Vec<_>>(), false, format),
+        (Output::Calendar { days, layout }, Format::Plain) => {
+            almanac::localized_month_table(days, *layout, settings.language.unwrap_or_default())
+        }
+        // A week to a line.
+        (Output::Calendar { days, .. }, Format::Emoji) => days
+            .chunks(7)
+            .map(|week| week.iter().map(|day| day.phase_name.emoji().to_string()).collect::<String>() + "\n")
+            .collect(),
+        (Output::Calendar { days, .. }, _) => structured(&days.iter().map(day_record).collect::<Vec<_>>(), false, format),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_offset;
+    use crate::zone::Zone;
+    use moon_phase::{Hemisphere, Observer, PrimaryPhase};
+
+    fn settings(format: Format) -> Settings {
+        Settings {
+            format,
+            zone: Zone::default(),
+            observer: None,
+            hemisphere: Hemisphere::North,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let secs = 1642464000.;
+        assert_eq!(render(&Output::Moon(secs), &settings(Format::Json)), MoonPhase::from_secs(1642464000).to_json() + "\n");
+        let yaml = render(&Output::Moon(secs), &settings(Format::Yaml));
+        assert!(yaml.starts_with("schema_version: 1\ntimestamp: \"2022-01-18T00:00:00Z\"\n"), "Failed for {}", yaml);
+        assert_eq!(render(&Output::Moon(secs), &settings(Format::Emoji)), "🌕\n");
+        let csv = render(&Output::Moon(secs), &settings(Format::Csv));
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.starts_with("schema_version,timestamp,"));
+
+        let events = Output::Events(vec![
+            PhaseEvent {
+                phase: PrimaryPhase::Full,
+                secs: 1642463280.,
+            },
+            PhaseEvent {
+                phase: PrimaryPhase::LastQuarter,
+                secs: 1643118470.,
+            },
+        ]);
+        assert_eq!(
+            render(&events, &settings(Format::Json)),
+            "[{\"timestamp\":\"2022-01-17T23:48:00Z\",\"phase_name\":\"full\",\"emoji\":\"🌕\"},\n\
+             {\"timestamp\":\"2022-01-25T13:47:50Z\",\"phase_name\":\"last_quarter\",\"emoji\":\"🌗\"}]\n"
+        );
+        assert_eq!(
+            render(&events, &settings(Format::Yaml)),
+            "- timestamp: \"2022-01-17T23:48:00Z\"\n  phase_name: \"full\"\n  emoji: \"🌕\"\n\
+             - timestamp: \"2022-01-25T13:47:50Z\"\n  phase_name: \"last_quarter\"\n  emoji: \"🌗\"\n"
+        );
+        assert_eq!(
+            render(&events, &settings(Format::Csv)),
+            "timestamp,phase_name,emoji\n2022-01-17T23:48:00Z,full,🌕\n2022-01-25T13:47:50Z,last_quarter,🌗\n"
+        );
+        assert_eq!(render(&events, &settings(Format::Plain)).lines().count(), 2);
+        assert_eq!(render(&Output::Events(Vec::new()), &settings(Format::Json)), "[]\n");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn observer() {
+        let settings = Settings {
+            format: Format::Plain,
+            zone: Zone::Fixed(parse_offset("+11:00").unwrap()),
+            observer: Some(Observer::new(-33.9, 151.2)),
+            hemisphere: Hemisphere::South,
+            language: None,
+        };
+        // Waning on 2022-01-25T13:47:50Z.
+        let plain = render(&Output::Moon(1643118470.), &settings);
+        assert!(plain.contains("Time:          2022-01-26T00:47:50+11:00\n"), "Failed for {}", plain);
+        assert!(plain.contains("Lit side:      right\n"));
+        assert!(plain.contains("Altitude:  "));
+        let json = render(&Output::Moon(1643118470.), &Settings { format: Format::Json, ..settings });
+        assert!(json.contains(",\"altitude\":"), "Failed for {}", json);
+    }
+
+    #[test]
+    fn languages() {
+        let german = Settings {
+            language: Some(Language::German),
+            ..settings(Format::Plain)
+        };
+        let plain = render(&Output::Moon(1642464000.), &german);
+        assert!(plain.starts_with("🌕 Vollmond\nZeit:         Dienstag, 18. Januar 2022, 00:00 UTC\nBeleuchtung:  "), "Failed for {}", plain);
+        assert!(plain.contains("Tierkreis:    "));
+        assert!(plain.contains(",") && plain.contains(" Tage\n"));
+        let event = Output::Event(PhaseEvent {
+            phase: PrimaryPhase::Full,
+            secs: 1642463280.,
+        });
+        assert_eq!(render(&event, &german), "Montag, 17. Januar 2022, 23:48 UTC  🌕 Vollmond\n");
+        // The structured formats stay the same.
+        let json = Settings { format: Format::Json, ..german };
+        assert_eq!(render(&event, &json), render(&event, &settings(Format::Json)));
+        let plain = render(&Output::Moon(1642464000.), &settings(Format::Plain));
+        assert!(plain.starts_with("🌕 Full Moon\nTime:          2022-01-18T00:00:00Z\n"), "Failed for {}", plain);
+    }
+}
@@ -0,0 +1,99 @@
+// `moon-phase is <state>`: prints nothing and exits with 0 when the moon is
+// in `state` and 1 when it isn't, for shell scripts and cron jobs:
+//
+//     moon-phase is full --within 12h && echo "Full moon tonight"
+//     moon-phase is waxing || exit
+//     moon-phase is up --latitude 52.52 --longitude 13.40
+
+use clap::ValueEnum;
+
+use moon_phase::almanac;
+use moon_phase::{MoonPhase, Observer, Phase, PrimaryPhase};
+
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+pub enum State {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+    Waxing,
+    Waning,
+    // Above the observer's horizon
+    Up,
+}
+
+impl State {
+    fn phase(self) -> Option<Phase> {
+        Some(match self {
+            State::New => Phase::New,
+            State::WaxingCrescent => Phase::WaxingCrescent,
+            State::FirstQuarter => Phase::FirstQuarter,
+            State::WaxingGibbous => Phase::WaxingGibbous,
+            State::Full => Phase::Full,
+            State::WaningGibbous => Phase::WaningGibbous,
+            State::LastQuarter => Phase::LastQuarter,
+            State::WaningCrescent => Phase::WaningCrescent,
+            _ => return None,
+        })
+    }
+
+    fn primary_phase(self) -> Option<PrimaryPhase> {
+        Some(match self {
+            State::New => PrimaryPhase::New,
+            State::FirstQuarter => PrimaryPhase::FirstQuarter,
+            State::Full => PrimaryPhase::Full,
+            State::LastQuarter => PrimaryPhase::LastQuarter,
+            _ => return None,
+        })
+    }
+}
+
+// Whether the moon is in `state` at `secs`. With `within` seconds, new, full
+// and the quarters are instead the moment of that phase being at most that
+// long before or after `secs`.
+pub fn check(state: State, secs: f64, within: Option<f64>, observer: Option<Observer>) -> Result<bool, String> {
+    let moon_phase = MoonPhase::from_secs_float(secs);
+    if let Some(within) = within {
+        let phase = state
+            .primary_phase()
+            .ok_or("--within only works with new, first-quarter, full and last-quarter")?;
+        return Ok(almanac::events(secs - within, secs + within).iter().any(|event| event.phase == phase));
+    }
+    Ok(match state {
+        State::Waxing => moon_phase.phase < 0.5,
+        State::Waning => moon_phase.phase >= 0.5,
+        State::Up => {
+            let observer = observer.ok_or("'is up' needs --latitude and --longitude or an [observer] in the config")?;
+            observer.moon_altitude(&moon_phase) > 0.
+        }
+        _ => state.phase() == Some(moon_phase.phase_name),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn states() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let full = 1642463280.;
+        assert_eq!(check(State::Full, full, None, None), Ok(true));
+        assert_eq!(check(State::New, full, None, None), Ok(false));
+        assert_eq!(check(State::Waning, full + 86400., None, None), Ok(true));
+        assert_eq!(check(State::Waxing, full + 86400., None, None), Ok(false));
+        assert_eq!(check(State::Full, full - 10. * 3600., Some(12. * 3600.), None), Ok(true));
+        assert_eq!(check(State::Full, full + 14. * 3600., Some(12. * 3600.), None), Ok(false));
+        assert_eq!(check(State::New, full, Some(86400.), None), Ok(false));
+        assert!(check(State::Waxing, full, Some(86400.), None).is_err());
+        assert!(check(State::Up, full, None, None).is_err());
+        // Opposite the sun, so up at midnight and down at noon.
+        let greenwich = Some(Observer::new(51.48, 0.));
+        assert_eq!(check(State::Up, full, None, greenwich), Ok(true));
+        assert_eq!(check(State::Up, full + 12. * 3600., None, greenwich), Ok(false));
+    }
+}
@@ -0,0 +1,277 @@
+// Times and phase questions in plain English, such as "tomorrow night",
+// "in 3 days" or "next full moon after 2025-12-24". Days are those of the
+// configured time zone, so "tomorrow noon" is noon on the clock even across
+// a change to or from daylight saving time.
+
+use chrono::{Datelike, Duration, NaiveDate, TimeZone};
+
+use moon_phase::almanac::{self, PhaseEvent};
+use moon_phase::PrimaryPhase;
+
+use crate::zone::Zone;
+use crate::{local_secs, parse_time};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Query {
+    // Unix seconds
+    Moment(f64),
+    // The first `phase` after `from`, or the last one before it when not
+    // `forward`.
+    Phase { phase: PrimaryPhase, forward: bool, from: f64 },
+}
+
+const WEEKDAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+fn time_of_day(word: &str) -> Option<f64> {
+    Some(match word {
+        "midnight" => 0.,
+        "morning" => 9.,
+        "noon" | "midday" => 12.,
+        "afternoon" => 15.,
+        "evening" => 18.,
+        "night" => 22.,
+        _ => return None,
+    } * 3600.)
+}
+
+fn unit_secs(word: &str) -> Option<f64> {
+    Some(match word.trim_end_matches('s') {
+        "second" | "sec" => 1.,
+        "minute" | "min" => 60.,
+        "hour" => 3600.,
+        "day" => 86400.,
+        "week" => 7. * 86400.,
+        _ => return None,
+    })
+}
+
+// "12h", "30 min", "1.5d" or seconds.
+pub fn parse_duration(text: &str) -> Result<f64, String> {
+    let error = || format!("can't read the duration '{}', expected e.g. 12h, 30m or 2d", text);
+    let lower = text.trim().to_lowercase();
+    let (count, unit) = lower.split_at(lower.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(lower.len()));
+    let count: f64 = count.parse().map_err(|_| error())?;
+    let unit = match unit.trim() {
+        "" | "s" => 1.,
+        "m" => 60.,
+        "h" => 3600.,
+        "d" => 86400.,
+        "w" => 7. * 86400.,
+        unit => unit_secs(unit).ok_or_else(error)?,
+    };
+    Ok(count * unit)
+}
+
+// Unix seconds of a time, absolute or relative to `now`.
+pub fn parse_moment(text: &str, now: f64, zone: &Zone) -> Result<f64, String> {
+    if let Ok(secs) = parse_time(text, zone) {
+        return Ok(secs);
+    }
+    let text = text.trim().to_lowercase();
+    let words: Vec<&str> = text.split_whitespace().filter(|word| *word != "at" && *word != "the").collect();
+    let error = || format!("can't read the time '{}'", text);
+    let local_date = |secs: f64| -> Result<NaiveDate, String> {
+        let time = zone.timestamp_opt(secs.floor() as i64, 0).single().ok_or_else(error)?;
+        Ok(time.naive_local().date())
+    };
+    match words[..] {
+        [] | ["now"] => return Ok(now),
+        ["in", count, unit] | ["in", count, unit, "from", "now"] => {
+            let count: f64 = count.parse().map_err(|_| error())?;
+            return Ok(now + count * unit_secs(unit).ok_or_else(error)?);
+        }
+        [count, unit, "ago"] => {
+            let count: f64 = count.parse().map_err(|_| error())?;
+            return Ok(now - count * unit_secs(unit).ok_or_else(error)?);
+        }
+        _ => {}
+    }
+    // A day, then maybe a time of day.
+    let (day, rest) = match words[..] {
+        ["tonight", ref rest @ ..] => (local_date(now)?, if rest.is_empty() { &["night"][..] } else { rest }),
+        ["today", ref rest @ ..] => (local_date(now)?, rest),
+        ["tomorrow", ref rest @ ..] => (local_date(now)? + Duration::days(1), rest),
+        ["yesterday", ref rest @ ..] => (local_date(now)? - Duration::days(1), rest),
+        [first, ref rest @ ..] => {
+            let (weekday, rest) = match (first, rest) {
+                ("next", [weekday, ref rest @ ..]) => (*weekday, rest),
+                _ => (first, rest),
+            };
+            if let Some(weekday) = WEEKDAYS.iter().position(|name| *name == weekday) {
+                let today = local_date(now)?;
+                let days_ahead = (weekday as i64 - today.weekday().num_days_from_monday() as i64 + 6).rem_euclid(7) + 1;
+                (today + Duration::days(days_ahead), rest)
+            } else {
+                (local_date(parse_time(first, zone).map_err(|_| error())?)?, rest)
+            }
+        }
+        [] => return Ok(now),
+    };
+    let secs = match rest {
+        [] => 0.,
+        [word] => time_of_day(word).or_else(|| parse_hour(word)).ok_or_else(error)?,
+        _ => return Err(error()),
+    };
+    Ok(local_secs(&(day.and_hms_opt(0, 0, 0).unwrap() + Duration::seconds(secs as i64)), zone))
+}
+
+// "21:30", "9pm" or "9am" as seconds after midnight.
+// With am or pm the hour is 1 - 12, 12am being midnight and 12pm noon.
+fn parse_hour(word: &str) -> Option<f64> {
+    let (word, afternoon) = match (word.strip_suffix("pm"), word.strip_suffix("am")) {
+        (Some(word), _) => (word, Some(true)),
+        (_, Some(word)) => (word, Some(false)),
+        _ => (word, None),
+    };
+    let mut parts = word.splitn(2, ':');
+    let hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = parts.next().map_or(Some(0), |minute| minute.parse().ok())?;
+    let hour = match afternoon {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(afternoon) => hour % 12 + if afternoon { 12 } else { 0 },
+        None => hour,
+    };
+    if hour < 24 && minute < 60 {
+        Some((hour * 3600 + minute * 60) as f64)
+    } else {
+        None
+    }
+}
+
+// A phase question, or else just a time.
+pub fn parse_query(text: &str, now: f64, zone: &Zone) -> Result<Query, String> {
+    let lower = text.trim().to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let (forward, words) = match words[..] {
+        ["next", ref rest @ ..] => (true, rest),
+        ["previous", ref rest @ ..] => (false, rest),
+        ["last", ref rest @ ..] if rest.first() != Some(&"quarter") => (false, rest),
+        _ => (true, &words[..]),
+    };
+    let (phase, rest) = match words {
+        ["new", ref rest @ ..] => (PrimaryPhase::New, rest),
+        ["full", ref rest @ ..] => (PrimaryPhase::Full, rest),
+        ["first", "quarter", ref rest @ ..] => (PrimaryPhase::FirstQuarter, rest),
+        ["last", "quarter", ref rest @ ..] | ["third", "quarter", ref rest @ ..] => (PrimaryPhase::LastQuarter, rest),
+        _ => return parse_moment(text, now, zone).map(Query::Moment),
+    };
+    let rest = match rest {
+        ["moon", ref rest @ ..] => rest,
+        _ => rest,
+    };
+    let (forward, from) = match rest {
+        [] => (forward, now),
+        ["after", ref moment @ ..] | ["from", ref moment @ ..] | ["since", ref moment @ ..] => {
+            (true, parse_moment(&moment.join(" "), now, zone)?)
+        }
+        ["before", ref moment @ ..] => (false, parse_moment(&moment.join(" "), now, zone)?),
+        _ => return Err(format!("can't read '{}'", text.trim())),
+    };
+    Ok(Query::Phase { phase, forward, from })
+}
+
+// The event a phase question asks for, searching up to 40 days.
+pub fn find_phase(phase: PrimaryPhase, forward: bool, from: f64) -> Option<PhaseEvent> {
+    let matches = |event: &PhaseEvent| event.phase == phase;
+    if forward {
+        almanac::events(from, from + 40. * 86400.).into_iter().find(matches)
+    } else {
+        almanac::events(from - 40. * 86400., from).into_iter().rev().find(matches)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zone::parse_zone;
+
+    // 2022-01-18T15:00:00Z, a Tuesday.
+    const NOW: f64 = 1642518000.;
+    const MIDNIGHT: f64 = 1642464000.;
+
+    #[test]
+    fn moments() {
+        let moments = [
+            ("now", NOW),
+            ("today", MIDNIGHT),
+            ("tonight", MIDNIGHT + 22. * 3600.),
+            ("tomorrow night", MIDNIGHT + 86400. + 22. * 3600.),
+            ("Yesterday at noon", MIDNIGHT - 12. * 3600.),
+            ("tomorrow 9pm", MIDNIGHT + 86400. + 21. * 3600.),
+            ("today 06:30", MIDNIGHT + 6.5 * 3600.),
+            ("today 12am", MIDNIGHT),
+            ("today 12pm", MIDNIGHT + 12. * 3600.),
+            ("today 12:30am", MIDNIGHT + 0.5 * 3600.),
+            ("today 9am", MIDNIGHT + 9. * 3600.),
+            ("in 3 days", NOW + 3. * 86400.),
+            ("2 hours ago", NOW - 2. * 3600.),
+            ("friday", MIDNIGHT + 3. * 86400.),
+            ("next tuesday evening", MIDNIGHT + 7. * 86400. + 18. * 3600.),
+            ("2025-12-24 evening", 1766534400. + 18. * 3600.),
+            ("2022-01-17T23:48:00Z", 1642463280.),
+        ];
+        for (text, secs) in moments.iter() {
+            assert_eq!(parse_moment(text, NOW, &Zone::default()), Ok(*secs), "Failed for {}", text);
+        }
+        assert!(parse_moment("tomorrow at teatime", NOW, &Zone::default()).is_err());
+        assert!(parse_moment("in three days", NOW, &Zone::default()).is_err());
+        assert!(parse_moment("today 13pm", NOW, &Zone::default()).is_err());
+        assert!(parse_moment("today 0am", NOW, &Zone::default()).is_err());
+        // In Tokyo it is already past midnight, Wednesday.
+        let tokyo = parse_zone("+09:00").unwrap();
+        assert_eq!(parse_moment("today", NOW, &tokyo), Ok(MIDNIGHT + 15. * 3600.));
+        assert_eq!(parse_moment("friday noon", NOW, &tokyo), Ok(MIDNIGHT + 2. * 86400. + 15. * 3600. + 12. * 3600.));
+        // Summer time starts in Berlin on Sunday 2022-03-27.
+        let berlin = parse_zone("Europe/Berlin").unwrap();
+        let saturday = 1648306800.;
+        assert_eq!(parse_moment("tomorrow noon", saturday, &berlin), Ok(1648375200.));
+        assert_eq!(parse_moment("today", saturday, &berlin), Ok(1648249200.));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("12h"), Ok(43200.));
+        assert_eq!(parse_duration("30 min"), Ok(1800.));
+        assert_eq!(parse_duration("1.5d"), Ok(129600.));
+        assert_eq!(parse_duration("2 weeks"), Ok(14. * 86400.));
+        assert_eq!(parse_duration("90"), Ok(90.));
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("12 fortnights").is_err());
+    }
+
+    #[test]
+    fn queries() {
+        assert_eq!(
+            parse_query("next full moon after 2025-12-24", NOW, &Zone::default()),
+            Ok(Query::Phase {
+                phase: PrimaryPhase::Full,
+                forward: true,
+                from: 1766534400.,
+            })
+        );
+        assert_eq!(
+            parse_query("last quarter", NOW, &Zone::default()),
+            Ok(Query::Phase {
+                phase: PrimaryPhase::LastQuarter,
+                forward: true,
+                from: NOW,
+            })
+        );
+        assert_eq!(
+            parse_query("last new moon", NOW, &Zone::default()),
+            Ok(Query::Phase {
+                phase: PrimaryPhase::New,
+                forward: false,
+                from: NOW,
+            })
+        );
+        assert_eq!(parse_query("tomorrow night", NOW, &Zone::default()), Ok(Query::Moment(MIDNIGHT + 86400. + 22. * 3600.)));
+        assert!(parse_query("full moon during lunch", NOW, &Zone::default()).is_err());
+
+        // Full moon on 2022-01-17T23:48:00+00:00, new moon on 2022-01-02.
+        let full = find_phase(PrimaryPhase::Full, false, NOW).unwrap();
+        assert!((full.secs - 1642463280.).abs() < 600., "Failed for {:?}", full);
+        let new = find_phase(PrimaryPhase::New, true, 1640995200.).unwrap();
+        assert!((new.secs - 1641148380.).abs() < 600., "Failed for {:?}", new);
+    }
+}
@@ -0,0 +1,196 @@
+// `moon-phase tui`: the moon at a time that can be moved with the keys, its
+// illumination over the next month and the coming phase events.
+//
+//     ← →  a day back and forth        ↑ ↓  an hour
+//     [ ]  a week                      n    the current time
+//     q    quit
+
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+
+use moon_phase::almanac;
+use moon_phase::ephemeris::EARTH_RADIUS;
+use moon_phase::observer::Limb;
+use moon_phase::{MoonPhase, Phase};
+
+use crate::{format_local, now, Settings};
+
+// Lines of the moon drawn with characters twice as high as wide, lit with
+// █ and dark with ░.
+fn disk(illumination: f64, lit: Limb, rows: usize) -> Vec<String> {
+    let columns = rows * 2;
+    (0..rows)
+        .map(|row| {
+            let y = (row as f64 + 0.5) / rows as f64 * 2. - 1.;
+            (0..columns)
+                .map(|column| {
+                    let x = (column as f64 + 0.5) / columns as f64 * 2. - 1.;
+                    let half_width = (1. - y * y).sqrt();
+                    if x.abs() > half_width {
+                        return ' ';
+                    }
+                    // The terminator is an ellipse across the disk.
+                    let terminator = (1. - 2. * illumination) * half_width;
+                    let x = if lit == Limb::Right { x } else { -x };
+                    if x > terminator { '█' } else { '░' }
+                })
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+struct App<'a> {
+    settings: &'a Settings,
+    secs: f64,
+}
+
+impl App<'_> {
+    // False to quit.
+    fn handle(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Left => self.secs -= 86400.,
+            KeyCode::Right => self.secs += 86400.,
+            KeyCode::Up => self.secs -= 3600.,
+            KeyCode::Down => self.secs += 3600.,
+            KeyCode::Char('[') => self.secs -= 7. * 86400.,
+            KeyCode::Char(']') => self.secs += 7. * 86400.,
+            KeyCode::Char('n') => self.secs = now(),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let moon_phase = MoonPhase::from_secs_float(self.secs);
+        let [top, timeline, help] =
+            Layout::vertical([Constraint::Min(12), Constraint::Length(6), Constraint::Length(1)]).areas(frame.area());
+        let [disk_area, info_area, events_area] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(30), Constraint::Min(34)]).areas(top);
+
+        let rows = (disk_area.height.saturating_sub(2) as usize).min(disk_area.width.saturating_sub(2) as usize / 2);
+        let lines: Vec<Line> = disk(moon_phase.illumination, moon_phase.lit_limb(self.settings.hemisphere), rows)
+            .into_iter()
+            .map(Line::from)
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).style(Style::default().fg(Color::Yellow)).block(Block::bordered()),
+            disk_area,
+        );
+
+        let mut info = vec![
+            Line::from(format!("{} {}", moon_phase.phase_name.emoji(), moon_phase.phase_name)),
+            Line::from(""),
+            Line::from(format!("Time:          {}", format_local(self.secs, &self.settings.zone))),
+            Line::from(format!("Illumination:  {:.1}%", moon_phase.illumination * 100.)),
+            Line::from(format!("Age:           {:.1} days", moon_phase.age)),
+            Line::from(format!("Distance:      {:.0} km", moon_phase.distance * EARTH_RADIUS)),
+            Line::from(format!("Zodiac:        {:?}", moon_phase.zodiac_name)),
+        ];
+        if let Some(observer) = self.settings.observer {
+            info.push(Line::from(format!("Altitude:      {:.1}°", observer.moon_altitude(&moon_phase))));
+        }
+        frame.render_widget(Paragraph::new(info).block(Block::bordered().title(" Moon ")), info_area);
+
+        let events: Vec<String> = almanac::events(self.secs, self.secs + 60. * 86400.)
+            .into_iter()
+            .map(|event| {
+                let phase = Phase::from(event.phase);
+                format!("{} {}  {}", phase.emoji(), format_local(event.secs, &self.settings.zone), event.phase)
+            })
+            .collect();
+        frame.render_widget(List::new(events).block(Block::bordered().title(" Coming up ")), events_area);
+
+        // One bar for every six hours.
+        let illumination: Vec<u64> = (0..timeline.width.saturating_sub(2) as u64)
+            .map(|i| {
+                let moon_phase = MoonPhase::from_secs_float(self.secs + i as f64 * 6. * 3600.);
+                (moon_phase.illumination * 100.).round() as u64
+            })
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&illumination)
+                .max(100)
+                .style(Style::default().fg(Color::Yellow))
+                .block(Block::bordered().title(" Illumination, 6 hours a bar ")),
+            timeline,
+        );
+        frame.render_widget(Line::from(" ←/→ day  ↑/↓ hour  [/] week  n now  q quit"), help);
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, settings: &Settings, secs: f64) -> std::io::Result<()> {
+    let mut app = App { settings, secs };
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if !event::poll(Duration::from_secs(60))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+pub fn tui(settings: &Settings, secs: f64) -> Result<(), String> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, settings, secs);
+    ratatui::restore();
+    result.map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn disks() {
+        let full = disk(1., Limb::Right, 4);
+        assert_eq!(full.len(), 4);
+        assert!(full.iter().all(|line| !line.contains('░')));
+        assert!(disk(0., Limb::Right, 4).iter().all(|line| !line.contains('█')));
+        // Lit on the right side only.
+        let quarter = disk(0.5, Limb::Right, 4);
+        assert_eq!(quarter[1], "░░░░████");
+        let quarter = disk(0.5, Limb::Left, 4);
+        assert_eq!(quarter[1], "████░░░░");
+    }
+
+    #[test]
+    fn keys() {
+        let settings = Settings {
+            format: Default::default(),
+            zone: Default::default(),
+            observer: None,
+            hemisphere: moon_phase::Hemisphere::North,
+            language: None,
+        };
+        let mut app = App {
+            settings: &settings,
+            secs: 1642464000.,
+        };
+        assert!(app.handle(KeyCode::Right));
+        assert!(app.handle(KeyCode::Up));
+        assert_eq!(app.secs, 1642464000. + 86400. - 3600.);
+        assert!(!app.handle(KeyCode::Char('q')));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Full Moon"), "Failed for {}", screen);
+        assert!(screen.contains("Coming up"));
+    }
+}
@@ -0,0 +1,122 @@
+// `moon-phase watch`: runs until stopped and reports whenever the name of
+// the phase changes or a new, full or quarter moon passes, optionally
+// running a command for each.
+
+use std::io::{self, Write};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use moon_phase::almanac::{self, PhaseEvent};
+use moon_phase::{phase_key, MoonPhase, Phase};
+
+#[cfg(feature="mqtt")]
+use crate::mqtt::Publisher;
+use crate::output::{render, Output};
+use crate::{format_time, now, Settings};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Change {
+    Phase { phase: Phase, secs: f64 },
+    Event(PhaseEvent),
+}
+
+#[derive(Debug, Default)]
+pub struct Watcher {
+    phase: Option<Phase>,
+    last_poll: Option<f64>,
+}
+
+impl Watcher {
+    // The changes since the last poll. The first poll gives the current
+    // phase.
+    pub fn poll(&mut self, now: f64) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if let Some(last_poll) = self.last_poll {
+            changes.extend(almanac::events(last_poll, now).into_iter().map(Change::Event));
+        }
+        let phase = MoonPhase::from_secs_float(now).phase_name;
+        if self.phase != Some(phase) {
+            changes.push(Change::Phase { phase, secs: now });
+        }
+        self.phase = Some(phase);
+        self.last_poll = Some(now);
+        changes
+    }
+}
+
+// Runs `command` with the shell, with the change in the environment.
+fn run_hook(command: &str, change: &Change) {
+    let (kind, phase, secs) = match change {
+        Change::Phase { phase, secs } => ("phase", *phase, *secs),
+        Change::Event(event) => ("event", event.phase.into(), event.secs),
+    };
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("MOON_PHASE_KIND", kind)
+        .env("MOON_PHASE_NAME", phase_key(phase))
+        .env("MOON_PHASE_EMOJI", phase.emoji().to_string())
+        .env("MOON_PHASE_TIME", format_time(secs))
+        .status();
+    if let Err(error) = status {
+        eprintln!("moon-phase: can't run '{}': {}", command, error);
+    }
+}
+
+// Polls every `interval` seconds, or at the next event if that is sooner.
+pub fn watch(
+    settings: &Settings,
+    interval: f64,
+    exec: Option<&str>,
+    #[cfg(feature="mqtt")] mqtt: Option<&Publisher>,
+) -> Result<(), String> {
+    let mut watcher = Watcher::default();
+    loop {
+        let now = now();
+        #[cfg(feature="mqtt")]
+        if let Some(mqtt) = mqtt {
+            mqtt.state(now, settings);
+        }
+        for change in watcher.poll(now) {
+            let output = match change {
+                Change::Phase { secs, .. } => Output::Moon(secs),
+                Change::Event(event) => Output::Event(event),
+            };
+            let mut stdout = io::stdout();
+            write!(stdout, "{}", render(&output, settings)).and_then(|_| stdout.flush()).map_err(|error| error.to_string())?;
+            if let Some(command) = exec {
+                run_hook(command, &change);
+            }
+            #[cfg(feature="mqtt")]
+            if let Some(mqtt) = mqtt {
+                mqtt.change(&change, settings);
+            }
+        }
+        let next_event = almanac::events(now, now + interval + 1.).first().map_or(f64::INFINITY, |event| event.secs);
+        let sleep = (next_event + 1. - now).clamp(1., interval);
+        thread::sleep(Duration::from_secs_f64(sleep));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use moon_phase::PrimaryPhase;
+
+    #[test]
+    fn changes() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let mut watcher = Watcher::default();
+        let changes = watcher.poll(1642460000.);
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0], Change::Phase { phase: Phase::Full, .. }), "Failed for {:?}", changes);
+        assert!(watcher.poll(1642462000.).is_empty());
+        let changes = watcher.poll(1642466000.);
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0], Change::Event(PhaseEvent { phase: PrimaryPhase::Full, .. })), "Failed for {:?}", changes);
+        // Waning gibbous from the 19th.
+        let changes = watcher.poll(1642700000.);
+        assert!(matches!(changes[..], [Change::Phase { phase: Phase::WaningGibbous, .. }]), "Failed for {:?}", changes);
+    }
+}
@@ -0,0 +1,138 @@
+// The time zone of `--tz`, the config file or `$TZ`: either a fixed offset
+// from UTC or a name from the tz database such as "Europe/Berlin", whose
+// offset changes with daylight saving time.
+
+use std::fmt;
+
+use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{parse_offset, utc};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Zone {
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl Default for Zone {
+    fn default() -> Self {
+        Zone::Fixed(utc())
+    }
+}
+
+// The offset of a `Zone` at some time, remembering the zone it belongs to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ZoneOffset {
+    zone: Zone,
+    offset: FixedOffset,
+}
+
+impl Offset for ZoneOffset {
+    fn fix(&self) -> FixedOffset {
+        self.offset
+    }
+}
+
+impl fmt::Display for ZoneOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.offset)
+    }
+}
+
+impl Zone {
+    fn with_offset(self, offset: FixedOffset) -> ZoneOffset {
+        ZoneOffset { zone: self, offset }
+    }
+
+    fn map(self, offset: LocalResult<FixedOffset>) -> LocalResult<ZoneOffset> {
+        match offset {
+            LocalResult::None => LocalResult::None,
+            LocalResult::Single(offset) => LocalResult::Single(self.with_offset(offset)),
+            LocalResult::Ambiguous(earliest, latest) => {
+                LocalResult::Ambiguous(self.with_offset(earliest), self.with_offset(latest))
+            }
+        }
+    }
+}
+
+impl TimeZone for Zone {
+    type Offset = ZoneOffset;
+
+    fn from_offset(offset: &ZoneOffset) -> Self {
+        offset.zone
+    }
+
+    #[allow(deprecated)]
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<ZoneOffset> {
+        match self {
+            Zone::Fixed(zone) => self.map(zone.offset_from_local_date(local)),
+            Zone::Named(zone) => self.map(zone.offset_from_local_date(local).map(|offset| offset.fix())),
+        }
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<ZoneOffset> {
+        match self {
+            Zone::Fixed(zone) => self.map(zone.offset_from_local_datetime(local)),
+            Zone::Named(zone) => self.map(zone.offset_from_local_datetime(local).map(|offset| offset.fix())),
+        }
+    }
+
+    #[allow(deprecated)]
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneOffset {
+        match self {
+            Zone::Fixed(zone) => self.with_offset(zone.offset_from_utc_date(utc)),
+            Zone::Named(zone) => self.with_offset(zone.offset_from_utc_date(utc).fix()),
+        }
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneOffset {
+        match self {
+            Zone::Fixed(zone) => self.with_offset(zone.offset_from_utc_datetime(utc)),
+            Zone::Named(zone) => self.with_offset(zone.offset_from_utc_datetime(utc).fix()),
+        }
+    }
+}
+
+// An offset as for `parse_offset`, or a tz database name.
+pub fn parse_zone(text: &str) -> Result<Zone, String> {
+    if let Ok(offset) = parse_offset(text) {
+        return Ok(Zone::Fixed(offset));
+    }
+    text.trim().parse::<Tz>().map(Zone::Named).map_err(|_| {
+        format!("can't read the time zone '{}', expected UTC, an offset like +01:00 or a name like Europe/Berlin", text)
+    })
+}
+
+// `$TZ` as set for the C library, which may start with a colon. Other
+// forms, such as "CET-1CEST,M3.5.0,M10.5.0/3", are left alone.
+pub fn parse_tz_variable(text: &str) -> Option<Zone> {
+    let text = text.strip_prefix(':').unwrap_or(text);
+    text.parse::<Tz>().ok().map(Zone::Named).or_else(|| parse_offset(text).ok().map(Zone::Fixed))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Timelike;
+
+    #[test]
+    fn zones() {
+        assert_eq!(parse_zone("UTC"), Ok(Zone::Fixed(utc())));
+        assert_eq!(parse_zone("+05:30"), Ok(Zone::Fixed(FixedOffset::east_opt(19800).unwrap())));
+        assert_eq!(parse_zone("Europe/Berlin"), Ok(Zone::Named(Tz::Europe__Berlin)));
+        assert!(parse_zone("Europe/Atlantis").is_err());
+        assert_eq!(parse_tz_variable(":America/New_York"), Some(Zone::Named(Tz::America__New_York)));
+        assert_eq!(parse_tz_variable("CET-1CEST,M3.5.0,M10.5.0/3"), None);
+
+        // Winter and summer time in Berlin.
+        let berlin = Zone::Named(Tz::Europe__Berlin);
+        let winter = berlin.timestamp_opt(1642463280, 0).unwrap();
+        assert_eq!((winter.hour(), winter.offset().fix().local_minus_utc()), (0, 3600));
+        let summer = berlin.timestamp_opt(1656633600, 0).unwrap();
+        assert_eq!((summer.hour(), summer.offset().fix().local_minus_utc()), (2, 7200));
+        // 02:30 on 2022-03-27 doesn't exist there.
+        let skipped = NaiveDate::from_ymd_opt(2022, 3, 27).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(berlin.from_local_datetime(&skipped), LocalResult::None);
+    }
+}
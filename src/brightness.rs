@@ -0,0 +1,157 @@
+// How bright the moon is, and how much it brightens the sky. The apparent
+// visual magnitude follows Allen, "Astrophysical Quantities" (1973), with the
+// phase angle term 0.026 |i| + 4e-9 i^4, which holds up to a phase angle of
+// about 150 degrees. Thinner crescents are fainter than it says. The sky
+// brightness follows K. Krisciunas and B. E. Schaefer, "A Model of the
+// Brightness of Moonlight" (PASP 103, 1991), good to about 20% in V.
+
+use crate::ephemeris::{AU, EARTH_RADIUS};
+use crate::observer::Observer;
+use crate::sun::SunPosition;
+use crate::MoonPhase;
+
+// Magnitude of the full moon seen from 1 AU, with the sun 1 AU away.
+const MAGNITUDE_AT_1_AU: f64 = 0.21;
+// Extinction in V in magnitudes per airmass, for a good site.
+const EXTINCTION: f64 = 0.172;
+// Moonless sky at the zenith in V, mag/arcsec^2.
+const DARK_SKY: f64 = 21.6;
+// Mean distance Krisciunas and Schaefer's scattering is scaled to, in km.
+const MEAN_DISTANCE_KM: f64 = 384400.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SkyBrightness {
+    pub moonlight: f64,     // Scattered moonlight in nanolamberts
+    pub dark_sky: f64,      // Moonless sky in nanolamberts
+    pub magnitude: f64,     // Both together in V mag/arcsec^2, smaller is brighter
+    pub interference: f64,  // Share of the moon in the sky's brightness, 0 - 1
+}
+
+fn nanolamberts(magnitude: f64) -> f64 {
+    34.08 * (20.7233 - 0.92104 * magnitude).exp()
+}
+
+fn magnitude(nanolamberts: f64) -> f64 {
+    (20.7233 - (nanolamberts / 34.08).ln()) / 0.92104
+}
+
+// Optical path through the atmosphere relative to the zenith, for a zenith
+// distance in degrees.
+fn airmass(zenith_distance: f64) -> f64 {
+    let sin = zenith_distance.to_radians().sin();
+    (1. - 0.96 * sin * sin).powf(-0.5)
+}
+
+// Angle between two points in the sky given in altitude and azimuth, in
+// degrees.
+fn separation(altitude1: f64, azimuth1: f64, altitude2: f64, azimuth2: f64) -> f64 {
+    let (a1, a2) = (altitude1.to_radians(), altitude2.to_radians());
+    let cos = a1.sin() * a2.sin() + a1.cos() * a2.cos() * (azimuth1 - azimuth2).to_radians().cos();
+    cos.clamp(-1., 1.).acos().to_degrees()
+}
+
+impl MoonPhase {
+    // Apparent visual magnitude, about -12.7 at full moon and -10 at the
+    // quarters. Smaller is brighter.
+    pub fn apparent_magnitude(&self) -> f64 {
+        let phase_angle = self.phase_angle().abs();
+        let moon_distance = self.distance * EARTH_RADIUS / AU;
+        // The moon is as far from the sun as the earth, to a fraction of a
+        // percent.
+        let sun_distance = SunPosition::new(self.j_date).distance;
+        MAGNITUDE_AT_1_AU
+            + 0.026 * phase_angle
+            + 4e-9 * phase_angle.powi(4)
+            + 5. * (sun_distance * moon_distance).log10()
+    }
+}
+
+impl Observer {
+    // The sky at `altitude` and `azimuth` (degrees, azimuth from north
+    // through east) with `moon_phase` up, seen by the observer.
+    pub fn sky_brightness(&self, moon_phase: &MoonPhase, altitude: f64, azimuth: f64) -> SkyBrightness {
+        let topocentric = self.topocentric(moon_phase);
+        let (moon_altitude, moon_azimuth) =
+            self.horizontal(topocentric.j_date, topocentric.longitude, topocentric.latitude);
+        let zenith_distance = 90. - altitude.clamp(0., 90.);
+        let dark_sky = nanolamberts(DARK_SKY) * 10f64.powf(-0.4 * EXTINCTION * (airmass(zenith_distance) - 1.))
+            * airmass(zenith_distance);
+
+        let moonlight = if moon_altitude > 0. {
+            let phase_angle = moon_phase.phase_angle().abs();
+            let illuminance = 10f64.powf(-0.4 * (3.84 + 0.026 * phase_angle + 4e-9 * phase_angle.powi(4)))
+                * (MEAN_DISTANCE_KM / (moon_phase.distance * EARTH_RADIUS)).powi(2);
+            // Scattering by aerosols and molecules, the first term only
+            // holds further than 10 degrees from the moon.
+            let rho = separation(altitude, azimuth, moon_altitude, moon_azimuth).max(0.5);
+            let scattering = if rho > 10. {
+                10f64.powf(5.36) * (1.06 + rho.to_radians().cos().powi(2)) + 10f64.powf(6.15 - rho / 40.)
+            } else {
+                6.2e7 / (rho * rho)
+            };
+            scattering
+                * illuminance
+                * 10f64.powf(-0.4 * EXTINCTION * airmass(90. - moon_altitude))
+                * (1. - 10f64.powf(-0.4 * EXTINCTION * airmass(zenith_distance)))
+        } else {
+            0.
+        };
+
+        SkyBrightness {
+            moonlight,
+            dark_sky,
+            magnitude: magnitude(moonlight + dark_sky),
+            interference: moonlight / (moonlight + dark_sky),
+        }
+    }
+
+    // How much the moon brightens the sky at the zenith, 0 for none and
+    // towards 1 when moonlight drowns everything else.
+    pub fn moonlight_interference(&self, moon_phase: &MoonPhase) -> f64 {
+        self.sky_brightness(moon_phase, 90., 0.).interference
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apparent_magnitude() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let full = MoonPhase::from_secs(1642463280).apparent_magnitude();
+        assert!((-12.9..-12.5).contains(&full), "Failed for {}", full);
+        // About 2.2 magnitudes fainter at the quarters.
+        let quarter = MoonPhase::from_secs(1641852000).apparent_magnitude();
+        assert!((-10.6..-9.5).contains(&quarter), "Failed for {}", quarter);
+        // Brighter at perigee than at apogee, the supermoon of
+        // 2022-07-13T18:37:00+00:00 against the full moon of 2022-01-17.
+        assert!(MoonPhase::from_secs(1657737420).apparent_magnitude() < full);
+    }
+
+    #[test]
+    fn sky_brightness() {
+        // Full moon on 2022-01-17T23:48:00+00:00, high in the south at
+        // Greenwich, and below the horizon at noon.
+        let greenwich = Observer::new(51.48, 0.);
+        let midnight = MoonPhase::from_secs(1642464000);
+        let zenith = greenwich.sky_brightness(&midnight, 90., 0.);
+        assert!((17.0..19.5).contains(&zenith.magnitude), "Failed for {:?}", zenith);
+        assert!(zenith.interference > 0.9, "Failed for {:?}", zenith);
+        // Brighter next to the moon, fainter opposite to it.
+        let moon_altitude = greenwich.moon_altitude(&midnight);
+        let near = greenwich.sky_brightness(&midnight, moon_altitude, 180.);
+        let opposite = greenwich.sky_brightness(&midnight, 30., 0.);
+        assert!(near.magnitude < zenith.magnitude - 1., "Failed for {:?}", near);
+        assert!(opposite.moonlight < near.moonlight, "Failed for {:?}", opposite);
+
+        let noon = greenwich.sky_brightness(&MoonPhase::from_secs(1642507200), 90., 0.);
+        assert_eq!(noon.moonlight, 0.);
+        assert!((noon.magnitude - DARK_SKY).abs() < 1e-9, "Failed for {:?}", noon);
+        // The young crescent in the evening of 2022-01-05 matters much less.
+        let crescent = MoonPhase::from_secs(1641402000);
+        assert!(greenwich.moon_altitude(&crescent) > 0.);
+        let interference = greenwich.moonlight_interference(&crescent);
+        assert!(interference > 0. && interference < 0.6, "Failed for {}", interference);
+    }
+}
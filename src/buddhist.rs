@@ -0,0 +1,97 @@
+// Observance days of Theravada Buddhism, which follow the phases of the moon
+// on the local calendar day: the Uposatha days at new, full and quarter moon,
+// and Vesak on the full moon of May. Traditional lunisolar calendars shift
+// Vesak to June in some years, which is not followed here.
+
+use crate::aspect::{sun_aspects, Aspect};
+use crate::calendar::{civil_from_days, days_from_civil};
+use crate::ephemeris::Ephemeris;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Uposatha {
+    NewMoon,
+    FirstQuarter,
+    FullMoon,
+    LastQuarter,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UposathaDay {
+    pub uposatha: Uposatha,
+    pub date: (i32, u32, u32),  // Local (year, month, day)
+    pub secs: f64,              // Unix seconds of the exact phase
+}
+
+fn local_date(secs: f64, utc_offset: i32) -> (i32, u32, u32) {
+    let days = ((secs + utc_offset as f64) / 86400.).floor() as i64;
+    let (year, month, day) = civil_from_days(days);
+    (year as i32, month, day)
+}
+
+// The Uposatha days from `from` up to `until` (unix seconds), for a place
+// `utc_offset` seconds ahead of UTC.
+fn uposatha_between<E: Ephemeris + ?Sized>(from: f64, until: f64, utc_offset: i32, ephemeris: &E) -> Vec<UposathaDay> {
+    sun_aspects(from, until, ephemeris)
+        .into_iter()
+        .filter_map(|event| {
+            let uposatha = match (event.aspect, event.elongation as u32) {
+                (Aspect::Conjunction, _) => Uposatha::NewMoon,
+                (Aspect::Square, 90) => Uposatha::FirstQuarter,
+                (Aspect::Opposition, _) => Uposatha::FullMoon,
+                (Aspect::Square, _) => Uposatha::LastQuarter,
+                _ => return None,
+            };
+            Some(UposathaDay {
+                uposatha,
+                date: local_date(event.secs, utc_offset),
+                secs: event.secs,
+            })
+        })
+        .collect()
+}
+
+// The Uposatha days of the Gregorian `year` in a time zone `utc_offset`
+// seconds ahead of UTC.
+pub fn uposatha_days<E: Ephemeris + ?Sized>(year: i32, utc_offset: i32, ephemeris: &E) -> Vec<UposathaDay> {
+    let start = |year: i32| days_from_civil(year as i64, 1, 1) as f64 * 86400. - utc_offset as f64;
+    uposatha_between(start(year), start(year + 1), utc_offset, ephemeris)
+}
+
+// Vesak of the Gregorian `year`, the first full moon with a local date in
+// May, in a time zone `utc_offset` seconds ahead of UTC.
+pub fn vesak<E: Ephemeris + ?Sized>(year: i32, utc_offset: i32, ephemeris: &E) -> UposathaDay {
+    let start = |month: u32| days_from_civil(year as i64, month, 1) as f64 * 86400. - utc_offset as f64;
+    uposatha_between(start(5), start(6), utc_offset, ephemeris)
+        .into_iter()
+        .find(|day| day.uposatha == Uposatha::FullMoon)
+        .expect("no full moon in May")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    const SRI_LANKA: i32 = 19800;
+    const THAILAND: i32 = 7 * 3600;
+
+    #[test]
+    fn uposatha() {
+        let days = uposatha_days(2022, THAILAND, &Meeus);
+        assert!((49..=51).contains(&days.len()), "Failed for {}", days.len());
+        // New moon on 2022-01-02T18:33:00+00:00, after midnight in Bangkok.
+        assert_eq!((days[0].uposatha, days[0].date), (Uposatha::NewMoon, (2022, 1, 3)));
+        assert_eq!(days[1].uposatha, Uposatha::FirstQuarter);
+        assert_eq!(days[2].uposatha, Uposatha::FullMoon);
+        assert_eq!(days[3].uposatha, Uposatha::LastQuarter);
+        assert!(days.iter().all(|day| day.date.0 == 2022));
+    }
+
+    #[test]
+    fn vesak_days() {
+        assert_eq!(vesak(2023, SRI_LANKA, &Meeus).date, (2023, 5, 5));
+        assert_eq!(vesak(2024, SRI_LANKA, &Meeus).date, (2024, 5, 23));
+        // Full moons on 2026-05-01 and 2026-05-31.
+        assert_eq!(vesak(2026, SRI_LANKA, &Meeus).date, (2026, 5, 1));
+    }
+}
@@ -0,0 +1,202 @@
+// Builder collecting all the options a `MoonPhase` can be calculated with.
+// The plain constructors (`new`, `from_secs`, `with_algorithm`, ...) stay
+// as shortcuts for the common cases.
+//
+//     let moon_phase = MoonPhase::builder()
+//         .secs(1642291200)
+//         .observer(Observer::new(52.5, 13.4))
+//         .algorithm(Algorithm::Meeus)
+//         .build();
+//     let limb = moon_phase.lit_limb();
+
+#[cfg(feature="chrono")]
+use chrono::{DateTime, offset::TimeZone};
+use std::ops::Deref;
+#[cfg(not(feature="chrono"))]
+use std::time::SystemTime;
+
+use crate::algorithm::Algorithm;
+use crate::clock::{Clock, SystemClock};
+use crate::naming::PhaseNaming;
+use crate::observer::{Hemisphere, Limb, Observer};
+use crate::zodiac::ZodiacMode;
+use crate::{unix_seconds, MoonPhase};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MoonPhaseBuilder {
+    secs: Option<f64>,
+    observer: Option<Observer>,
+    hemisphere: Option<Hemisphere>,
+    algorithm: Algorithm,
+    naming: PhaseNaming,
+    zodiac: ZodiacMode,
+}
+
+// What `MoonPhaseBuilder::build` returns: the moon phase and the hemisphere
+// it is seen from. Derefs to the `MoonPhase`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BuiltMoonPhase {
+    pub moon_phase: MoonPhase,
+    pub hemisphere: Hemisphere,
+}
+
+impl BuiltMoonPhase {
+    // See `MoonPhase::lit_limb`.
+    pub fn lit_limb(&self) -> Limb {
+        self.moon_phase.lit_limb(self.hemisphere)
+    }
+}
+
+impl Deref for BuiltMoonPhase {
+    type Target = MoonPhase;
+
+    fn deref(&self) -> &MoonPhase {
+        &self.moon_phase
+    }
+}
+
+impl MoonPhase {
+    pub fn builder() -> MoonPhaseBuilder {
+        MoonPhaseBuilder::default()
+    }
+}
+
+impl MoonPhaseBuilder {
+    #[cfg(feature="chrono")]
+    pub fn time<Tz: TimeZone>(self, time: DateTime<Tz>) -> Self {
+        self.secs_float(unix_seconds(time))
+    }
+
+    #[cfg(not(feature="chrono"))]
+    pub fn time(self, time: SystemTime) -> Self {
+        self.secs_float(unix_seconds(time))
+    }
+
+    pub fn secs(self, secs: i64) -> Self {
+        self.secs_float(secs as f64)
+    }
+
+    pub fn secs_float(self, secs: f64) -> Self {
+        MoonPhaseBuilder {
+            secs: Some(secs),
+            ..self
+        }
+    }
+
+    // Makes the position topocentric, see `Observer::topocentric`.
+    pub fn observer(self, observer: Observer) -> Self {
+        MoonPhaseBuilder {
+            observer: Some(observer),
+            ..self
+        }
+    }
+
+    // Overrides the hemisphere taken from the observer, for orientation
+    // dependent output such as `BuiltMoonPhase::lit_limb`.
+    pub fn hemisphere(self, hemisphere: Hemisphere) -> Self {
+        MoonPhaseBuilder {
+            hemisphere: Some(hemisphere),
+            ..self
+        }
+    }
+
+    pub fn algorithm(self, algorithm: Algorithm) -> Self {
+        MoonPhaseBuilder { algorithm, ..self }
+    }
+
+    // How `phase_name` is derived from the phase.
+    pub fn naming(self, naming: PhaseNaming) -> Self {
+        MoonPhaseBuilder { naming, ..self }
+    }
+
+    // How `zodiac_name` is derived from the longitude.
+    pub fn zodiac(self, zodiac: ZodiacMode) -> Self {
+        MoonPhaseBuilder { zodiac, ..self }
+    }
+
+    // The hemisphere set, or else the one of the observer, or else north.
+    pub fn get_hemisphere(&self) -> Hemisphere {
+        self.hemisphere
+            .or_else(|| self.observer.map(|observer| observer.hemisphere()))
+            .unwrap_or_default()
+    }
+
+    // Without a time, the current time is used.
+    pub fn build(&self) -> BuiltMoonPhase {
+        self.build_with_clock(&SystemClock)
+    }
+
+    // Same as `build`, but takes the current time from `clock`.
+    pub fn build_with_clock<C: Clock + ?Sized>(&self, clock: &C) -> BuiltMoonPhase {
+        let secs = self.secs.unwrap_or_else(|| clock.now());
+        let mut moon_phase = MoonPhase::from_secs_float_with_algorithm(secs, self.algorithm);
+        moon_phase.phase_name = self.naming.name(moon_phase.phase);
+        let mut moon_phase = match self.observer {
+            Some(observer) => observer.topocentric(&moon_phase),
+            None => moon_phase,
+        };
+        moon_phase.zodiac_name = moon_phase.zodiac(self.zodiac);
+        BuiltMoonPhase {
+            moon_phase,
+            hemisphere: self.get_hemisphere(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let builder = MoonPhase::builder().secs(1642291200);
+        assert_eq!(builder.build().moon_phase, MoonPhase::from_secs(1642291200));
+        assert_eq!(builder.get_hemisphere(), Hemisphere::North);
+        let clock = crate::clock::FixedClock(1642291200.);
+        assert_eq!(MoonPhase::builder().build_with_clock(&clock), builder.build());
+    }
+
+    #[test]
+    fn options() {
+        let observer = Observer::new(-33.9, 151.2);
+        let builder = MoonPhase::builder()
+            .secs(1642291200)
+            .observer(observer)
+            .algorithm(Algorithm::Meeus);
+        let expected = observer.topocentric(&MoonPhase::with_algorithm(1642291200, Algorithm::Meeus));
+        assert_eq!(builder.build().moon_phase, expected);
+        assert_eq!(builder.get_hemisphere(), Hemisphere::South);
+        assert_eq!(builder.hemisphere(Hemisphere::North).get_hemisphere(), Hemisphere::North);
+    }
+
+    #[test]
+    fn hemisphere() {
+        // Waxing, lit on the right in the north and on the left in the south.
+        let builder = MoonPhase::builder().secs(1642291200);
+        assert_eq!(builder.build().hemisphere, Hemisphere::North);
+        assert_eq!(builder.build().lit_limb(), Limb::Right);
+        let south = builder.observer(Observer::new(-33.9, 151.2));
+        assert_eq!(south.build().lit_limb(), Limb::Left);
+        assert_eq!(south.hemisphere(Hemisphere::North).build().lit_limb(), Limb::Right);
+        assert_eq!(builder.hemisphere(Hemisphere::South).build().lit_limb(), Limb::Left);
+    }
+
+    #[test]
+    fn naming() {
+        // About 39 hours before the full moon of 2022-01-17T23:48:00+00:00.
+        let builder = MoonPhase::builder().secs(1642291200);
+        assert_eq!(builder.build().phase_name, crate::Phase::Full);
+        let exact = builder.naming(PhaseNaming::Exact { hours: 12. }).build();
+        assert_eq!(exact.phase_name, crate::Phase::WaxingGibbous);
+    }
+
+    #[test]
+    fn zodiac() {
+        let builder = MoonPhase::builder().secs(1642291200).zodiac(ZodiacMode::Tropical);
+        let moon_phase = builder.build();
+        assert_eq!(moon_phase.zodiac_name, moon_phase.zodiac(ZodiacMode::Tropical));
+        let sidereal = ZodiacMode::Sidereal(crate::Ayanamsa::Lahiri);
+        let moon_phase = builder.zodiac(sidereal).build();
+        assert_eq!(moon_phase.zodiac_name, moon_phase.zodiac(sidereal));
+    }
+}
@@ -0,0 +1,98 @@
+// Memoizes `MoonPhase`s for servers that get asked about the same dates over
+// and over again. Timestamps are rounded down to a multiple of the
+// resolution, so every request within e.g. the same day shares one result.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::MoonPhase;
+
+pub const MINUTE: i64 = 60;
+pub const HOUR: i64 = 60 * MINUTE;
+pub const DAY: i64 = 24 * HOUR;
+
+#[derive(Debug)]
+pub struct MoonPhaseCache {
+    resolution: i64,
+    capacity: usize,
+    entries: Mutex<HashMap<i64, MoonPhase>>,
+}
+
+impl MoonPhaseCache {
+    // `resolution` is in seconds and must be positive.
+    pub fn new(resolution: i64) -> Self {
+        Self::with_capacity(resolution, usize::MAX)
+    }
+
+    // Once `capacity` entries are stored, the cache is cleared and starts
+    // over, which keeps memory bounded for long running processes.
+    pub fn with_capacity(resolution: i64, capacity: usize) -> Self {
+        assert!(resolution > 0, "resolution must be positive");
+        MoonPhaseCache {
+            resolution,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn resolution(&self) -> i64 {
+        self.resolution
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    // Returns the phase at the start of the interval `secs` falls into.
+    pub fn get(&self, secs: i64) -> MoonPhase {
+        let key = secs.div_euclid(self.resolution);
+        let mut entries = self.entries();
+        if let Some(moon_phase) = entries.get(&key) {
+            return *moon_phase;
+        }
+        if entries.len() >= self.capacity {
+            entries.clear();
+        }
+        let moon_phase = MoonPhase::from_secs(key * self.resolution);
+        entries.insert(key, moon_phase);
+        moon_phase
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<i64, MoonPhase>> {
+        // A panic while holding the lock can't leave the map inconsistent.
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quantized() {
+        let cache = MoonPhaseCache::new(DAY);
+        let midnight = 1642291200; // 2022-01-16T00:00:00+00:00
+        assert_eq!(cache.get(midnight + 5 * HOUR), MoonPhase::from_secs(midnight));
+        assert_eq!(cache.get(midnight + 23 * HOUR), MoonPhase::from_secs(midnight));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(midnight - 1), MoonPhase::from_secs(midnight - DAY));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn capacity() {
+        let cache = MoonPhaseCache::with_capacity(MINUTE, 10);
+        for i in 0..25 {
+            cache.get(i * MINUTE);
+            assert!(cache.len() <= 10);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Conversions between days since the unix epoch and proleptic Gregorian
+// calendar dates, after Howard Hinnant's `civil_from_days` and
+// `days_from_civil`.
+
+// Returns (year, month, day) for the given number of days since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+// Returns the number of days since 1970-01-01 for the given date. Months
+// outside of 1 - 12 carry over into the year, e.g. month 13 is January of
+// the next year and month 0 December of the previous one.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let months = year * 12 + month as i64 - 1;
+    let (year, month) = (months.div_euclid(12), months.rem_euclid(12) + 1);
+    let year = year - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// RFC 3339 timestamp in UTC for unix seconds, rounded to whole seconds.
+pub(crate) fn format_utc(secs: f64) -> String {
+    let secs = secs.round() as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let time = secs.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(10957), (2000, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(-719468), (0, 3, 1));
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 2, 29), 11016);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(0, 3, 1), -719468);
+        assert_eq!(days_from_civil(2000, 13, 1), days_from_civil(2001, 1, 1));
+        assert_eq!(days_from_civil(2000, 0, 1), days_from_civil(1999, 12, 1));
+        assert_eq!(days_from_civil(2000, 1201, 1), days_from_civil(2100, 1, 1));
+        assert_eq!(format_utc(0.), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(1642463280.4), "2022-01-17T23:48:00Z");
+        assert_eq!(format_utc(-1.), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn round_trip() {
+        for days in (-1_000_000..1_000_000).step_by(97) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days, "Failed for {}", days);
+        }
+    }
+}
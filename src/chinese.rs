@@ -0,0 +1,313 @@
+// The Chinese lunisolar calendar. Months begin on the day of the new moon in
+// Beijing time. Month 11 contains the winter solstice, and if there are 13
+// new moons from one month 11 to the next, the first month without a
+// principal solar term (sun at a multiple of 30 degrees) is a leap month and
+// repeats the number of the month before.
+
+use std::fmt;
+
+use crate::calendar::{civil_from_days, days_from_civil};
+use crate::ephemeris::Ephemeris;
+use crate::events::{new_moons, zero_crossings};
+use crate::julian_date_from_seconds;
+
+const BEIJING: f64 = 8. * 3600.;  // Seconds ahead of UTC
+// The sun moves a degree a day, twelve times that is well within a step.
+const TERM_STEP_SECS: f64 = 86400.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct LunarMonth {
+    pub number: u32,     // 1 - 12
+    pub leap: bool,
+    pub first_day: i64,  // Days since 1970-01-01 in Beijing time
+    pub days: u32,       // 29 or 30
+}
+
+impl LunarMonth {
+    // Gregorian (year, month, day) of the first day.
+    pub fn first_date(&self) -> (i32, u32, u32) {
+        let (year, month, day) = civil_from_days(self.first_day);
+        (year as i32, month, day)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChineseDate {
+    pub year: i32,   // Gregorian year in which the lunar year begins
+    pub month: u32,  // 1 - 12
+    pub leap: bool,
+    pub day: u32,    // 1 - 30
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Animal {
+    Rat,
+    Ox,
+    Tiger,
+    Rabbit,
+    Dragon,
+    Snake,
+    Horse,
+    Goat,
+    Monkey,
+    Rooster,
+    Dog,
+    Pig,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Element {
+    Wood,
+    Fire,
+    Earth,
+    Metal,
+    Water,
+}
+
+// Animal from the earthly branch and element from the heavenly stem of the
+// year in the sexagenary cycle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChineseZodiac {
+    pub animal: Animal,
+    pub element: Element,
+}
+
+const ANIMALS: [Animal; 12] = [
+    Animal::Rat,
+    Animal::Ox,
+    Animal::Tiger,
+    Animal::Rabbit,
+    Animal::Dragon,
+    Animal::Snake,
+    Animal::Horse,
+    Animal::Goat,
+    Animal::Monkey,
+    Animal::Rooster,
+    Animal::Dog,
+    Animal::Pig,
+];
+
+const ELEMENTS: [Element; 5] = [
+    Element::Wood,
+    Element::Fire,
+    Element::Earth,
+    Element::Metal,
+    Element::Water,
+];
+
+impl ChineseZodiac {
+    // For the lunar year beginning in the Gregorian `year`. 4 AD was the
+    // first year of a cycle, a Wood Rat.
+    pub fn from_year(year: i32) -> Self {
+        let cycle = (year as i64 - 4).rem_euclid(60) as usize;
+        ChineseZodiac {
+            animal: ANIMALS[cycle % 12],
+            element: ELEMENTS[cycle % 10 / 2],
+        }
+    }
+}
+
+impl fmt::Display for ChineseZodiac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {:?}", self.element, self.animal)
+    }
+}
+
+impl ChineseDate {
+    pub fn zodiac(&self) -> ChineseZodiac {
+        ChineseZodiac::from_year(self.year)
+    }
+}
+
+fn local_day(secs: f64) -> i64 {
+    ((secs + BEIJING) / 86400.).floor() as i64
+}
+
+fn sun_longitude<E: Ephemeris + ?Sized>(secs: f64, ephemeris: &E) -> f64 {
+    ephemeris.sun_longitude(julian_date_from_seconds(secs))
+}
+
+// Day of the winter solstice of the Gregorian `year`, None if the ephemeris
+// has the sun nowhere near it in December.
+fn winter_solstice<E: Ephemeris + ?Sized>(year: i32, ephemeris: &E) -> Option<i64> {
+    let from = days_from_civil(year as i64, 12, 1) as f64 * 86400.;
+    let solstice = zero_crossings(|secs| sun_longitude(secs, ephemeris) - 270., from, from + 31. * 86400., TERM_STEP_SECS);
+    solstice.first().copied().map(local_day)
+}
+
+// The months from the one containing the winter solstice of `year - 1` up to
+// the one before the winter solstice of `year`. None if the ephemeris gives no
+// solstice or no new moon before one.
+fn months_between_solstices<E: Ephemeris + ?Sized>(year: i32, ephemeris: &E) -> Option<Vec<LunarMonth>> {
+    let (start, end) = (winter_solstice(year - 1, ephemeris)?, winter_solstice(year, ephemeris)?);
+    let days: Vec<i64> = new_moons((start - 35) as f64 * 86400., (end + 35) as f64 * 86400., ephemeris)
+        .into_iter()
+        .map(local_day)
+        .collect();
+    let first = days.iter().rposition(|day| *day <= start)?;
+    let last = days.iter().rposition(|day| *day <= end)?;
+    let terms: Vec<i64> = zero_crossings(
+        |secs| 12. * sun_longitude(secs, ephemeris),
+        days[first] as f64 * 86400. - BEIJING,
+        days[last] as f64 * 86400. - BEIJING,
+        TERM_STEP_SECS,
+    )
+    .into_iter()
+    .map(local_day)
+    .collect();
+
+    let leap_year = last - first == 13;
+    let mut leap_used = false;
+    let mut months: Vec<LunarMonth> = Vec::new();
+    for i in first..last {
+        let has_term = terms.iter().any(|term| days[i] <= *term && *term < days[i + 1]);
+        let (number, leap) = match months.last() {
+            None => (11, false),
+            Some(previous) if leap_year && !leap_used && !has_term => {
+                leap_used = true;
+                (previous.number, true)
+            }
+            Some(previous) => (previous.number % 12 + 1, false),
+        };
+        months.push(LunarMonth {
+            number,
+            leap,
+            first_day: days[i],
+            days: (days[i + 1] - days[i]) as u32,
+        });
+    }
+    Some(months)
+}
+
+// The months of the lunar year beginning in the Gregorian `year`, from the
+// first month up to the last before the next Lunar New Year. None if the
+// ephemeris gives no winter solstice or Lunar New Year around the year, as
+// can happen outside of its range.
+pub fn lunar_months<E: Ephemeris + ?Sized>(year: i32, ephemeris: &E) -> Option<Vec<LunarMonth>> {
+    let is_first = |month: &LunarMonth| month.number == 1 && !month.leap;
+    let this = months_between_solstices(year, ephemeris)?;
+    let next = months_between_solstices(year + 1, ephemeris)?;
+    let months: Vec<LunarMonth> = this
+        .into_iter()
+        .skip_while(|month| !is_first(month))
+        .chain(next.into_iter().take_while(|month| !is_first(month)))
+        .collect();
+    (!months.is_empty()).then_some(months)
+}
+
+// Gregorian (year, month, day) of Lunar New Year in the Gregorian `year`,
+// see `lunar_months`.
+pub fn new_year<E: Ephemeris + ?Sized>(year: i32, ephemeris: &E) -> Option<(i32, u32, u32)> {
+    months_between_solstices(year, ephemeris)?
+        .into_iter()
+        .find(|month| month.number == 1 && !month.leap)
+        .map(|month| month.first_date())
+}
+
+// The Chinese date at `secs` (unix seconds), on the day in Beijing time, see
+// `lunar_months`.
+pub fn chinese_date<E: Ephemeris + ?Sized>(secs: f64, ephemeris: &E) -> Option<ChineseDate> {
+    let day = local_day(secs);
+    let mut year = civil_from_days(day).0 as i32;
+    let mut months = lunar_months(year, ephemeris)?;
+    if day < months[0].first_day {
+        year -= 1;
+        months = lunar_months(year, ephemeris)?;
+    }
+    let month = months.iter().rev().find(|month| month.first_day <= day)?;
+    Some(ChineseDate {
+        year,
+        month: month.number,
+        leap: month.leap,
+        day: (day - month.first_day) as u32 + 1,
+    })
+}
+
+// The zodiac year at `secs` (unix seconds), changing at Lunar New Year.
+pub fn chinese_zodiac<E: Ephemeris + ?Sized>(secs: f64, ephemeris: &E) -> Option<ChineseZodiac> {
+    chinese_date(secs, ephemeris).map(|date| date.zodiac())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    #[test]
+    fn new_years() {
+        let dates = [
+            (2020, (2020, 1, 25)),
+            (2022, (2022, 2, 1)),
+            (2023, (2023, 1, 22)),
+            (2033, (2033, 1, 31)),
+            (2034, (2034, 2, 19)),
+        ];
+        for (year, exp) in dates.iter() {
+            assert_eq!(new_year(*year, &Meeus), Some(*exp), "Failed for {}", year);
+        }
+    }
+
+    #[test]
+    fn leap_months() {
+        // 2023 has a leap second month from 2023-03-22.
+        let months = lunar_months(2023, &Meeus).unwrap();
+        assert_eq!(months.len(), 13);
+        let leap = months.iter().find(|month| month.leap).unwrap();
+        assert_eq!((leap.number, leap.first_date()), (2, (2023, 3, 22)));
+        for pair in months.windows(2) {
+            assert_eq!(pair[0].first_day + pair[0].days as i64, pair[1].first_day);
+            assert!(pair[0].days == 29 || pair[0].days == 30);
+        }
+        // And 2033 the famous leap eleventh month.
+        let leap = lunar_months(2033, &Meeus).unwrap().into_iter().find(|month| month.leap).unwrap();
+        assert_eq!(leap.number, 11);
+        assert_eq!(lunar_months(2022, &Meeus).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn chinese_dates() {
+        // 2022-01-31T16:00:00+00:00, midnight of New Year in Beijing.
+        let date = chinese_date(1643644800., &Meeus).unwrap();
+        assert_eq!(date, ChineseDate { year: 2022, month: 1, leap: false, day: 1 });
+        let date = chinese_date(1643644799., &Meeus).unwrap();
+        assert_eq!(date, ChineseDate { year: 2021, month: 12, leap: false, day: 29 });
+    }
+
+    #[test]
+    fn zodiac() {
+        assert_eq!(ChineseZodiac::from_year(2020).to_string(), "Metal Rat");
+        assert_eq!(ChineseZodiac::from_year(1984).to_string(), "Wood Rat");
+        assert_eq!(ChineseZodiac::from_year(2024).to_string(), "Wood Dragon");
+        assert_eq!(ChineseZodiac::from_year(1900).to_string(), "Metal Rat");
+        // Astronomical year numbering, this is 3 BC.
+        assert_eq!(ChineseZodiac::from_year(-2).to_string(), "Earth Horse");
+        // The Metal Ox lasted until 2022-01-31 in Beijing.
+        assert_eq!(chinese_zodiac(1643644799., &Meeus).unwrap().to_string(), "Metal Ox");
+        let water_tiger = ChineseZodiac {
+            animal: Animal::Tiger,
+            element: Element::Water,
+        };
+        assert_eq!(chinese_zodiac(1643644800., &Meeus), Some(water_tiger));
+    }
+
+    // A sun that never reaches the winter solstice.
+    struct StillSun;
+
+    impl Ephemeris for StillSun {
+        fn moon(&self, j_date: f64) -> crate::ephemeris::MoonPosition {
+            Meeus.moon(j_date)
+        }
+
+        fn sun_longitude(&self, _j_date: f64) -> f64 {
+            90.
+        }
+    }
+
+    #[test]
+    fn no_solstice() {
+        assert_eq!(new_year(2022, &StillSun), None);
+        assert_eq!(lunar_months(2022, &StillSun), None);
+        assert_eq!(chinese_date(1643644800., &StillSun), None);
+        assert_eq!(chinese_zodiac(1643644800., &StillSun), None);
+    }
+}
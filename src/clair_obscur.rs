@@ -0,0 +1,106 @@
+// Clair-obscur effects: light and shadow on the terminator briefly forming
+// shapes, each at a certain colongitude of the sun (see `selenographic`).
+// The colongitudes are those reported by observers and good to a few
+// tenths of a degree, the colongitude moves about half a degree an hour.
+// Whether an event can be seen from a place depends on the moon being up
+// there at the time.
+
+use crate::elements::MOON_SYNODIC_PERIOD;
+use crate::events::zero_crossings;
+use crate::MoonPhase;
+
+// The colongitude moves about 3 degrees in that time.
+const STEP_SECS: f64 = 6. * 3600.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ClairObscur {
+    LunarX,  // Rims of Blanchinus, La Caille and Purbach forming an X
+    LunarV,  // Ukert and its neighbours forming a V, at about the same time
+    JewelledHandle,  // Montes Jura lit around the dark Sinus Iridum, a few days later
+}
+
+impl ClairObscur {
+    pub const ALL: [ClairObscur; 3] = [ClairObscur::LunarX, ClairObscur::LunarV, ClairObscur::JewelledHandle];
+
+    // Colongitudes in degrees from which on and until which the effect is
+    // seen. The end may wrap around past 360.
+    pub fn colongitudes(self) -> (f64, f64) {
+        match self {
+            ClairObscur::LunarX => (358.0, 360.0),
+            ClairObscur::LunarV => (358.4, 360.4),
+            ClairObscur::JewelledHandle => (30.0, 34.0),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClairObscurEvent {
+    pub effect: ClairObscur,
+    pub start: f64,  // Unix seconds
+    pub end: f64,    // Unix seconds
+}
+
+// The times `effect` is seen in from..until (unix seconds), in order. Events
+// overlapping either end are included whole.
+pub fn clair_obscur_events(effect: ClairObscur, from: f64, until: f64) -> Vec<ClairObscurEvent> {
+    let (start_colongitude, end_colongitude) = effect.colongitudes();
+    let colongitude = |secs| MoonPhase::from_secs_float(secs).colongitude();
+    // Starts up to a lunation back can still be running at `from`.
+    let search_from = from - MOON_SYNODIC_PERIOD * 86400.;
+    zero_crossings(|secs| colongitude(secs) - start_colongitude, search_from, until, STEP_SECS)
+        .into_iter()
+        .filter_map(|start| {
+            let end = *zero_crossings(|secs| colongitude(secs) - end_colongitude, start, start + 2. * 86400., STEP_SECS)
+                .first()?;
+            Some(ClairObscurEvent { effect, start, end })
+        })
+        .filter(|event| event.end > from)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lunar_x() {
+        // Seen in the hours before the first quarter of 2022-01-09T18:11:00+00:00
+        // and about four hours long.
+        let events = clair_obscur_events(ClairObscur::LunarX, 1641340800., 1643760000.);
+        assert_eq!(events.len(), 1, "Failed for {:?}", events);
+        let event = events[0];
+        let hours = (event.end - event.start) / 3600.;
+        assert!((3.0..5.).contains(&hours), "Failed for {}", hours);
+        let days = (event.start - 1641751860.) / 86400.;
+        assert!(days.abs() < 1.5, "Failed for {}", days);
+        let colongitude = MoonPhase::from_secs_float(event.start).colongitude();
+        assert!((colongitude - 358.).abs() < 0.01, "Failed for {}", colongitude);
+
+        // The V shows at about the same time.
+        let lunar_v = clair_obscur_events(ClairObscur::LunarV, 1641340800., 1643760000.)[0];
+        assert!(lunar_v.start > event.start && lunar_v.start < event.end, "Failed for {:?}", lunar_v);
+
+        // A window starting in the middle of the event still has it.
+        let middle = (event.start + event.end) / 2.;
+        let found = clair_obscur_events(ClairObscur::LunarX, middle, middle + 3600.);
+        assert_eq!(found.len(), 1, "Failed for {:?}", found);
+        assert!((found[0].start - event.start).abs() < 60., "Failed for {:?}", found);
+    }
+
+    #[test]
+    fn jewelled_handle() {
+        // Once a lunation, two or three days after the first quarter of
+        // 2022-01-09T18:11:00+00:00 and for the better part of a night.
+        let events = clair_obscur_events(ClairObscur::JewelledHandle, 1641340800., 1643760000.);
+        assert_eq!(events.len(), 1, "Failed for {:?}", events);
+        let event = events[0];
+        let days = (event.start - 1641751860.) / 86400.;
+        assert!((2.0..3.5).contains(&days), "Failed for {}", days);
+        let hours = (event.end - event.start) / 3600.;
+        assert!((7.0..9.).contains(&hours), "Failed for {}", hours);
+
+        // Every lunation over a year.
+        let year = clair_obscur_events(ClairObscur::JewelledHandle, 1640995200., 1672531200.);
+        assert!((12..=13).contains(&year.len()), "Failed for {:?}", year);
+    }
+}
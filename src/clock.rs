@@ -0,0 +1,84 @@
+// Source of the current time for `MoonPhase::now` and friends, so tests and
+// simulations can substitute their own.
+
+use crate::MoonPhase;
+
+pub trait Clock {
+    // Current time in unix seconds.
+    fn now(&self) -> f64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> f64 {
+        (**self).now()
+    }
+}
+
+// The system's wall clock.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    // Read through std, so chrono doesn't need its `clock` feature.
+    #[cfg(not(all(feature="wasm", target_arch="wasm32")))]
+    fn now(&self) -> f64 {
+        use std::time::SystemTime;
+        use crate::unix_seconds;
+
+        #[cfg(feature="chrono")]
+        let time = chrono::DateTime::<chrono::Utc>::from(SystemTime::now());
+        #[cfg(not(feature="chrono"))]
+        let time = SystemTime::now();
+        unix_seconds(time)
+    }
+
+    // std has no clock on wasm32-unknown-unknown, JavaScript's is used.
+    #[cfg(all(feature="wasm", target_arch="wasm32"))]
+    fn now(&self) -> f64 {
+        js_sys::Date::now() / 1000.
+    }
+}
+
+// A clock standing still at the given unix seconds.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FixedClock(pub f64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> f64 {
+        self.0
+    }
+}
+
+impl MoonPhase {
+    pub fn now_with<C: Clock + ?Sized>(clock: &C) -> Self {
+        Self::from_secs_float(clock.now())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn fixed_clock() {
+        assert_eq!(MoonPhase::now_with(&FixedClock(1642291200.)), MoonPhase::from_secs(1642291200));
+    }
+
+    #[test]
+    fn custom_clock() {
+        struct Ticking(Cell<f64>);
+        impl Clock for Ticking {
+            fn now(&self) -> f64 {
+                let now = self.0.get();
+                self.0.set(now + 86400.);
+                now
+            }
+        }
+
+        let clock = Ticking(Cell::new(1642291200.));
+        let clock: &dyn Clock = &clock;
+        assert_eq!(MoonPhase::now_with(clock), MoonPhase::from_secs(1642291200));
+        assert_eq!(MoonPhase::now_with(clock), MoonPhase::from_secs(1642291200 + 86400));
+    }
+}
@@ -0,0 +1,110 @@
+// Column oriented counterpart of `Vec<MoonPhase>`, one vector per field, for
+// feeding dataframes without transposing first.
+
+use crate::{MoonPhase, Phase, Zodiac};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoonPhaseColumns {
+    pub j_date: Vec<f64>,
+    pub phase: Vec<f64>,
+    pub age: Vec<f64>,
+    #[deprecated(note = "wrong for the default model, use `illumination` instead")]
+    pub fraction: Vec<f64>,
+    pub illumination: Vec<f64>,
+    pub distance: Vec<f64>,
+    pub latitude: Vec<f64>,
+    pub longitude: Vec<f64>,
+    pub phase_name: Vec<Phase>,
+    pub zodiac_name: Vec<Zodiac>,
+}
+
+impl MoonPhaseColumns {
+    #[allow(deprecated)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        MoonPhaseColumns {
+            j_date: Vec::with_capacity(capacity),
+            phase: Vec::with_capacity(capacity),
+            age: Vec::with_capacity(capacity),
+            fraction: Vec::with_capacity(capacity),
+            illumination: Vec::with_capacity(capacity),
+            distance: Vec::with_capacity(capacity),
+            latitude: Vec::with_capacity(capacity),
+            longitude: Vec::with_capacity(capacity),
+            phase_name: Vec::with_capacity(capacity),
+            zodiac_name: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.j_date.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.j_date.is_empty()
+    }
+
+    #[allow(deprecated)]
+    pub fn push(&mut self, moon_phase: MoonPhase) {
+        self.j_date.push(moon_phase.j_date);
+        self.phase.push(moon_phase.phase);
+        self.age.push(moon_phase.age);
+        self.fraction.push(moon_phase.fraction);
+        self.illumination.push(moon_phase.illumination);
+        self.distance.push(moon_phase.distance);
+        self.latitude.push(moon_phase.latitude);
+        self.longitude.push(moon_phase.longitude);
+        self.phase_name.push(moon_phase.phase_name);
+        self.zodiac_name.push(moon_phase.zodiac_name);
+    }
+
+    // Returns the row at `index`, if there is one.
+    #[allow(deprecated)]
+    pub fn get(&self, index: usize) -> Option<MoonPhase> {
+        Some(MoonPhase {
+            j_date: *self.j_date.get(index)?,
+            phase: self.phase[index],
+            age: self.age[index],
+            fraction: self.fraction[index],
+            illumination: self.illumination[index],
+            distance: self.distance[index],
+            latitude: self.latitude[index],
+            longitude: self.longitude[index],
+            phase_name: self.phase_name[index],
+            zodiac_name: self.zodiac_name[index],
+        })
+    }
+}
+
+impl Extend<MoonPhase> for MoonPhaseColumns {
+    fn extend<I: IntoIterator<Item = MoonPhase>>(&mut self, iter: I) {
+        for moon_phase in iter {
+            self.push(moon_phase);
+        }
+    }
+}
+
+impl std::iter::FromIterator<MoonPhase> for MoonPhaseColumns {
+    fn from_iter<I: IntoIterator<Item = MoonPhase>>(iter: I) -> Self {
+        let mut columns = MoonPhaseColumns::default();
+        columns.extend(iter);
+        columns
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let secs: Vec<i64> = (0..50).map(|i| 947182380 + i * 3600 * 11).collect();
+        let rows = MoonPhase::batch(&secs);
+        let columns = MoonPhase::batch_columns(&secs);
+        assert_eq!(columns.len(), rows.len());
+        assert_eq!(columns, rows.iter().copied().collect());
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(columns.get(i), Some(*row));
+        }
+        assert_eq!(columns.get(rows.len()), None);
+    }
+}
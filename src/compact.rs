@@ -0,0 +1,161 @@
+// The moon quantized into 13 bytes, for sensors sending it over LoRa or BLE.
+// `to_bytes` and `from_bytes` give the fixed little endian layout
+//
+//     0 - 3    i32  Minutes since 1970-01-01T00:00:00Z
+//     4 - 5    u16  Phase, 1/65536 of the cycle
+//     6 - 7    u16  Illumination, 1/65535
+//     8 - 9    u16  Distance in km above 350000
+//     10 - 11  u16  Ecliptic longitude, 360/65536 degrees
+//     12       i8   Ecliptic latitude, 1/20 degree
+//
+// With the `serde` feature `CompactMoon` goes through postcard or bincode as
+// well, at about the same size. The phase name, age and zodiac sign follow
+// from the fields and aren't stored.
+
+use crate::elements::MOON_SYNODIC_PERIOD;
+use crate::ephemeris::EARTH_RADIUS;
+use crate::{MoonPhase, Phase, Zodiac};
+
+pub const COMPACT_LEN: usize = 13;
+
+const DISTANCE_OFFSET_KM: f64 = 350000.;
+const LATITUDE_STEPS: f64 = 20.;  // Per degree
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompactMoon {
+    pub minutes: i32,
+    pub phase: u16,
+    pub illumination: u16,
+    pub distance: u16,
+    pub longitude: u16,
+    pub latitude: i8,
+}
+
+impl CompactMoon {
+    pub fn to_bytes(&self) -> [u8; COMPACT_LEN] {
+        let mut bytes = [0; COMPACT_LEN];
+        bytes[0..4].copy_from_slice(&self.minutes.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.phase.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.illumination.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.distance.to_le_bytes());
+        bytes[10..12].copy_from_slice(&self.longitude.to_le_bytes());
+        bytes[12] = self.latitude as u8;
+        bytes
+    }
+
+    // None if `bytes` isn't `COMPACT_LEN` long.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != COMPACT_LEN {
+            return None;
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        Some(CompactMoon {
+            minutes: i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            phase: u16_at(4),
+            illumination: u16_at(6),
+            distance: u16_at(8),
+            longitude: u16_at(10),
+            latitude: bytes[12] as i8,
+        })
+    }
+
+    // Unix seconds, to the minute.
+    pub fn secs(&self) -> f64 {
+        self.minutes as f64 * 60.
+    }
+
+    // 0 - 1, 0.5 = full
+    pub fn phase(&self) -> f64 {
+        self.phase as f64 / 65536.
+    }
+
+    // 0 - 1
+    pub fn illumination(&self) -> f64 {
+        self.illumination as f64 / 65535.
+    }
+
+    pub fn distance_km(&self) -> f64 {
+        self.distance as f64 + DISTANCE_OFFSET_KM
+    }
+
+    // Ecliptic, degrees
+    pub fn longitude(&self) -> f64 {
+        self.longitude as f64 * 360. / 65536.
+    }
+
+    // Ecliptic, degrees
+    pub fn latitude(&self) -> f64 {
+        self.latitude as f64 / LATITUDE_STEPS
+    }
+
+    // Days
+    pub fn age(&self) -> f64 {
+        self.phase() * MOON_SYNODIC_PERIOD
+    }
+
+    pub fn phase_name(&self) -> Phase {
+        Phase::from_cycle_fraction(self.phase())
+    }
+
+    pub fn zodiac(&self) -> Zodiac {
+        Zodiac::from_long(self.longitude())
+    }
+}
+
+impl From<&MoonPhase> for CompactMoon {
+    fn from(moon_phase: &MoonPhase) -> Self {
+        let secs = (moon_phase.j_date - 2440587.5) * 86400.;
+        // Float to integer casts saturate, out of range values end up at
+        // the nearest end.
+        CompactMoon {
+            minutes: (secs / 60.).round() as i32,
+            phase: (moon_phase.phase.rem_euclid(1.) * 65536.).round() as u32 as u16,
+            illumination: (moon_phase.illumination * 65535.).round() as u16,
+            distance: (moon_phase.distance * EARTH_RADIUS - DISTANCE_OFFSET_KM).round() as u16,
+            longitude: (moon_phase.longitude.rem_euclid(360.) * 65536. / 360.).round() as u32 as u16,
+            latitude: (moon_phase.latitude * LATITUDE_STEPS).round() as i8,
+        }
+    }
+}
+
+impl MoonPhase {
+    pub fn to_compact(&self) -> CompactMoon {
+        CompactMoon::from(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for secs in (1577836800..1893456000).step_by(86400 * 37 + 3607) {
+            let moon_phase = MoonPhase::from_secs(secs);
+            let compact = moon_phase.to_compact();
+            assert_eq!(CompactMoon::from_bytes(&compact.to_bytes()), Some(compact));
+
+            assert!((compact.secs() - secs as f64).abs() <= 30., "Failed for {}", secs);
+            let phase_diff = (compact.phase() - moon_phase.phase + 0.5).rem_euclid(1.) - 0.5;
+            assert!(phase_diff.abs() < 1e-4, "Failed for {}", secs);
+            assert!((compact.illumination() - moon_phase.illumination).abs() < 1e-4, "Failed for {}", secs);
+            assert!((compact.distance_km() - moon_phase.distance * EARTH_RADIUS).abs() <= 0.5, "Failed for {}", secs);
+            let longitude_diff = (compact.longitude() - moon_phase.longitude + 180.).rem_euclid(360.) - 180.;
+            assert!(longitude_diff.abs() < 0.01, "Failed for {}", secs);
+            assert!((compact.latitude() - moon_phase.latitude).abs() <= 0.025, "Failed for {}", secs);
+            assert!((compact.age() - moon_phase.age).abs() < 0.01, "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn bytes() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let compact = MoonPhase::from_secs(1642463280).to_compact();
+        assert_eq!(compact.minutes, 27374388);
+        assert_eq!(compact.phase_name(), Phase::Full);
+        assert_eq!(compact.to_bytes()[0..4], 27374388i32.to_le_bytes());
+        assert_eq!(CompactMoon::from_bytes(&[0; 12]), None);
+        assert_eq!(CompactMoon::from_bytes(&[0; 14]), None);
+    }
+}
@@ -0,0 +1,151 @@
+// The date of Easter from the ecclesiastical moon, a table of lunations
+// repeating every 19 years (the Metonic cycle) that only roughly follows the
+// real moon. Easter is the first Sunday after the paschal full moon, the 14th
+// day of the moon on or after 21 March. The Gregorian reform corrected the
+// table for the solar and lunar drift, the Orthodox churches still use the
+// Julian one. After the algorithms in chapter 8 of Jean Meeus,
+// "Astronomical Algorithms".
+
+use crate::calendar::{civil_from_days, days_from_civil};
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Reckoning {
+    #[default]
+    Gregorian,
+    Julian,
+}
+
+// Position of `year` in the Metonic cycle, 1 - 19.
+pub fn golden_number(year: i32) -> u32 {
+    year.rem_euclid(19) as u32 + 1
+}
+
+// Days from 21 March to the paschal full moon, not yet moved back for the
+// Gregorian exceptions.
+fn full_moon_offset(year: i32, reckoning: Reckoning) -> i32 {
+    let a = year.rem_euclid(19);
+    match reckoning {
+        Reckoning::Gregorian => {
+            let b = year.div_euclid(100);
+            let d = b / 4;
+            let f = (b + 8) / 25;
+            let g = (b - f + 1) / 3;
+            (19 * a + b - d - g + 15).rem_euclid(30)
+        }
+        Reckoning::Julian => (19 * a + 15).rem_euclid(30),
+    }
+}
+
+// Age of the ecclesiastical moon at the start of the year, 0 - 29.
+pub fn epact(year: i32, reckoning: Reckoning) -> u32 {
+    (23 - full_moon_offset(year, reckoning)).rem_euclid(30) as u32
+}
+
+// Days since 1970-01-01 of a date of the Julian calendar.
+fn days_from_julian(year: i32, month: u32, day: u32) -> i64 {
+    let (year, month) = if month <= 2 {
+        (year as i64 - 1, month as i64 + 12)
+    } else {
+        (year as i64, month as i64)
+    };
+    let julian_day = (1461 * (year + 4716)).div_euclid(4) + (153 * (month + 1)) / 5 + day as i64 - 1524;
+    julian_day - 2440588
+}
+
+// Days since 1970-01-01 of the paschal full moon.
+fn full_moon_day(year: i32, reckoning: Reckoning) -> i64 {
+    let offset = full_moon_offset(year, reckoning);
+    match reckoning {
+        Reckoning::Gregorian => {
+            // Keeps Easter from falling after 25 April.
+            let a = year.rem_euclid(19);
+            let offset = if offset == 29 || (offset == 28 && a > 10) { offset - 1 } else { offset };
+            days_from_civil(year as i64, 3, 21) + offset as i64
+        }
+        Reckoning::Julian => days_from_julian(year, 3, 21) + offset as i64,
+    }
+}
+
+fn to_date(days: i64) -> (i32, u32, u32) {
+    let (year, month, day) = civil_from_days(days);
+    (year as i32, month, day)
+}
+
+// The paschal full moon of `year`. The date is always in the (proleptic)
+// Gregorian calendar, also for the Julian reckoning.
+pub fn paschal_full_moon(year: i32, reckoning: Reckoning) -> (i32, u32, u32) {
+    to_date(full_moon_day(year, reckoning))
+}
+
+// Easter Sunday of `year` in the Gregorian calendar, as kept by the Western
+// churches.
+pub fn easter_date(year: i32) -> (i32, u32, u32) {
+    easter_date_with(year, Reckoning::Gregorian)
+}
+
+// Easter Sunday of `year` by the Julian reckoning of the Orthodox churches,
+// given in the Gregorian calendar.
+pub fn julian_easter_date(year: i32) -> (i32, u32, u32) {
+    easter_date_with(year, Reckoning::Julian)
+}
+
+pub fn easter_date_with(year: i32, reckoning: Reckoning) -> (i32, u32, u32) {
+    let full_moon = full_moon_day(year, reckoning);
+    // 1970-01-01 was a Thursday, so Sundays are 3 modulo 7.
+    let days_to_sunday = (3 - full_moon).rem_euclid(7);
+    let days_to_sunday = if days_to_sunday == 0 { 7 } else { days_to_sunday };
+    to_date(full_moon + days_to_sunday)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gregorian() {
+        let dates = [
+            (1818, (1818, 3, 22)),
+            (1943, (1943, 4, 25)),
+            (2000, (2000, 4, 23)),
+            (2019, (2019, 4, 21)),
+            (2024, (2024, 3, 31)),
+            (2025, (2025, 4, 20)),
+            (2038, (2038, 4, 25)),
+            (2285, (2285, 3, 22)),
+        ];
+        for (year, exp) in dates.iter() {
+            assert_eq!(easter_date(*year), *exp, "Failed for {}", year);
+        }
+    }
+
+    #[test]
+    fn julian() {
+        let dates = [
+            (2022, (2022, 4, 24)),
+            (2023, (2023, 4, 16)),
+            (2024, (2024, 5, 5)),
+            (2025, (2025, 4, 20)),
+        ];
+        for (year, exp) in dates.iter() {
+            assert_eq!(julian_easter_date(*year), *exp, "Failed for {}", year);
+        }
+        assert_eq!(days_from_julian(1582, 10, 5), days_from_civil(1582, 10, 15));
+    }
+
+    #[test]
+    fn lunar_calendar() {
+        assert_eq!(golden_number(2000), 6);
+        // One Metonic cycle later, the lunar calendar repeats.
+        assert_eq!(golden_number(2019), 6);
+        assert_eq!(epact(2000, Reckoning::Gregorian), 24);
+        assert_eq!(epact(2019, Reckoning::Gregorian), 24);
+        assert_eq!(epact(2001, Reckoning::Gregorian), 5);
+        assert_eq!(paschal_full_moon(2000, Reckoning::Gregorian), (2000, 4, 18));
+        // The ecclesiastical full moon of 2019-03-20, a day before the real
+        // one, was too early to count.
+        assert_eq!(paschal_full_moon(2019, Reckoning::Gregorian), (2019, 4, 18));
+        // Julian epacts are 11 days apart, 8 with the golden number 1.
+        assert_eq!(epact(1995, Reckoning::Julian), 8);
+        assert_eq!(epact(1996, Reckoning::Julian), 19);
+    }
+}
@@ -0,0 +1,117 @@
+// The constellations the ecliptic passes through according to the official
+// IAU boundaries, which include Ophiuchus. Only the crossing points of the
+// boundaries with the ecliptic are used, so a moon far from the ecliptic may
+// actually stand in a neighbouring constellation (e.g. Orion or Cetus).
+
+use crate::zodiac::{precession, ZodiacScheme};
+use crate::MoonPhase;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Constellation {
+    Pisces,
+    Aries,
+    Taurus,
+    Gemini,
+    Cancer,
+    Leo,
+    Virgo,
+    Libra,
+    Scorpius,
+    Ophiuchus,
+    Sagittarius,
+    Capricornus,
+    Aquarius,
+}
+
+// Ecliptic longitudes (J2000) at which the constellations end. Pisces,
+// which spans the equinox, is the part after the last end.
+const BOUNDARIES: [(f64, Constellation); 13] = [
+    (28.687, Constellation::Pisces),
+    (53.417, Constellation::Aries),
+    (90.140, Constellation::Taurus),
+    (117.988, Constellation::Gemini),
+    (138.038, Constellation::Cancer),
+    (173.851, Constellation::Leo),
+    (217.810, Constellation::Virgo),
+    (241.047, Constellation::Libra),
+    (247.638, Constellation::Scorpius),
+    (266.238, Constellation::Ophiuchus),
+    (299.656, Constellation::Sagittarius),
+    (327.488, Constellation::Capricornus),
+    (351.650, Constellation::Aquarius),
+];
+
+const SCHEME: ZodiacScheme<Constellation> = ZodiacScheme::from_static(&BOUNDARIES);
+
+impl Constellation {
+    // For an ecliptic longitude in degrees, referred to the J2000 equinox.
+    pub fn from_long_j2000(longitude: f64) -> Self {
+        SCHEME.find(longitude)
+    }
+
+    // The constellations as a `ZodiacScheme` for the equinox of the given
+    // Julian date, e.g. to find the times the moon enters the next one.
+    // The boundaries move by about 1.4 degrees a century.
+    pub fn scheme(j_date: f64) -> ZodiacScheme<Constellation> {
+        let shift = precession(j_date);
+        let mut ends: Vec<_> = BOUNDARIES
+            .iter()
+            .map(|(end, constellation)| ((end + shift).rem_euclid(360.), *constellation))
+            .collect();
+        ends.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        ZodiacScheme::new(ends).unwrap()
+    }
+}
+
+impl MoonPhase {
+    pub fn constellation(&self) -> Constellation {
+        Constellation::from_long_j2000(self.longitude - precession(self.j_date))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_long_j2000() {
+        assert_eq!(Constellation::from_long_j2000(0.), Constellation::Pisces);
+        assert_eq!(Constellation::from_long_j2000(30.), Constellation::Aries);
+        assert_eq!(Constellation::from_long_j2000(245.), Constellation::Scorpius);
+        assert_eq!(Constellation::from_long_j2000(255.), Constellation::Ophiuchus);
+        assert_eq!(Constellation::from_long_j2000(355.), Constellation::Pisces);
+        assert_eq!(Constellation::from_long_j2000(-10.), Constellation::Aquarius);
+    }
+
+    #[test]
+    fn moon_phase_constellation() {
+        // 2022-01-16T00:00:00+00:00, 22 years of precession after J2000.
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        let longitude = moon_phase.longitude - precession(moon_phase.j_date);
+        assert!((precession(moon_phase.j_date) - 0.307).abs() < 1e-3);
+        assert_eq!(moon_phase.constellation(), Constellation::from_long_j2000(longitude));
+    }
+
+    #[test]
+    fn scheme() {
+        let j2000 = Constellation::scheme(2451545.);
+        for longitude in 0..720 {
+            let longitude = longitude as f64 / 2. + 0.25;
+            assert_eq!(j2000.find(longitude), Constellation::from_long_j2000(longitude), "Failed for {}", longitude);
+        }
+        // Far enough from J2000 that Aquarius ends after the equinox.
+        let scheme = Constellation::scheme(2451545. + 10. * 36525.);
+        assert_eq!(scheme.find(0.5), Constellation::Aquarius);
+        assert_eq!(scheme.find(20.), Constellation::Pisces);
+
+        use crate::algorithm::Meeus;
+        // A month from 2022-01-01T00:00:00+00:00, the moon passes all of them.
+        let from = 1640995200.;
+        let scheme = Constellation::scheme(crate::julian_date_from_seconds(from));
+        let ingresses = scheme.ingresses(from, from + 27.3 * 86400., &Meeus);
+        assert_eq!(ingresses.len(), 13);
+        for pair in ingresses.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from);
+        }
+    }
+}
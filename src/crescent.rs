@@ -0,0 +1,390 @@
+// Whether the young crescent can be seen after sunset, after B. D. Yallop,
+// "A Method for Predicting the First Sighting of the New Crescent Moon"
+// (NAO Technical Note 69, 1997) and M. Odeh, "New Criterion for Lunar
+// Crescent Visibility" (Experimental Astronomy 18, 2004). Both compare the
+// altitude difference of sun and moon against the width of the crescent at
+// the best time, 4/9 of the way from sunset to moonset. The old crescent in
+// the morning is judged the same, from sunrise back to moonrise.
+
+use crate::ephemeris::Ephemeris;
+use crate::events::{new_moons, zero_crossings};
+use crate::hijri::MonthStart;
+use crate::observer::{Observer, Twilight};
+use crate::photography::MOON_RADIUS_RATIO;
+use crate::sun::SunPosition;
+use crate::{julian_date_from_seconds, MoonPhase};
+
+// Altitude of the center at rise and set, for refraction and semidiameter.
+const HORIZON: f64 = -0.833;
+const STEP_SECS: f64 = 600.;
+// Evenings checked after a new moon before the month starts anyway.
+const MAX_EVENINGS: i64 = 3;
+// Mornings and evenings searched on either side of a new moon, long enough
+// for high latitudes.
+const MAX_PLANNED_DAYS: i64 = 5;
+// Least elongation in degrees at which any crescent has been seen, after
+// A. Danjon (1932). Closer to the sun the lit part is too thin to show.
+pub const DANJON_LIMIT: f64 = 7.;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Criterion {
+    #[default]
+    Yallop,
+    Odeh,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Visibility {
+    NotVisible,
+    OpticalAid,  // Only with binoculars or a telescope, at least to find it
+    NakedEye,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Crescent {
+    pub twilight: Twilight,
+    pub sun_horizon: f64,           // Unix seconds of sunset in the evening, sunrise in the morning
+    pub moon_horizon: Option<f64>,  // Unix seconds of moonset in the evening, moonrise in the morning. None if the moon is down
+    pub best_time: f64,             // Unix seconds
+    pub arcl: f64,                  // Elongation of the moon from the sun in degrees
+    pub arcv: f64,                  // Altitude of the moon above the sun in degrees
+    pub daz: f64,                   // Azimuth of the sun minus that of the moon in degrees
+    pub width: f64,                 // Width of the crescent in arc minutes
+    pub value: f64,                 // Yallop's q or Odeh's V
+    pub visibility: Visibility,
+}
+
+impl MoonPhase {
+    // Geocentric angle between the moon and the sun in degrees, 0 - 180.
+    pub fn elongation(&self) -> f64 {
+        self.elongation_from(SunPosition::new(self.j_date).longitude)
+    }
+
+    // Same as `elongation`, with the sun at `sun_longitude` (degrees).
+    fn elongation_from(&self, sun_longitude: f64) -> f64 {
+        (self.latitude.to_radians().cos() * (self.longitude - sun_longitude).to_radians().cos())
+            .clamp(-1., 1.)
+            .acos()
+            .to_degrees()
+    }
+
+    // Whether the moon is far enough from the sun for a crescent to be seen
+    // at all, with whatever sky and optics. Passing says nothing about a
+    // particular evening, see `crescent` for that.
+    pub fn beyond_danjon_limit(&self) -> bool {
+        self.elongation() > DANJON_LIMIT
+    }
+
+    // Geocentric width of the lit part of the disk across its middle, in arc
+    // minutes: nothing at new moon, the full diameter at full moon. Taken
+    // from the elongation of the moon from the sun, 180 degrees less the
+    // phase angle, which is more exact for thin crescents than
+    // `illumination`. Over the diameter it gives the share of a drawn disk to
+    // light.
+    pub fn crescent_width(&self) -> f64 {
+        self.semidiameter() / 60. * (1. - self.elongation().to_radians().cos())
+    }
+}
+
+// The crescent on the evening of `day` (days since 1970-01-01) as seen by
+// `observer`. Returns None if the sun does not set that day.
+pub fn crescent<E: Ephemeris + ?Sized>(
+    observer: &Observer,
+    day: i64,
+    criterion: Criterion,
+    ephemeris: &E,
+) -> Option<Crescent> {
+    crescent_in(observer, day, Twilight::Evening, criterion, ephemeris)
+}
+
+// The old crescent on the morning of `day` before sunrise, the same criteria
+// mirrored. Returns None if the sun does not rise that day.
+pub fn morning_crescent<E: Ephemeris + ?Sized>(
+    observer: &Observer,
+    day: i64,
+    criterion: Criterion,
+    ephemeris: &E,
+) -> Option<Crescent> {
+    crescent_in(observer, day, Twilight::Morning, criterion, ephemeris)
+}
+
+fn crescent_in<E: Ephemeris + ?Sized>(
+    observer: &Observer,
+    day: i64,
+    twilight: Twilight,
+    criterion: Criterion,
+    ephemeris: &E,
+) -> Option<Crescent> {
+    let sun_altitude = |secs| {
+        let j_date = julian_date_from_seconds(secs);
+        observer.horizontal(j_date, ephemeris.sun_longitude(j_date), 0.).0
+    };
+    let moon_altitude = |secs| {
+        let moon_phase = observer.topocentric(&MoonPhase::from_secs_float_with_ephemeris(secs, ephemeris));
+        observer.horizontal(moon_phase.j_date, moon_phase.longitude, moon_phase.latitude).0
+    };
+
+    // Local noon from the observer's longitude.
+    let noon = day as f64 * 86400. + 43200. - observer.longitude * 240.;
+    if sun_altitude(noon) < HORIZON {
+        return None;
+    }
+    // Sunset and moonset in the evening, sunrise and moonrise in the morning.
+    let (sun_horizon, moon_horizon) = match twilight {
+        Twilight::Evening => {
+            let sunset = *zero_crossings(|secs| sun_altitude(secs) - HORIZON, noon, noon + 43200., STEP_SECS).first()?;
+            let moonset = if moon_altitude(sunset) > HORIZON {
+                zero_crossings(|secs| moon_altitude(secs) - HORIZON, sunset, sunset + 86400., STEP_SECS)
+                    .first()
+                    .copied()
+            } else {
+                None
+            };
+            (sunset, moonset)
+        }
+        Twilight::Morning => {
+            let sunrise = *zero_crossings(|secs| sun_altitude(secs) - HORIZON, noon - 43200., noon, STEP_SECS).last()?;
+            let moonrise = if moon_altitude(sunrise) > HORIZON {
+                zero_crossings(|secs| moon_altitude(secs) - HORIZON, sunrise - 86400., sunrise, STEP_SECS)
+                    .last()
+                    .copied()
+            } else {
+                None
+            };
+            (sunrise, moonrise)
+        }
+    };
+    let best_time = moon_horizon.map_or(sun_horizon, |moon| sun_horizon + 4. / 9. * (moon - sun_horizon));
+
+    let moon_phase = MoonPhase::from_secs_float_with_ephemeris(best_time, ephemeris);
+    let topocentric = observer.topocentric(&moon_phase);
+    // Yallop uses the geocentric moon, except for the width.
+    let moon = match criterion {
+        Criterion::Yallop => &moon_phase,
+        Criterion::Odeh => &topocentric,
+    };
+    let j_date = moon_phase.j_date;
+    let sun_longitude = ephemeris.sun_longitude(j_date);
+    let (sun_altitude, sun_azimuth) = observer.horizontal(j_date, sun_longitude, 0.);
+    let (moon_altitude, moon_azimuth) = observer.horizontal(j_date, moon.longitude, moon.latitude);
+    let (topo_altitude, _) = observer.horizontal(j_date, topocentric.longitude, topocentric.latitude);
+    let arcl = moon.elongation_from(sun_longitude);
+    let arcv = moon_altitude - sun_altitude;
+    let daz = sun_azimuth - moon_azimuth;
+
+    // Semidiameter of the moon in arc minutes, as seen by the observer.
+    let parallax = (1. / moon_phase.distance).asin();
+    let semidiameter = MOON_RADIUS_RATIO * parallax.to_degrees() * 60. * (1. + topo_altitude.to_radians().sin() * parallax.sin());
+    let width = semidiameter * (1. - arcl.to_radians().cos());
+
+    let limit = 0.7319 * width * width - 0.1018 * width * width * width - 6.3226 * width;
+    let (value, visibility) = match criterion {
+        Criterion::Yallop => {
+            let q = (arcv - (11.8371 + limit)) / 10.;
+            let visibility = if q > -0.014 {
+                Visibility::NakedEye
+            } else if q > -0.232 {
+                Visibility::OpticalAid
+            } else {
+                Visibility::NotVisible
+            };
+            (q, visibility)
+        }
+        Criterion::Odeh => {
+            let v = arcv - (7.1651 + limit);
+            let visibility = if v >= 5.65 {
+                Visibility::NakedEye
+            } else if v >= -0.96 {
+                Visibility::OpticalAid
+            } else {
+                Visibility::NotVisible
+            };
+            (v, visibility)
+        }
+    };
+    Some(Crescent {
+        twilight,
+        sun_horizon,
+        moon_horizon,
+        best_time,
+        arcl,
+        arcv,
+        daz,
+        width,
+        value,
+        visibility: if moon_horizon.is_some() { visibility } else { Visibility::NotVisible },
+    })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SightingPlan {
+    pub new_moon: f64,                    // Unix seconds
+    pub last_morning: Option<Crescent>,   // The last morning before the new moon the old crescent is seen
+    pub first_evening: Option<Crescent>,  // The first evening after it the young crescent is seen
+}
+
+// When to look for the thinnest crescents around the first new moon after
+// `from` (unix seconds): the last old crescent before it and the first young
+// one after, each at least `visibility`. None at the end of the valid range.
+pub fn sighting_plan<E: Ephemeris + ?Sized>(
+    observer: &Observer,
+    from: f64,
+    criterion: Criterion,
+    visibility: Visibility,
+    ephemeris: &E,
+) -> Option<SightingPlan> {
+    let new_moon = *new_moons(from, from + 31. * 86400., ephemeris).first()?;
+    // The observer's day of the new moon, going by the sun.
+    let day = ((new_moon + observer.longitude * 240.) / 86400.).floor() as i64;
+    let seen = |crescent: &Crescent| crescent.visibility >= visibility;
+    let last_morning = (day - MAX_PLANNED_DAYS..=day)
+        .rev()
+        .filter_map(|day| morning_crescent(observer, day, criterion, ephemeris))
+        .find(|crescent| crescent.best_time < new_moon && seen(crescent));
+    let first_evening = (day..=day + MAX_PLANNED_DAYS)
+        .filter_map(|day| crescent(observer, day, criterion, ephemeris))
+        .find(|crescent| crescent.best_time > new_moon && seen(crescent));
+    Some(SightingPlan {
+        new_moon,
+        last_morning,
+        first_evening,
+    })
+}
+
+// A `MonthStart` rule for the Hijri calendar: the month begins on the day
+// after the first evening the crescent is predicted to be seen by `observer`,
+// or after the third evening if it is not.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sighting<E> {
+    pub observer: Observer,
+    pub criterion: Criterion,
+    pub visibility: Visibility,  // Least visibility that counts as a sighting
+    pub utc_offset: i32,         // Seconds ahead of UTC of the observer's calendar
+    pub ephemeris: E,
+}
+
+impl<E: Ephemeris> MonthStart for Sighting<E> {
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn first_day(&self, new_moon: f64) -> i64 {
+        let day = ((new_moon + self.utc_offset as f64) / 86400.).floor() as i64;
+        (day..day + MAX_EVENINGS)
+            .find(|day| {
+                crescent(&self.observer, *day, self.criterion, &self.ephemeris).is_some_and(|crescent| {
+                    crescent.best_time > new_moon && crescent.visibility >= self.visibility
+                })
+            })
+            .unwrap_or(day + MAX_EVENINGS - 1)
+            + 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+    use crate::calendar::days_from_civil;
+    use crate::hijri::{hijri_date_of_day, HijriDate};
+
+    const MECCA: Observer = Observer {
+        latitude: 21.42,
+        longitude: 39.83,
+        elevation: 0.,
+    };
+
+    #[test]
+    fn ramadan_1443() {
+        // New moon on 2022-04-01T06:24:00+00:00, about nine hours before
+        // sunset in Mecca. The crescent was first seen the next evening.
+        let first = days_from_civil(2022, 4, 1);
+        for criterion in &[Criterion::Yallop, Criterion::Odeh] {
+            let evening = crescent(&MECCA, first, *criterion, &Meeus).unwrap();
+            assert_eq!(evening.visibility, Visibility::NotVisible, "Failed for {:?}", evening);
+            let evening = crescent(&MECCA, first + 1, *criterion, &Meeus).unwrap();
+            assert_eq!(evening.visibility, Visibility::NakedEye, "Failed for {:?}", evening);
+            assert!(evening.moon_horizon.unwrap() - evening.sun_horizon > 3600., "Failed for {:?}", evening);
+            assert!(evening.arcl > 10. && evening.arcl < 25., "Failed for {:?}", evening);
+        }
+    }
+
+    #[test]
+    fn crescent_width() {
+        // Full moon on 2022-01-17T23:48:00+00:00 and first quarter on the 9th.
+        let full = MoonPhase::from_secs(1642463280);
+        let diameter = 2. * full.semidiameter() / 60.;
+        assert!((full.crescent_width() - diameter).abs() < 0.1, "Failed for {}", full.crescent_width());
+        let quarter = MoonPhase::from_secs(1641751860);
+        let ratio = quarter.crescent_width() / (2. * quarter.semidiameter() / 60.);
+        assert!((ratio - 0.5).abs() < 0.02, "Failed for {}", ratio);
+        // The crescent of 2022-04-02 in Mecca, against Yallop's W.
+        let evening = crescent(&MECCA, days_from_civil(2022, 4, 2), Criterion::Yallop, &Meeus).unwrap();
+        let width = MoonPhase::from_secs_float(evening.best_time).crescent_width();
+        assert!((width - evening.width).abs() < 0.05, "Failed for {} {}", width, evening.width);
+    }
+
+    #[test]
+    fn danjon_limit() {
+        // At the new moon of 2022-04-01T06:24:00+00:00 the moon passed about
+        // 5 degrees from the sun, a day later it was far beyond the limit.
+        let new_moon = MoonPhase::from_secs(1648794240);
+        assert!(new_moon.elongation() < DANJON_LIMIT, "Failed for {}", new_moon.elongation());
+        assert!(!new_moon.beyond_danjon_limit());
+        let next_day = MoonPhase::from_secs(1648794240 + 86400);
+        assert!((10.0..16.).contains(&next_day.elongation()), "Failed for {}", next_day.elongation());
+        assert!(next_day.beyond_danjon_limit());
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        assert!(MoonPhase::from_secs(1642463280).elongation() > 170.);
+    }
+
+    #[test]
+    fn morning_crescent() {
+        // The old crescent on the morning before the new moon of
+        // 2022-04-01T06:24:00+00:00 rises too close to the sun, two mornings
+        // before it is easy.
+        let last = days_from_civil(2022, 4, 1);
+        let morning = super::morning_crescent(&MECCA, last, Criterion::Yallop, &Meeus).unwrap();
+        assert_eq!(morning.visibility, Visibility::NotVisible, "Failed for {:?}", morning);
+        let morning = super::morning_crescent(&MECCA, last - 2, Criterion::Yallop, &Meeus).unwrap();
+        assert_eq!(morning.visibility, Visibility::NakedEye, "Failed for {:?}", morning);
+        assert_eq!(morning.twilight, Twilight::Morning);
+        let moonrise = morning.moon_horizon.unwrap();
+        assert!(moonrise < morning.best_time && morning.best_time < morning.sun_horizon, "Failed for {:?}", morning);
+    }
+
+    #[test]
+    fn sighting_plan() {
+        let plan = super::sighting_plan(&MECCA, 1648512000., Criterion::Odeh, Visibility::NakedEye, &Meeus).unwrap();
+        assert!((plan.new_moon - 1648794240.).abs() < 1800., "Failed for {:?}", plan);
+        // First seen on the evening of 2022-04-02, as in `ramadan_1443`.
+        let evening = plan.first_evening.unwrap();
+        assert_eq!((evening.sun_horizon / 86400.).floor() as i64, days_from_civil(2022, 4, 2), "Failed for {:?}", evening);
+        let morning = plan.last_morning.unwrap();
+        let hours = (plan.new_moon - morning.best_time) / 3600.;
+        assert!((20.0..72.).contains(&hours), "Failed for {:?}", morning);
+        assert!(morning.visibility >= Visibility::NakedEye);
+    }
+
+    #[test]
+    fn no_sunset() {
+        // Midsummer beyond the arctic circle.
+        let tromso = Observer::new(69.65, 18.96);
+        assert_eq!(crescent(&tromso, days_from_civil(2022, 6, 21), Criterion::Yallop, &Meeus), None);
+    }
+
+    #[test]
+    fn sighting() {
+        let rule = Sighting {
+            observer: MECCA,
+            criterion: Criterion::Odeh,
+            visibility: Visibility::NakedEye,
+            utc_offset: 3 * 3600,
+            ephemeris: Meeus,
+        };
+        // Countries following local sightings started Ramadan 1443 on
+        // 2022-04-03, a day after the Umm al-Qura calendar.
+        let date = hijri_date_of_day(2022, 4, 3, &rule, &Meeus);
+        assert_eq!(date, Some(HijriDate { year: 1443, month: 9, day: 1 }));
+    }
+}
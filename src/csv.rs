@@ -0,0 +1,70 @@
+// CSV output of the moon sampled at a fixed step, one row per sample, for
+// spreadsheets and dataframes. Values are those of `MoonPhase::from_secs_float`.
+
+use std::io;
+
+use crate::calendar::format_utc;
+use crate::MoonPhase;
+
+pub const HEADER: &str = "timestamp,phase,illumination,distance,latitude,longitude";
+
+// Writes the header and a row for every `step` seconds from `from` up to,
+// but not including, `until` (unix seconds). Times are RFC 3339 in UTC,
+// the distance is in earth radii and the coordinates in degrees.
+pub fn write_csv<W: io::Write>(writer: &mut W, from: f64, until: f64, step: f64) -> io::Result<()> {
+    if step.is_nan() || step <= 0. || !from.is_finite() || !until.is_finite() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "step has to be positive and the range finite"));
+    }
+    writeln!(writer, "{}", HEADER)?;
+    let mut i = 0;
+    loop {
+        let secs = from + i as f64 * step;
+        if secs >= until {
+            return Ok(());
+        }
+        let moon_phase = MoonPhase::from_secs_float(secs);
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            format_utc(secs),
+            moon_phase.phase,
+            moon_phase.illumination,
+            moon_phase.distance,
+            moon_phase.latitude,
+            moon_phase.longitude
+        )?;
+        i += 1;
+    }
+}
+
+pub fn to_csv(from: f64, until: f64, step: f64) -> io::Result<String> {
+    let mut csv = Vec::new();
+    write_csv(&mut csv, from, until, step)?;
+    Ok(String::from_utf8(csv).unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rows() {
+        let csv = to_csv(1642464000., 1642464000. + 86400., 3600.).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 25);
+        assert_eq!(lines[0], HEADER);
+        assert!(lines[1].starts_with("2022-01-18T00:00:00Z,"));
+        assert!(lines[24].starts_with("2022-01-18T23:00:00Z,"));
+        let moon_phase = MoonPhase::from_secs(1642464000);
+        let values: Vec<f64> = lines[1].split(',').skip(1).map(|value| value.parse().unwrap()).collect();
+        assert_eq!(values, vec![
+            moon_phase.phase,
+            moon_phase.illumination,
+            moon_phase.distance,
+            moon_phase.latitude,
+            moon_phase.longitude,
+        ]);
+        assert!(to_csv(0., 1., 0.).is_err());
+        assert_eq!(to_csv(0., 0., 1.).unwrap(), format!("{}\n", HEADER));
+    }
+}
@@ -0,0 +1,88 @@
+// One value per calendar day, the way almanacs publish the illumination:
+// evaluated at a fixed local time of day, usually midnight.
+
+#[cfg(feature="chrono")]
+use chrono::{offset::TimeZone, Duration, NaiveDate};
+
+use crate::MoonPhase;
+#[cfg(not(feature="chrono"))]
+use crate::calendar::days_from_civil;
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum TimeOfDay {
+    #[default]
+    Midnight,
+    Noon,
+    // Seconds after local midnight.
+    At(u32),
+}
+
+impl TimeOfDay {
+    fn seconds(self) -> u32 {
+        match self {
+            TimeOfDay::Midnight => 0,
+            TimeOfDay::Noon => 12 * 3600,
+            TimeOfDay::At(secs) => secs,
+        }
+    }
+}
+
+impl MoonPhase {
+    // Percent illuminated at the given local time of `date` in `tz`. Local
+    // times skipped by a daylight saving change are taken an hour later.
+    #[cfg(feature="chrono")]
+    pub fn illumination_on_date<Tz: TimeZone>(date: NaiveDate, tz: &Tz, time_of_day: TimeOfDay) -> f64 {
+        let local = date.and_hms_opt(0, 0, 0).unwrap() + Duration::seconds(time_of_day.seconds() as i64);
+        let time = tz
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+            .unwrap_or_else(|| tz.from_utc_datetime(&local));
+        MoonPhase::new(time).percent_illuminated()
+    }
+
+    // Percent illuminated at the given local time of the day, in a time zone
+    // `utc_offset` seconds ahead of UTC.
+    #[cfg(not(feature="chrono"))]
+    pub fn illumination_on_date(
+        year: i32,
+        month: u32,
+        day: u32,
+        utc_offset: i32,
+        time_of_day: TimeOfDay,
+    ) -> f64 {
+        let days = days_from_civil(year as i64, month, day);
+        let secs = days * 86400 + time_of_day.seconds() as i64 - utc_offset as i64;
+        MoonPhase::from_secs(secs).percent_illuminated()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature="chrono")]
+    fn illumination_on_date() {
+        use chrono::{FixedOffset, Utc};
+        let date = NaiveDate::from_ymd_opt(2000, 1, 21).unwrap();
+        let midnight = MoonPhase::illumination_on_date(date, &Utc, TimeOfDay::Midnight);
+        assert_eq!(midnight, MoonPhase::from_secs(948412800).percent_illuminated());
+        let noon = MoonPhase::illumination_on_date(date, &Utc, TimeOfDay::Noon);
+        assert_eq!(noon, MoonPhase::from_secs(948412800 + 43200).percent_illuminated());
+        let east = FixedOffset::east_opt(3600).unwrap();
+        let local = MoonPhase::illumination_on_date(date, &east, TimeOfDay::At(3600));
+        assert_eq!(local, midnight);
+    }
+
+    #[test]
+    #[cfg(not(feature="chrono"))]
+    fn illumination_on_date() {
+        let midnight = MoonPhase::illumination_on_date(2000, 1, 21, 0, TimeOfDay::Midnight);
+        assert_eq!(midnight, MoonPhase::from_secs(948412800).percent_illuminated());
+        let noon = MoonPhase::illumination_on_date(2000, 1, 21, 0, TimeOfDay::Noon);
+        assert_eq!(noon, MoonPhase::from_secs(948412800 + 43200).percent_illuminated());
+        let local = MoonPhase::illumination_on_date(2000, 1, 21, 3600, TimeOfDay::At(3600));
+        assert_eq!(local, midnight);
+    }
+}
@@ -0,0 +1,109 @@
+// When the sky is dark enough for faint objects: the sun below astronomical
+// twilight and the moon down, or too thin to matter. Times are unix seconds.
+
+use crate::events::intervals_where;
+use crate::observer::Observer;
+use crate::sun::SunPosition;
+use crate::{julian_date_from_seconds, MoonPhase};
+
+// Short enough for no altitude to pass a limit twice in between.
+const STEP_SECS: f64 = 600.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DarkSkyLimits {
+    pub sun_altitude: f64,       // Degrees the sun has to be below, -18 for astronomical twilight
+    pub moon_altitude: f64,      // Degrees the moon's center has to be below, -0.833 for moonset
+    pub moon_illumination: f64,  // A moon lit at most this much (0 - 1) may be up
+}
+
+impl Default for DarkSkyLimits {
+    fn default() -> Self {
+        DarkSkyLimits {
+            sun_altitude: -18.,
+            moon_altitude: -0.833,
+            moon_illumination: 0.,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DarkWindow {
+    pub start: f64,  // Unix seconds
+    pub end: f64,    // Unix seconds
+}
+
+impl DarkWindow {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+impl Observer {
+    // Altitude of the center of the sun in degrees, without refraction.
+    pub fn sun_altitude(&self, secs: f64) -> f64 {
+        let j_date = julian_date_from_seconds(secs);
+        self.horizontal(j_date, SunPosition::new(j_date).longitude, 0.).0
+    }
+
+    fn is_dark(&self, secs: f64, limits: &DarkSkyLimits) -> bool {
+        if self.sun_altitude(secs) >= limits.sun_altitude {
+            return false;
+        }
+        let moon_phase = MoonPhase::from_secs_float(secs);
+        moon_phase.illumination <= limits.moon_illumination || self.moon_altitude(&moon_phase) < limits.moon_altitude
+    }
+
+    // The dark intervals within from..until, in order, cut off at both ends.
+    pub fn dark_windows(&self, from: f64, until: f64, limits: &DarkSkyLimits) -> Vec<DarkWindow> {
+        let sun = |secs| self.sun_altitude(secs) - limits.sun_altitude;
+        let moon = |secs| self.moon_altitude(&MoonPhase::from_secs_float(secs)) - limits.moon_altitude;
+        // Scaled to stay within the -180 - 180 degrees of `zero_crossings`.
+        let illumination = |secs| (MoonPhase::from_secs_float(secs).illumination - limits.moon_illumination) * 90.;
+
+        intervals_where(|secs| self.is_dark(secs, limits), &[&sun, &moon, &illumination], from, until, STEP_SECS)
+            .into_iter()
+            .map(|(start, end)| DarkWindow { start, end })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dark_windows() {
+        // Around the new moon of 2022-01-02 the night is dark from the end of
+        // twilight to its start in the morning, around the full moon of the
+        // 17th it isn't dark at all.
+        let greenwich = Observer::new(51.48, 0.);
+        let limits = DarkSkyLimits::default();
+        let new_moon = greenwich.dark_windows(1641081600., 1641168000., &limits);
+        assert_eq!(new_moon.len(), 2, "Failed for {:?}", new_moon);
+        let hours: f64 = new_moon.iter().map(DarkWindow::duration).sum::<f64>() / 3600.;
+        assert!((11.0..13.).contains(&hours), "Failed for {}", hours);
+        for window in &new_moon {
+            let middle = (window.start + window.end) / 2.;
+            assert!(greenwich.sun_altitude(middle) < -18.);
+        }
+        let full_moon = greenwich.dark_windows(1642377600., 1642464000., &limits);
+        assert!(full_moon.is_empty(), "Failed for {:?}", full_moon);
+
+        // Allowing a full moon gives the whole astronomical night.
+        let any_moon = DarkSkyLimits {
+            moon_illumination: 1.,
+            ..limits
+        };
+        assert_eq!(greenwich.dark_windows(1642377600., 1642464000., &any_moon).len(), 2);
+    }
+
+    #[test]
+    fn moon_sets_during_the_night() {
+        // At the first quarter the moon sets after midnight, the window
+        // starts then. Noon 2022-01-09 to noon the next day.
+        let greenwich = Observer::new(51.48, 0.);
+        let windows = greenwich.dark_windows(1641729600., 1641816000., &DarkSkyLimits::default());
+        let moonset = greenwich.moon_rise_set(1641729600.).set.unwrap();
+        assert!(windows.iter().any(|window| (window.start - moonset).abs() < 1.), "Failed for {:?}", windows);
+    }
+}
@@ -0,0 +1,109 @@
+// When to look for earthshine, the "da Vinci glow" of the dark part of the
+// disk lit by the earth: a thin crescent, some days from new moon, standing
+// clear of the horizon once the sky has darkened. That is after sunset while
+// the moon waxes and before sunrise while it wanes. Times are unix seconds.
+
+use crate::events::intervals_where;
+use crate::observer::{Observer, Twilight};
+use crate::MoonPhase;
+
+// Short enough for no altitude to pass a limit twice in between.
+const STEP_SECS: f64 = 600.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EarthshineLimits {
+    pub sun_altitude: f64,      // Degrees the sun has to be below, -6 for the end of civil twilight
+    pub moon_altitude: f64,     // Degrees the moon's center has to be above
+    pub min_illumination: f64,  // 0 - 1, thinner crescents are lost in the twilight
+    pub max_illumination: f64,  // 0 - 1, wider ones outshine the earthshine
+}
+
+impl Default for EarthshineLimits {
+    fn default() -> Self {
+        EarthshineLimits {
+            sun_altitude: -6.,
+            moon_altitude: 5.,
+            min_illumination: 0.02,
+            max_illumination: 0.25,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EarthshineWindow {
+    pub twilight: Twilight,
+    pub start: f64,         // Unix seconds
+    pub end: f64,           // Unix seconds
+    pub illumination: f64,  // At the start, 0 - 1
+}
+
+impl EarthshineWindow {
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+impl Observer {
+    fn shows_earthshine(&self, secs: f64, limits: &EarthshineLimits) -> bool {
+        let moon_phase = MoonPhase::from_secs_float(secs);
+        self.sun_altitude(secs) < limits.sun_altitude
+            && self.moon_altitude(&moon_phase) > limits.moon_altitude
+            && (limits.min_illumination..=limits.max_illumination).contains(&moon_phase.illumination)
+    }
+
+    // The evenings and mornings within from..until with earthshine worth
+    // looking for, in order, cut off at both ends.
+    pub fn earthshine_windows(&self, from: f64, until: f64, limits: &EarthshineLimits) -> Vec<EarthshineWindow> {
+        let sun = |secs| self.sun_altitude(secs) - limits.sun_altitude;
+        let moon = |secs| self.moon_altitude(&MoonPhase::from_secs_float(secs)) - limits.moon_altitude;
+        // Scaled to stay within the -180 - 180 degrees of `zero_crossings`.
+        let illumination = |secs: f64, limit: f64| (MoonPhase::from_secs_float(secs).illumination - limit) * 90.;
+        let thinnest = |secs| illumination(secs, limits.min_illumination);
+        let widest = |secs| illumination(secs, limits.max_illumination);
+
+        let shows_earthshine = |secs| self.shows_earthshine(secs, limits);
+        intervals_where(shows_earthshine, &[&sun, &moon, &thinnest, &widest], from, until, STEP_SECS)
+            .into_iter()
+            .map(|(start, end)| {
+                let moon_phase = MoonPhase::from_secs_float(start);
+                EarthshineWindow {
+                    twilight: if moon_phase.is_waxing() { Twilight::Evening } else { Twilight::Morning },
+                    start,
+                    end,
+                    illumination: moon_phase.illumination,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn earthshine_windows() {
+        // The month after the new moon of 2022-01-02T18:33:00+00:00: a few
+        // evenings with the waxing crescent, then a few mornings before the
+        // new moon of 2022-02-01.
+        let greenwich = Observer::new(51.48, 0.);
+        let windows = greenwich.earthshine_windows(1641081600., 1643673600., &EarthshineLimits::default());
+        let evenings = windows.iter().filter(|window| window.twilight == Twilight::Evening).count();
+        let mornings = windows.len() - evenings;
+        assert!((2..=5).contains(&evenings), "Failed for {:?}", windows);
+        assert!((2..=5).contains(&mornings), "Failed for {:?}", windows);
+        assert_eq!(windows[0].twilight, Twilight::Evening);
+        assert_eq!(windows[windows.len() - 1].twilight, Twilight::Morning);
+
+        for window in &windows {
+            assert!(window.duration() > 0. && window.duration() < 6. * 3600., "Failed for {:?}", window);
+            let middle = (window.start + window.end) / 2.;
+            assert!(greenwich.sun_altitude(middle) < -6., "Failed for {:?}", window);
+            let moon_phase = MoonPhase::from_secs_float(middle);
+            assert!(greenwich.moon_altitude(&moon_phase) > 5., "Failed for {:?}", window);
+            assert!(moon_phase.illumination < 0.25, "Failed for {:?}", window);
+        }
+        // Nothing around the full moon.
+        assert!(greenwich.earthshine_windows(1642377600., 1642550400., &EarthshineLimits::default()).is_empty());
+    }
+}
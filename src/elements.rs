@@ -0,0 +1,290 @@
+// The core model, kept free of references to the rest of the crate so the
+// build script can include it as well.
+
+use num_traits::Float;
+
+// Copied from the std libary, from before it was available there. The crate
+// now needs Rust 1.82 anyway (see `rust-version`), for float math in const fns.
+#[allow(clippy::approx_constant)]
+pub const TAU: f64 = 6.28318530717958647692528676655900577_f64;
+
+pub(crate) const MOON_SYNODIC_PERIOD: f64 = 29.530588853; // Period of moon cycle in days.
+pub(crate) const MOON_SYNODIC_OFFSET: f64 = 2451550.26; // Reference cycle offset in days.
+pub(crate) const MOON_DISTANCE_PERIOD: f64 = 27.55454988; // Period of distance oscillation
+pub(crate) const MOON_DISTANCE_OFFSET: f64 = 2451562.2;
+pub(crate) const MOON_LATITUDE_PERIOD: f64 = 27.212220817; // Latitude oscillation
+pub(crate) const MOON_LATITUDE_OFFSET: f64 = 2451565.2;
+pub(crate) const MOON_LONGITUDE_PERIOD: f64 = 27.321582241; // Longitude oscillation
+pub(crate) const MOON_LONGITUDE_OFFSET: f64 = 2451555.8;
+
+const J2000: f64 = 2451545.0;
+
+// TT - UT in seconds for the given decimal year, using the polynomials by
+// Espenak and Meeus (NASA Five Millennium Canon of Solar Eclipses).
+pub const fn delta_t(year: f64) -> f64 {
+    let y = year;
+    if y < -500. {
+        long_term(y)
+    } else if y < 500. {
+        let u = y / 100.;
+        polynomial(u, &[10583.6, -1014.41, 33.78311, -5.952053, -0.1798452, 0.022174192, 0.0090316521])
+    } else if y < 1600. {
+        let u = (y - 1000.) / 100.;
+        polynomial(u, &[1574.2, -556.01, 71.23472, 0.319781, -0.8503463, -0.005050998, 0.0083572073])
+    } else if y < 1700. {
+        polynomial(y - 1600., &[120., -0.9808, -0.01532, 1. / 7129.])
+    } else if y < 1800. {
+        polynomial(y - 1700., &[8.83, 0.1603, -0.0059285, 0.00013336, -1. / 1174000.])
+    } else if y < 1860. {
+        polynomial(
+            y - 1800.,
+            &[13.72, -0.332447, 0.0068612, 0.0041116, -0.00037436, 0.0000121272, -0.0000001699, 0.000000000875],
+        )
+    } else if y < 1900. {
+        polynomial(y - 1860., &[7.62, 0.5737, -0.251754, 0.01680668, -0.0004473624, 1. / 233174.])
+    } else if y < 1920. {
+        polynomial(y - 1900., &[-2.79, 1.494119, -0.0598939, 0.0061966, -0.000197])
+    } else if y < 1941. {
+        polynomial(y - 1920., &[21.20, 0.84493, -0.076100, 0.0020936])
+    } else if y < 1961. {
+        polynomial(y - 1950., &[29.07, 0.407, -1. / 233., 1. / 2547.])
+    } else if y < 1986. {
+        polynomial(y - 1975., &[45.45, 1.067, -1. / 260., -1. / 718.])
+    } else if y < 2005. {
+        polynomial(y - 2000., &[63.86, 0.3345, -0.060374, 0.0017275, 0.000651814, 0.00002373599])
+    } else if y < 2050. {
+        polynomial(y - 2000., &[62.92, 0.32217, 0.005589])
+    } else if y < 2150. {
+        long_term(y) - 0.5628 * (2150. - y)
+    } else {
+        long_term(y)
+    }
+}
+
+const fn long_term(y: f64) -> f64 {
+    let u = (y - 1820.) / 100.;
+    -20. + 32. * u * u
+}
+
+// Horner's scheme, written as a loop so it can be evaluated in const fns.
+const fn polynomial(x: f64, coefficients: &[f64]) -> f64 {
+    let mut sum = 0.;
+    let mut i = coefficients.len();
+    while i > 0 {
+        i -= 1;
+        sum = sum * x + coefficients[i];
+    }
+    sum
+}
+
+// Converts a Julian date in UT to TT, the time scale of the model.
+pub(crate) const fn ut_to_tt(j_date: f64) -> f64 {
+    // Decimal year, close enough for `delta_t`.
+    let year = 2000. + (j_date - J2000) / 365.25;
+    j_date + delta_t(year) / 86400.
+}
+
+// Floats at least this large are whole numbers.
+const INTEGRAL: f64 = 4_503_599_627_370_496.; // 2^52
+
+// f64::fract and f64::round are only const fns from Rust 1.90 on, these
+// give the same results through integer casts.
+pub(crate) const fn fract(x: f64) -> f64 {
+    if !(x > -INTEGRAL && x < INTEGRAL) {
+        // 0 for large numbers, NaN for NaN and infinities.
+        return x * 0. + 0.;
+    }
+    x - (x as i64) as f64
+}
+
+// Rounds half way cases away from zero.
+pub(crate) const fn round(x: f64) -> f64 {
+    if !(x > -INTEGRAL && x < INTEGRAL) {
+        return x;
+    }
+    let truncated = (x as i64) as f64;
+    let rest = x - truncated;
+    if rest >= 0.5 {
+        truncated + 1.
+    } else if rest <= -0.5 {
+        truncated - 1.
+    } else {
+        truncated
+    }
+}
+
+// Fraction of the synodic cycle at a Julian date in TT, 0 - 1 with 0.5 =
+// full, negative before MOON_SYNODIC_OFFSET. It needs no trig, so it is a
+// const fn and the phase name can be known at compile time.
+pub(crate) const fn synodic_phase(j_date: f64) -> f64 {
+    fract((j_date - MOON_SYNODIC_OFFSET) / MOON_SYNODIC_PERIOD)
+}
+
+// The raw numbers of a `MoonPhase`, generic so they can be computed in single
+// precision as well.
+pub(crate) struct Elements<F> {
+    pub phase: F,
+    pub age: F,
+    pub fraction: F,
+    pub illumination: F,
+    pub distance: F,
+    pub latitude: F,
+    pub longitude: F,
+}
+
+// Sine and cosine as used by the model. With the `deterministic` feature they
+// come from libm instead of the platform's math library, which differ in the
+// last bits between platforms.
+pub(crate) trait Trig: Float {
+    fn sine(self) -> Self;
+    fn cosine(self) -> Self;
+}
+
+impl Trig for f64 {
+    #[cfg(feature="deterministic")]
+    fn sine(self) -> Self {
+        libm::sin(self)
+    }
+
+    #[cfg(not(feature="deterministic"))]
+    fn sine(self) -> Self {
+        self.sin()
+    }
+
+    #[cfg(feature="deterministic")]
+    fn cosine(self) -> Self {
+        libm::cos(self)
+    }
+
+    #[cfg(not(feature="deterministic"))]
+    fn cosine(self) -> Self {
+        self.cos()
+    }
+}
+
+impl Trig for f32 {
+    #[cfg(feature="deterministic")]
+    fn sine(self) -> Self {
+        libm::sinf(self)
+    }
+
+    #[cfg(not(feature="deterministic"))]
+    fn sine(self) -> Self {
+        self.sin()
+    }
+
+    #[cfg(feature="deterministic")]
+    fn cosine(self) -> Self {
+        libm::cosf(self)
+    }
+
+    #[cfg(not(feature="deterministic"))]
+    fn cosine(self) -> Self {
+        self.cos()
+    }
+}
+
+impl<F: Trig> Elements<F> {
+    // For a Julian date in UT, which is converted to TT first.
+    pub fn new(j_date: f64) -> Self {
+        Self::new_tt(ut_to_tt(j_date))
+    }
+
+    pub fn new_tt(j_date: f64) -> Self {
+        // Reducing the date to the fraction of a cycle is always done in
+        // double precision, f32 can't even represent today's Julian date to
+        // better than a quarter of a day.
+        let cycle = |offset: f64, period: f64| -> F {
+            num(((j_date - offset) / period).fract())
+        };
+        let tau: F = num(TAU);
+
+        // Calculate illumination (synodic) phase.
+        // From number of days since new moon on Julian date MOON_SYNODIC_OFFSET
+        // (1815UTC January 6, 2000), determine remainder of incomplete cycle.
+        let phase: F = num(synodic_phase(j_date));
+        // Calculate age and illuination fraction. `fraction` is the original,
+        // misparenthesized formula, kept for `MoonPhase::fraction`.
+        let age = phase * num(MOON_SYNODIC_PERIOD);
+        let fraction = (F::one() - (tau * phase)).cosine() / num(2.);
+        let illumination = (F::one() - (tau * phase).cosine()) / num(2.);
+        // Calculate distance fro anoalistic phase.
+        let distance_phase = cycle(MOON_DISTANCE_OFFSET, MOON_DISTANCE_PERIOD);
+        let distance_phase_tau = tau * distance_phase;
+        let phase_tau = num::<F>(2.) * tau * phase;
+        let phase_distance_tau_difference = phase_tau - distance_phase_tau;
+        let distance = num::<F>(60.4)
+            - num::<F>(3.3) * distance_phase_tau.cosine()
+            - num::<F>(0.6) * (phase_distance_tau_difference).cosine()
+            - num::<F>(0.5) * (phase_tau).cosine();
+
+        // Calculate ecliptic latitude from nodal (draconic) phase.
+        let lat_phase = cycle(MOON_LATITUDE_OFFSET, MOON_LATITUDE_PERIOD);
+        let latitude = num::<F>(5.1) * (tau * lat_phase).sine();
+
+        // Calculate ecliptic longitude ffrom sidereal motion.
+        let long_phase = cycle(MOON_LONGITUDE_OFFSET, MOON_LONGITUDE_PERIOD);
+        let longitude = (num::<F>(360.) * long_phase
+            + num::<F>(6.3) * (distance_phase_tau).sine()
+            + num::<F>(1.3) * (phase_distance_tau_difference).sine()
+            + num::<F>(0.7) * (phase_tau).sine())
+            % num(360.);
+        // The cycles are negative before their offset.
+        let longitude = if longitude < F::zero() {
+            longitude + num(360.)
+        } else {
+            longitude
+        };
+
+        Elements {
+            phase,
+            age,
+            fraction,
+            illumination,
+            distance,
+            latitude,
+            longitude,
+        }
+    }
+}
+
+fn num<F: Float>(value: f64) -> F {
+    F::from(value).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trig() {
+        for i in -2000..2000 {
+            let x = i as f64 / 100.;
+            assert!((x.sine() - x.sin()).abs() < 1e-15, "Failed for {}", x);
+            assert!((x.cosine() - x.cos()).abs() < 1e-15, "Failed for {}", x);
+            let x = x as f32;
+            assert!((x.sine() - x.sin()).abs() < 1e-6, "Failed for {}", x);
+            assert!((x.cosine() - x.cos()).abs() < 1e-6, "Failed for {}", x);
+        }
+    }
+
+    #[test]
+    fn fract_and_round() {
+        for i in -4000..4000 {
+            let x = i as f64 / 8. + 1e-3;
+            assert_eq!(fract(x), x.fract(), "Failed for {}", x);
+            assert_eq!(round(x), x.round(), "Failed for {}", x);
+            let x = i as f64 / 8.;
+            assert_eq!(fract(x), x.fract(), "Failed for {}", x);
+            assert_eq!(round(x), x.round(), "Failed for {}", x);
+        }
+        for &x in &[1e16, -1e16, 2.5e15 + 0.5, -2.5e15 - 0.5, f64::MAX] {
+            assert_eq!(fract(x), x.fract(), "Failed for {}", x);
+            assert_eq!(round(x), x.round(), "Failed for {}", x);
+        }
+        for &x in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(fract(x).is_nan());
+            assert_eq!(round(x).to_bits(), x.round().to_bits());
+        }
+    }
+}
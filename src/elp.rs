@@ -0,0 +1,258 @@
+// Lunar coordinates from the truncated ELP-2000/82 series given in chapter 47
+// of Jean Meeus, "Astronomical Algorithms". Accurate to about 10" in longitude
+// and 4" in latitude, compared to several degrees for the default model.
+
+pub use crate::ephemeris::MoonPosition;
+use crate::ephemeris::Ephemeris;
+use crate::MoonPhase;
+
+const J2000: f64 = 2451545.0;
+
+// Multiples of D, M, M' and F, followed by the coefficients of the sine
+// (longitude, 1e-6 degrees) and cosine (distance, 1e-3 km) terms.
+const LONGITUDE_DISTANCE_TERMS: [(i8, i8, i8, i8, i32, i32); 60] = [
+    (0, 0, 1, 0, 6288774, -20905355),
+    (2, 0, -1, 0, 1274027, -3699111),
+    (2, 0, 0, 0, 658314, -2955968),
+    (0, 0, 2, 0, 213618, -569925),
+    (0, 1, 0, 0, -185116, 48888),
+    (0, 0, 0, 2, -114332, -3149),
+    (2, 0, -2, 0, 58793, 246158),
+    (2, -1, -1, 0, 57066, -152138),
+    (2, 0, 1, 0, 53322, -170733),
+    (2, -1, 0, 0, 45758, -204586),
+    (0, 1, -1, 0, -40923, -129620),
+    (1, 0, 0, 0, -34720, 108743),
+    (0, 1, 1, 0, -30383, 104755),
+    (2, 0, 0, -2, 15327, 10321),
+    (0, 0, 1, 2, -12528, 0),
+    (0, 0, 1, -2, 10980, 79661),
+    (4, 0, -1, 0, 10675, -34782),
+    (0, 0, 3, 0, 10034, -23210),
+    (4, 0, -2, 0, 8548, -21636),
+    (2, 1, -1, 0, -7888, 24208),
+    (2, 1, 0, 0, -6766, 30824),
+    (1, 0, -1, 0, -5163, -8379),
+    (1, 1, 0, 0, 4987, -16675),
+    (2, -1, 1, 0, 4036, -12831),
+    (2, 0, 2, 0, 3994, -10445),
+    (4, 0, 0, 0, 3861, -11650),
+    (2, 0, -3, 0, 3665, 14403),
+    (0, 1, -2, 0, -2689, -7003),
+    (2, 0, -1, 2, -2602, 0),
+    (2, -1, -2, 0, 2390, 10056),
+    (1, 0, 1, 0, -2348, 6322),
+    (2, -2, 0, 0, 2236, -9884),
+    (0, 1, 2, 0, -2120, 5751),
+    (0, 2, 0, 0, -2069, 0),
+    (2, -2, -1, 0, 2048, -4950),
+    (2, 0, 1, -2, -1773, 4130),
+    (2, 0, 0, 2, -1595, 0),
+    (4, -1, -1, 0, 1215, -3958),
+    (0, 0, 2, 2, -1110, 0),
+    (3, 0, -1, 0, -892, 3258),
+    (2, 1, 1, 0, -810, 2616),
+    (4, -1, -2, 0, 759, -1897),
+    (0, 2, -1, 0, -713, -2117),
+    (2, 2, -1, 0, -700, 2354),
+    (2, 1, -2, 0, 691, 0),
+    (2, -1, 0, -2, 596, 0),
+    (4, 0, 1, 0, 549, -1423),
+    (0, 0, 4, 0, 537, -1117),
+    (4, -1, 0, 0, 520, -1571),
+    (1, 0, -2, 0, -487, -1739),
+    (2, 1, 0, -2, -399, 0),
+    (0, 0, 2, -2, -381, -4421),
+    (1, 1, 1, 0, 351, 0),
+    (3, 0, -2, 0, -340, 0),
+    (4, 0, -3, 0, 330, 0),
+    (2, -1, 2, 0, 327, 0),
+    (0, 2, 1, 0, -323, 1165),
+    (1, 1, -1, 0, 299, 0),
+    (2, 0, 3, 0, 294, 0),
+    (2, 0, -1, -2, 0, 8752),
+];
+
+// Multiples of D, M, M' and F, followed by the coefficient of the sine term
+// (latitude, 1e-6 degrees).
+const LATITUDE_TERMS: [(i8, i8, i8, i8, i32); 60] = [
+    (0, 0, 0, 1, 5128122),
+    (0, 0, 1, 1, 280602),
+    (0, 0, 1, -1, 277693),
+    (2, 0, 0, -1, 173237),
+    (2, 0, -1, 1, 55413),
+    (2, 0, -1, -1, 46271),
+    (2, 0, 0, 1, 32573),
+    (0, 0, 2, 1, 17198),
+    (2, 0, 1, -1, 9266),
+    (0, 0, 2, -1, 8822),
+    (2, -1, 0, -1, 8216),
+    (2, 0, -2, -1, 4324),
+    (2, 0, 1, 1, 4200),
+    (2, 1, 0, -1, -3359),
+    (2, -1, -1, 1, 2463),
+    (2, -1, 0, 1, 2211),
+    (2, -1, -1, -1, 2065),
+    (0, 1, -1, -1, -1870),
+    (4, 0, -1, -1, 1828),
+    (0, 1, 0, 1, -1794),
+    (0, 0, 0, 3, -1749),
+    (0, 1, -1, 1, -1565),
+    (1, 0, 0, 1, -1491),
+    (0, 1, 1, 1, -1475),
+    (0, 1, 1, -1, -1410),
+    (0, 1, 0, -1, -1344),
+    (1, 0, 0, -1, -1335),
+    (0, 0, 3, 1, 1107),
+    (4, 0, 0, -1, 1021),
+    (4, 0, -1, 1, 833),
+    (0, 0, 1, -3, 777),
+    (4, 0, -2, 1, 671),
+    (2, 0, 0, -3, 607),
+    (2, 0, 2, -1, 596),
+    (2, -1, 1, -1, 491),
+    (2, 0, -2, 1, -451),
+    (0, 0, 3, -1, 439),
+    (2, 0, 2, 1, 422),
+    (2, 0, -3, -1, 421),
+    (2, 1, -1, 1, -366),
+    (2, 1, 0, 1, -351),
+    (4, 0, 0, 1, 331),
+    (2, -1, 1, 1, 315),
+    (2, -2, 0, -1, 302),
+    (0, 0, 1, 3, -283),
+    (2, 1, 1, -1, -229),
+    (1, 1, 0, -1, 223),
+    (1, 1, 0, 1, 223),
+    (0, 1, -2, -1, -220),
+    (2, 1, -1, -1, -220),
+    (1, 0, 1, 1, -185),
+    (2, -1, -2, -1, 181),
+    (0, 1, 2, 1, -177),
+    (4, 0, -2, -1, 176),
+    (4, -1, -1, -1, 166),
+    (1, 0, 1, -1, -164),
+    (4, 0, 1, -1, 132),
+    (1, 0, -1, -1, -119),
+    (4, -1, 0, -1, 115),
+    (2, -2, 0, 1, 107),
+];
+
+// `Ephemeris` backed by the series above.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Elp;
+
+impl Ephemeris for Elp {
+    fn moon(&self, j_date: f64) -> MoonPosition {
+        MoonPosition::new(crate::elements::ut_to_tt(j_date))
+    }
+}
+
+impl MoonPosition {
+    // For a Julian date in TT, see `Elp` for UT.
+    pub fn new(j_date: f64) -> Self {
+        let t = (j_date - J2000) / 36525.;
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let t4 = t3 * t;
+        // Mean longitude, elongation, anomaly of the sun, anomaly of the moon
+        // and argument of latitude.
+        let l = 218.3164477 + 481267.88123421 * t - 0.0015786 * t2 + t3 / 538841.
+            - t4 / 65194000.;
+        let d = 297.8501921 + 445267.1114034 * t - 0.0018819 * t2 + t3 / 545868.
+            - t4 / 113065000.;
+        let m = 357.5291092 + 35999.0502909 * t - 0.0001536 * t2 + t3 / 24490000.;
+        let m_moon = 134.9633964 + 477198.8675055 * t + 0.0087414 * t2 + t3 / 69699.
+            - t4 / 14712000.;
+        let f = 93.2720950 + 483202.0175233 * t - 0.0036539 * t2 - t3 / 3526000.
+            + t4 / 863310000.;
+        let a1 = 119.75 + 131.849 * t;
+        let a2 = 53.09 + 479264.290 * t;
+        let a3 = 313.45 + 481266.484 * t;
+        // Correction for the decreasing eccentricity of earth's orbit.
+        let e = 1. - 0.002516 * t - 0.0000074 * t2;
+
+        let argument = |dm: i8, mm: i8, mmm: i8, fm: i8| {
+            let eccentricity = e.powi(mm.abs() as i32);
+            let angle = (dm as f64 * d + mm as f64 * m + mmm as f64 * m_moon + fm as f64 * f)
+                .to_radians();
+            (eccentricity, angle)
+        };
+
+        let mut sum_l = 0.;
+        let mut sum_r = 0.;
+        for &(dm, mm, mmm, fm, sl, sr) in LONGITUDE_DISTANCE_TERMS.iter() {
+            let (eccentricity, angle) = argument(dm, mm, mmm, fm);
+            sum_l += eccentricity * sl as f64 * angle.sin();
+            sum_r += eccentricity * sr as f64 * angle.cos();
+        }
+        let mut sum_b = 0.;
+        for &(dm, mm, mmm, fm, sb) in LATITUDE_TERMS.iter() {
+            let (eccentricity, angle) = argument(dm, mm, mmm, fm);
+            sum_b += eccentricity * sb as f64 * angle.sin();
+        }
+
+        // Action of Venus, Jupiter and the flattening of the earth.
+        let sin = |degrees: f64| degrees.to_radians().sin();
+        sum_l += 3958. * sin(a1) + 1962. * sin(l - f) + 318. * sin(a2);
+        sum_b += -2235. * sin(l) + 382. * sin(a3) + 175. * sin(a1 - f) + 175. * sin(a1 + f)
+            + 127. * sin(l - m_moon) - 115. * sin(l + m_moon);
+
+        MoonPosition {
+            longitude: (l + sum_l / 1_000_000.).rem_euclid(360.),
+            latitude: sum_b / 1_000_000.,
+            distance: 385000.56 + sum_r / 1000.,
+        }
+    }
+}
+
+impl MoonPhase {
+    // Same as `from_secs`, but with positions from the ELP-2000/82 series
+    // and the phase derived from the actual elongation from the sun.
+    pub fn from_secs_precise(secs: i64) -> Self {
+        Self::from_secs_float_precise(secs as f64)
+    }
+
+    pub fn from_secs_float_precise(secs: f64) -> Self {
+        Self::_new_precise(crate::julian_date_from_seconds(secs))
+    }
+
+    fn _new_precise(j_date: f64) -> Self {
+        Self::from_ephemeris(j_date, &Elp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meeus_example() {
+        // Example 47.a: 1992 April 12, 0h TD
+        let moon = MoonPosition::new(2448724.5);
+        assert!((moon.longitude - 133.162655).abs() < 1e-5, "{:?}", moon);
+        assert!((moon.latitude - -3.229126).abs() < 1e-5, "{:?}", moon);
+        assert!((moon.distance - 368409.7).abs() < 0.1, "{:?}", moon);
+    }
+
+    #[test]
+    fn illuminated_fraction() {
+        // Example 48.a: 1992 April 12, 0h TD, k = 0.6786
+        let moon_phase = MoonPhase::_new_precise(2448724.5);
+        assert!((moon_phase.illumination - 0.6786).abs() < 1e-3, "{:?}", moon_phase);
+        assert_eq!(moon_phase.phase_name, crate::Phase::FirstQuarter);
+    }
+
+    #[test]
+    fn agrees_with_simple_model() {
+        for secs in (900_000_000..1_700_000_000).step_by(86_400 * 3) {
+            let simple = MoonPhase::from_secs(secs);
+            let precise = MoonPhase::from_secs_precise(secs);
+            // The simple model ignores most of the periodic terms.
+            let diff = (simple.longitude - precise.longitude).rem_euclid(360.);
+            assert!(diff.min(360. - diff) < 5., "Failed for {}", secs);
+            let diff = (simple.phase - precise.phase).rem_euclid(1.);
+            assert!(diff.min(1. - diff) < 0.04, "Failed for {}", secs);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Lets `MoonPhase` be calculated from any source of lunar positions, e.g. a
+// reader for JPL kernels, instead of the built in models.
+
+use crate::elements::ut_to_tt;
+use crate::sun::SunPosition;
+use crate::{julian_date_from_seconds, phase_name, MoonPhase, Zodiac, MOON_SYNODIC_PERIOD, TAU};
+
+pub const EARTH_RADIUS: f64 = 6378.14; // Equatorial radius in km
+pub const AU: f64 = 149_597_870.7; // km
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonPosition {
+    pub longitude: f64,  // Geocentric ecliptic longitude in degrees, mean equinox of date
+    pub latitude: f64,   // Geocentric ecliptic latitude in degrees
+    pub distance: f64,   // Distance between the centers of earth and moon in km
+}
+
+// All methods take the Julian date in UT, implementations convert it to the
+// time scale of their theory (e.g. with `TimeScale::Utc.to_tt`). Only `moon`
+// has to be provided, the sun defaults to the `sun` module.
+pub trait Ephemeris {
+    fn moon(&self, j_date: f64) -> MoonPosition;
+
+    // Geocentric ecliptic longitude in degrees, same equinox as `moon`.
+    fn sun_longitude(&self, j_date: f64) -> f64 {
+        SunPosition::new(ut_to_tt(j_date)).longitude
+    }
+
+    // In astronomical units.
+    fn sun_distance(&self, j_date: f64) -> f64 {
+        SunPosition::new(ut_to_tt(j_date)).distance
+    }
+}
+
+impl<E: Ephemeris + ?Sized> Ephemeris for &E {
+    fn moon(&self, j_date: f64) -> MoonPosition {
+        (**self).moon(j_date)
+    }
+
+    fn sun_longitude(&self, j_date: f64) -> f64 {
+        (**self).sun_longitude(j_date)
+    }
+
+    fn sun_distance(&self, j_date: f64) -> f64 {
+        (**self).sun_distance(j_date)
+    }
+}
+
+impl MoonPhase {
+    pub fn with_ephemeris<E: Ephemeris + ?Sized>(secs: i64, ephemeris: &E) -> Self {
+        Self::from_secs_float_with_ephemeris(secs as f64, ephemeris)
+    }
+
+    pub fn from_secs_float_with_ephemeris<E: Ephemeris + ?Sized>(secs: f64, ephemeris: &E) -> Self {
+        Self::from_ephemeris(julian_date_from_seconds(secs), ephemeris)
+    }
+
+    // The phase follows from the elongation of the moon from the sun.
+    #[allow(deprecated)]
+    pub(crate) fn from_ephemeris<E: Ephemeris + ?Sized>(j_date: f64, ephemeris: &E) -> Self {
+        let MoonPosition {
+            longitude,
+            latitude,
+            distance,
+        } = ephemeris.moon(j_date);
+        let elongation = (longitude - ephemeris.sun_longitude(j_date))
+            .to_radians()
+            .rem_euclid(TAU);
+        let phase = elongation / TAU;
+
+        // Phase angle (sun-moon-earth) from the geocentric elongation.
+        let cos_elongation = latitude.to_radians().cos() * elongation.cos();
+        let sun_distance = ephemeris.sun_distance(j_date) * AU;
+        let sin_elongation = (1. - cos_elongation * cos_elongation).sqrt();
+        let phase_angle =
+            (sun_distance * sin_elongation).atan2(distance - sun_distance * cos_elongation);
+        let illumination = (1. + phase_angle.cos()) / 2.;
+
+        let longitude = longitude.rem_euclid(360.);
+        MoonPhase {
+            j_date,
+            phase,
+            age: phase * MOON_SYNODIC_PERIOD,
+            fraction: illumination,
+            illumination,
+            distance: distance / EARTH_RADIUS,
+            latitude,
+            longitude,
+            phase_name: phase_name(phase),
+            zodiac_name: Zodiac::from_long(longitude),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::{Algorithm, Meeus};
+
+    // Moon exactly opposite to the sun.
+    struct AlwaysFull;
+
+    impl Ephemeris for AlwaysFull {
+        fn moon(&self, j_date: f64) -> MoonPosition {
+            MoonPosition {
+                longitude: self.sun_longitude(j_date) + 180.,
+                latitude: 0.,
+                distance: 384400.,
+            }
+        }
+    }
+
+    #[test]
+    fn custom_ephemeris() {
+        let moon_phase = MoonPhase::with_ephemeris(1642291200, &AlwaysFull);
+        assert_eq!(moon_phase.phase_name, crate::Phase::Full);
+        assert!((moon_phase.phase - 0.5).abs() < 1e-9);
+        assert!(moon_phase.illumination > 0.9999);
+        let dynamic: &dyn Ephemeris = &AlwaysFull;
+        assert_eq!(MoonPhase::with_ephemeris(1642291200, dynamic), moon_phase);
+    }
+
+    #[test]
+    fn same_as_algorithm() {
+        assert_eq!(
+            MoonPhase::with_ephemeris(1642291200, &Meeus),
+            MoonPhase::with_algorithm(1642291200, Algorithm::Meeus)
+        );
+    }
+}
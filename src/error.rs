@@ -0,0 +1,31 @@
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Error {
+    // The timestamp is NaN or infinite.
+    NotFinite,
+    // The timestamp (unix seconds) lies outside of VALID_FROM..VALID_UNTIL.
+    OutOfRange(f64),
+    // A date or time of day that doesn't exist, e.g. February 30th or 24:00.
+    InvalidDate,
+    // The boundaries of a `ZodiacScheme` are empty, not ascending or
+    // outside of 0 - 360 degrees.
+    InvalidScheme,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFinite => write!(f, "timestamp is not a finite number"),
+            Error::OutOfRange(secs) => write!(
+                f,
+                "timestamp {} is outside of the range the model is valid for",
+                secs
+            ),
+            Error::InvalidDate => write!(f, "date or time of day does not exist"),
+            Error::InvalidScheme => write!(f, "zodiac boundaries are not ascending within 0 - 360 degrees"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,146 @@
+// Searching for the instants at which an angle passes a given value, the
+// building block for ingresses, aspects and the like. Times are unix seconds.
+
+use crate::ephemeris::Ephemeris;
+use crate::julian_date_from_seconds;
+use crate::naming::PrimaryPhase;
+
+// The elongation changes by at most 1.1 degrees per step.
+const ELONGATION_STEP_SECS: f64 = 7200.;
+
+// Wraps an angle in degrees to -180 - 180.
+pub(crate) fn wrap_180(angle: f64) -> f64 {
+    (angle + 180.).rem_euclid(360.) - 180.
+}
+
+// Returns the times in from..until at which `angle` (in degrees, wrapped
+// to -180 - 180 here) passes through zero. `step` has to be short
+// enough for the angle to change by much less than 180 degrees, and to not
+// pass zero twice. The results are exact to about a millisecond.
+pub(crate) fn zero_crossings(angle: impl Fn(f64) -> f64, from: f64, until: f64, step: f64) -> Vec<f64> {
+    let angle = |secs| wrap_180(angle(secs));
+    let mut crossings = Vec::new();
+    let mut start = from;
+    let mut start_angle = angle(start);
+    while start < until {
+        let end = (start + step).min(until);
+        let end_angle = angle(end);
+        // A change of sign through 180 degrees is the wrap around, not a
+        // crossing.
+        let crosses = (start_angle < 0.) != (end_angle < 0.) && (end_angle - start_angle).abs() < 180.;
+        if start_angle == 0. {
+            crossings.push(start);
+        } else if crosses && end_angle != 0. {
+            crossings.push(bisect(&angle, start, end));
+        }
+        start = end;
+        start_angle = end_angle;
+    }
+    crossings
+}
+
+// The intervals of from..until in which `holds` is true, in order and cut
+// off at both ends. It may only change where one of `limits` passes through
+// zero, which are found with `zero_crossings` and `step`.
+pub(crate) fn intervals_where(
+    holds: impl Fn(f64) -> bool,
+    limits: &[&dyn Fn(f64) -> f64],
+    from: f64,
+    until: f64,
+    step: f64,
+) -> Vec<(f64, f64)> {
+    let mut bounds = vec![from, until];
+    for limit in limits {
+        bounds.extend(zero_crossings(limit, from, until, step));
+    }
+    bounds.sort_by(f64::total_cmp);
+    bounds.dedup();
+
+    let mut intervals: Vec<(f64, f64)> = Vec::new();
+    for pair in bounds.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if !holds((start + end) / 2.) {
+            continue;
+        }
+        match intervals.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => intervals.push((start, end)),
+        }
+    }
+    intervals
+}
+
+// The conjunctions of sun and moon in from..until, in order.
+pub(crate) fn new_moons<E: Ephemeris + ?Sized>(from: f64, until: f64, ephemeris: &E) -> Vec<f64> {
+    let elongation = |secs| {
+        let j_date = julian_date_from_seconds(secs);
+        ephemeris.moon(j_date).longitude - ephemeris.sun_longitude(j_date)
+    };
+    zero_crossings(elongation, from, until, ELONGATION_STEP_SECS)
+}
+
+// New, full and quarter moons in from..until, in order.
+pub(crate) fn primary_phases<E: Ephemeris + ?Sized>(from: f64, until: f64, ephemeris: &E) -> Vec<(PrimaryPhase, f64)> {
+    let elongation = |secs| {
+        let j_date = julian_date_from_seconds(secs);
+        ephemeris.moon(j_date).longitude - ephemeris.sun_longitude(j_date)
+    };
+    // Four times the elongation passes zero at every quarter.
+    zero_crossings(|secs| 4. * elongation(secs), from, until, ELONGATION_STEP_SECS)
+        .into_iter()
+        .map(|secs| {
+            let quarter = (elongation(secs).rem_euclid(360.) / 90.).round() as usize;
+            (PrimaryPhase::ALL[quarter % 4], secs)
+        })
+        .collect()
+}
+
+fn bisect(angle: &impl Fn(f64) -> f64, mut low: f64, mut high: f64) -> f64 {
+    let low_negative = angle(low) < 0.;
+    while high - low > 1e-3 {
+        let mid = (low + high) / 2.;
+        if (angle(mid) < 0.) == low_negative {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn crossings() {
+        // One revolution a day, starting at 10 degrees.
+        let angle = |secs: f64| 10. + secs / 86400. * 360.;
+        let crossings = zero_crossings(angle, 0., 3. * 86400., 3600.);
+        assert_eq!(crossings.len(), 3);
+        for (i, secs) in crossings.iter().enumerate() {
+            let expected = (i as f64 + 350. / 360.) * 86400.;
+            assert!((secs - expected).abs() < 1e-2, "Failed for {}", secs);
+        }
+        // Backwards works as well.
+        let crossings = zero_crossings(|secs| -angle(secs), 0., 86400., 3600.);
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(wrap_180(190.), -170.);
+    }
+
+    #[test]
+    fn new_moons_2022() {
+        use crate::algorithm::Meeus;
+        // 2022-01-02T18:33:00+00:00 and 2022-02-01T05:46:00+00:00.
+        let new_moons = new_moons(1640995200., 1640995200. + 40. * 86400., &Meeus);
+        assert_eq!(new_moons.len(), 2);
+        assert!((new_moons[0] - 1641148380.).abs() < 1800., "Failed for {}", new_moons[0]);
+        assert!((new_moons[1] - 1643694360.).abs() < 1800., "Failed for {}", new_moons[1]);
+        let phases = primary_phases(1640995200., 1640995200. + 40. * 86400., &Meeus);
+        assert_eq!(phases.len(), 6);
+        assert_eq!(phases[0].0, PrimaryPhase::New);
+        assert!((phases[0].1 - new_moons[0]).abs() < 1e-2);
+        assert_eq!(phases[2].0, PrimaryPhase::Full);
+        assert_eq!(phases[4].0, PrimaryPhase::New);
+    }
+}
@@ -0,0 +1,253 @@
+// C API. The header include/moon_phase.h is generated by cbindgen when the
+// crate is built with the `ffi` feature and checked by the `header` test,
+// which prints the new one if they differ. A static library is built with
+//
+//     cargo rustc --release --lib --features ffi --crate-type staticlib
+//
+// Strings returned by `moon_phase_to_json` belong to the caller and are
+// freed with `moon_phase_string_free`, all others are static.
+//
+//     #include "moon_phase.h"
+//     MoonPhase moon = moon_phase_from_secs(1642463280);
+//     printf("%s %s\n", moon_phase_emoji(moon.phase_name), moon_phase_name(moon.phase_name));
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::{Phase, Zodiac};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MoonPhaseName {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MoonZodiac {
+    Pisces,
+    Aries,
+    Taurus,
+    Gemini,
+    Cancer,
+    Leo,
+    Virgo,
+    Libra,
+    Scorpio,
+    Sagittarius,
+    Capricorn,
+    Aquarius,
+}
+
+// `crate::MoonPhase` without the deprecated `fraction`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonPhase {
+    pub julian_date: f64,
+    pub phase: f64,                // 0 - 1, 0.5 = full
+    pub age: f64,                  // Days
+    pub illumination: f64,         // 0 - 1
+    pub distance: f64,             // Earth radii
+    pub latitude: f64,             // Ecliptic, degrees
+    pub longitude: f64,            // Ecliptic, degrees
+    pub phase_name: MoonPhaseName,
+    pub zodiac: MoonZodiac,
+}
+
+const PHASE_NAMES: [&str; 8] = [
+    "New Moon\0", "Waxing Crescent\0", "First Quarter\0", "Waxing Gibbous\0",
+    "Full Moon\0", "Waning Gibbous\0", "Last Quarter\0", "Waning Crescent\0",
+];
+
+const PHASE_EMOJI: [&str; 8] = ["🌑\0", "🌒\0", "🌓\0", "🌔\0", "🌕\0", "🌖\0", "🌗\0", "🌘\0"];
+
+const ZODIAC_NAMES: [&str; 12] = [
+    "Pisces\0", "Aries\0", "Taurus\0", "Gemini\0", "Cancer\0", "Leo\0",
+    "Virgo\0", "Libra\0", "Scorpio\0", "Sagittarius\0", "Capricorn\0", "Aquarius\0",
+];
+
+impl From<Phase> for MoonPhaseName {
+    fn from(phase: Phase) -> Self {
+        match phase {
+            Phase::New => MoonPhaseName::New,
+            Phase::WaxingCrescent => MoonPhaseName::WaxingCrescent,
+            Phase::FirstQuarter => MoonPhaseName::FirstQuarter,
+            Phase::WaxingGibbous => MoonPhaseName::WaxingGibbous,
+            Phase::Full => MoonPhaseName::Full,
+            Phase::WaningGibbous => MoonPhaseName::WaningGibbous,
+            Phase::LastQuarter => MoonPhaseName::LastQuarter,
+            Phase::WaningCrescent => MoonPhaseName::WaningCrescent,
+        }
+    }
+}
+
+impl From<Zodiac> for MoonZodiac {
+    fn from(zodiac: Zodiac) -> Self {
+        match zodiac {
+            Zodiac::Pisces => MoonZodiac::Pisces,
+            Zodiac::Aries => MoonZodiac::Aries,
+            Zodiac::Taurus => MoonZodiac::Taurus,
+            Zodiac::Gemini => MoonZodiac::Gemini,
+            Zodiac::Cancer => MoonZodiac::Cancer,
+            Zodiac::Leo => MoonZodiac::Leo,
+            Zodiac::Virgo => MoonZodiac::Virgo,
+            Zodiac::Libra => MoonZodiac::Libra,
+            Zodiac::Scorpio => MoonZodiac::Scorpio,
+            Zodiac::Sagittarius => MoonZodiac::Sagittarius,
+            Zodiac::Capricorn => MoonZodiac::Capricorn,
+            Zodiac::Aquarius => MoonZodiac::Aquarius,
+        }
+    }
+}
+
+impl From<crate::MoonPhase> for MoonPhase {
+    fn from(moon_phase: crate::MoonPhase) -> Self {
+        MoonPhase {
+            julian_date: moon_phase.j_date,
+            phase: moon_phase.phase,
+            age: moon_phase.age,
+            illumination: moon_phase.illumination,
+            distance: moon_phase.distance,
+            latitude: moon_phase.latitude,
+            longitude: moon_phase.longitude,
+            phase_name: moon_phase.phase_name.into(),
+            zodiac: moon_phase.zodiac_name.into(),
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn moon_phase_from_secs(secs: f64) -> MoonPhase {
+    crate::MoonPhase::from_secs_float(secs).into()
+}
+
+#[no_mangle]
+pub extern "C" fn moon_phase_from_millis(millis: i64) -> MoonPhase {
+    crate::MoonPhase::from_millis(millis).into()
+}
+
+#[no_mangle]
+pub extern "C" fn moon_phase_now() -> MoonPhase {
+    crate::MoonPhase::now().into()
+}
+
+// False, leaving `out` alone, for NaN and times outside of
+// `VALID_FROM..VALID_UNTIL`.
+#[no_mangle]
+pub unsafe extern "C" fn moon_phase_try_from_secs(secs: f64, out: *mut MoonPhase) -> bool {
+    match crate::MoonPhase::try_from_secs_float(secs) {
+        Ok(moon_phase) if !out.is_null() => {
+            *out = moon_phase.into();
+            true
+        }
+        _ => false,
+    }
+}
+
+// The names take the enums as plain integers, since C may pass any value,
+// which isn't a valid Rust enum. Out of range values give NULL.
+fn c_str(strings: &[&'static str], index: u32) -> *const c_char {
+    strings.get(index as usize).map_or(ptr::null(), |string| string.as_ptr() as *const c_char)
+}
+
+// "Waxing Crescent" etc., `name` is a MoonPhaseName.
+#[no_mangle]
+pub extern "C" fn moon_phase_name(name: u32) -> *const c_char {
+    c_str(&PHASE_NAMES, name)
+}
+
+// UTF-8, `name` is a MoonPhaseName.
+#[no_mangle]
+pub extern "C" fn moon_phase_emoji(name: u32) -> *const c_char {
+    c_str(&PHASE_EMOJI, name)
+}
+
+// `zodiac` is a MoonZodiac.
+#[no_mangle]
+pub extern "C" fn moon_phase_zodiac_name(zodiac: u32) -> *const c_char {
+    c_str(&ZODIAC_NAMES, zodiac)
+}
+
+// `MoonPhase::to_json` of the same time, or NULL for NULL.
+#[no_mangle]
+pub unsafe extern "C" fn moon_phase_to_json(moon_phase: *const MoonPhase) -> *mut c_char {
+    if moon_phase.is_null() {
+        return ptr::null_mut();
+    }
+    // Only the date is read, the enums may hold anything C put there.
+    let julian_date = ptr::addr_of!((*moon_phase).julian_date).read();
+    let json = crate::MoonPhase::from_julian_date(julian_date).to_json();
+    CString::new(json).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+// Frees a string returned by this library, NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn moon_phase_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn api() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let moon_phase = moon_phase_from_secs(1642463280.);
+        assert_eq!(moon_phase, crate::MoonPhase::from_secs(1642463280).into());
+        assert_eq!(moon_phase_from_millis(1642463280000), moon_phase);
+        assert_eq!(moon_phase.phase_name, MoonPhaseName::Full);
+        unsafe {
+            assert_eq!(CStr::from_ptr(moon_phase_name(moon_phase.phase_name as u32)).to_str(), Ok("Full Moon"));
+            assert_eq!(CStr::from_ptr(moon_phase_emoji(moon_phase.phase_name as u32)).to_str(), Ok("🌕"));
+            assert_eq!(CStr::from_ptr(moon_phase_zodiac_name(MoonZodiac::Sagittarius as u32)).to_str(), Ok("Sagittarius"));
+
+            let mut out = moon_phase_from_secs(0.);
+            assert!(moon_phase_try_from_secs(1642463280., &mut out));
+            assert_eq!(out, moon_phase);
+            assert!(!moon_phase_try_from_secs(f64::NAN, &mut out));
+            assert!(!moon_phase_try_from_secs(1642463280., ptr::null_mut()));
+
+            let json = moon_phase_to_json(&moon_phase);
+            assert_eq!(CStr::from_ptr(json).to_str(), Ok(crate::MoonPhase::from_secs(1642463280).to_json().as_str()));
+            moon_phase_string_free(json);
+            assert!(moon_phase_to_json(ptr::null()).is_null());
+        }
+    }
+
+    #[test]
+    fn header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/moon_phase.h"));
+        let committed = include_str!("../include/moon_phase.h");
+        assert!(generated == committed, "include/moon_phase.h is out of date, it should be:\n{}", generated);
+    }
+
+    #[test]
+    fn names() {
+        for phase in Phase::ALL.iter() {
+            let name = unsafe { CStr::from_ptr(moon_phase_name(MoonPhaseName::from(*phase) as u32)) };
+            assert_eq!(name.to_str(), Ok(phase.to_string().as_str()), "Failed for {:?}", phase);
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(moon_phase_name(8).is_null());
+        assert!(moon_phase_emoji(u32::MAX).is_null());
+        assert!(moon_phase_zodiac_name(12).is_null());
+        assert!(!moon_phase_zodiac_name(11).is_null());
+    }
+}
@@ -0,0 +1,94 @@
+// Integer only variant of the phase calculation, meant for chips without an
+// FPU (e.g. Cortex-M0) where pulling in soft-float is too expensive.
+// Only the synodic phase is computed, which is all that is needed to name the
+// phase. The results agree with `MoonPhase` to within a few seconds.
+// Since it only uses integer math, it can also be evaluated at compile time:
+//
+//     const FULL: Phase = FixedMoonPhase::from_secs(948429600).phase_name;
+
+use crate::Phase;
+
+// MOON_SYNODIC_OFFSET, which is TT, expressed as unix seconds (UTC). ΔT is
+// taken as its value in 2000, 64 s. It changes by about a minute per
+// century around 2000.
+const MOON_SYNODIC_OFFSET_SECS: i64 = 947_182_464 - 64;
+// MOON_SYNODIC_PERIOD expressed in milliseconds.
+const MOON_SYNODIC_PERIOD_MILLIS: i64 = 2_551_442_877;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FixedMoonPhase {
+    pub phase: u16,         // 0 - 65535, 32768 = full
+    pub age: u32,           // Age in seconds of current cycle
+    pub phase_name: Phase,  // New, Full, etc.
+}
+
+impl FixedMoonPhase {
+    pub const fn from_secs(secs: i64) -> Self {
+        // In i128, so any i64 fits after scaling to milliseconds.
+        let elapsed = ((secs as i128 - MOON_SYNODIC_OFFSET_SECS as i128) * 1000)
+            .rem_euclid(MOON_SYNODIC_PERIOD_MILLIS as i128) as u64;
+        let phase = ((elapsed << 16) / MOON_SYNODIC_PERIOD_MILLIS as u64) as u16;
+        // Same as rounding phase * 8 to the nearest integer.
+        let index = ((phase as u32 * 8 + (1 << 15)) >> 16) as usize % 8;
+        FixedMoonPhase {
+            phase,
+            age: (elapsed / 1000) as u32,
+            phase_name: Phase::ALL[index],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MoonPhase;
+
+    #[test]
+    fn matches_float() {
+        // Every 6 hours over a few years.
+        for secs in (900_000_000..1_000_000_000).step_by(21_600) {
+            let fixed = FixedMoonPhase::from_secs(secs);
+            let float = MoonPhase::from_secs(secs);
+            // The float phase is negative before the reference new moon.
+            let diff = (fixed.phase as f64 / 65536. - float.phase).rem_euclid(1.);
+            assert!(!(1e-4..=1. - 1e-4).contains(&diff), "Failed for {}", secs);
+            assert_eq!(fixed.phase_name, float.phase_name, "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn const_evaluation() {
+        const FULL: FixedMoonPhase = FixedMoonPhase::from_secs(948429600); // 2000-01-21T04:40:00+00:00
+        assert_eq!(FULL.phase_name, Phase::Full);
+        assert_eq!(FULL, FixedMoonPhase::from_secs(948429600));
+    }
+
+    #[test]
+    fn extremes() {
+        for &secs in &[i64::MIN, i64::MIN + 1, -10_000_000_000_000_000, i64::MAX / 100, i64::MAX] {
+            let moon_phase = FixedMoonPhase::from_secs(secs);
+            assert!((moon_phase.age as i64) < MOON_SYNODIC_PERIOD_MILLIS / 1000 + 1, "Failed for {}", secs);
+            assert_eq!(moon_phase.phase_name, Phase::ALL[((moon_phase.phase as u32 * 8 + (1 << 15)) >> 16) as usize % 8]);
+        }
+        // A whole number of periods (1000 periods are a whole number of seconds) later.
+        let periods = 1_000_000_000 * MOON_SYNODIC_PERIOD_MILLIS;
+        assert_eq!(FixedMoonPhase::from_secs(948429600 + periods), FixedMoonPhase::from_secs(948429600));
+    }
+
+    #[test]
+    fn phase_detection() {
+        let testcases = [
+            ( 915245340, Phase::Full),            // 1999-01-02T02:49:00+00:00
+            ( 947182380, Phase::New),             // 2000-01-06T18:13:00+00:00
+            ( 948429600, Phase::Full),            // 2000-01-21T04:40:00+00:00
+            ( 949046160, Phase::LastQuarter),     // 2000-01-28T07:56:00+00:00
+            (1642290540, Phase::WaxingGibbous),   // 2022-01-15T23:49:00+00:00
+            (1642610700, Phase::WaningGibbous),  // 2022-01-19T16:45:00+00:00
+        ];
+
+        for (secs, exp) in &testcases {
+            let moon_phase = FixedMoonPhase::from_secs(*secs);
+            assert_eq!(&moon_phase.phase_name, exp, "Failed for {}", secs);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// Bevy plugin keeping a `MoonState` resource in step with the game's clock,
+// for day and night cycles with a real moon:
+//
+//     App::new()
+//         .add_plugins(DefaultPlugins)
+//         .add_plugins(MoonPlugin {
+//             start: 1642463280.,  // Unix seconds the game starts at
+//             time_scale: 60.,     // A game minute per second
+//             observer: Observer::new(52.5, 13.4),
+//         })
+//         .add_systems(Update, |moon: Res<MoonState>, mut light: Query<&mut Transform, With<MoonLight>>| {
+//             ...light.looking_to(-moon.direction, Vec3::Y)...
+//         });
+//
+// The clock is the `MoonClock` resource, which systems can change to jump
+// in time, speed it up or pause it with a scale of 0.
+
+use bevy::app::{App, Plugin, Update};
+use bevy::ecs::change_detection::DetectChanges;
+use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::ecs::system::{Res, ResMut, Resource};
+use bevy::math::Vec3;
+use bevy::time::Time;
+
+use crate::ephemeris::EARTH_RADIUS;
+use crate::observer::{Limb, Observer};
+use crate::{MoonPhase, Phase};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonPlugin {
+    pub start: f64,       // Unix seconds
+    pub time_scale: f64,  // Game seconds per real second
+    pub observer: Observer,
+}
+
+impl Default for MoonPlugin {
+    fn default() -> Self {
+        MoonPlugin {
+            start: 0.,
+            time_scale: 1.,
+            observer: Observer::default(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct MoonClock {
+    pub secs: f64,        // Unix seconds
+    pub time_scale: f64,  // Game seconds per real second
+    pub observer: Observer,
+}
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct MoonState {
+    pub secs: f64,           // Unix seconds
+    pub phase: f64,          // 0 - 1, 0.5 = full
+    pub phase_name: Phase,
+    pub illumination: f64,   // 0 - 1
+    pub age: f64,            // Days
+    pub distance_km: f64,
+    pub altitude: f64,       // Degrees above the horizon, without refraction
+    pub azimuth: f64,        // Degrees from north through east
+    pub direction: Vec3,     // Unit vector to the moon, Y up, -Z north and X east
+    pub lit_limb: Limb,      // Lit side as seen by the observer
+}
+
+impl MoonState {
+    pub fn new(secs: f64, observer: &Observer) -> Self {
+        let moon_phase = MoonPhase::from_secs_float(secs);
+        let topocentric = observer.topocentric(&moon_phase);
+        let (altitude, azimuth) = observer.horizontal(topocentric.j_date, topocentric.longitude, topocentric.latitude);
+        let (altitude_rad, azimuth_rad) = (altitude.to_radians(), azimuth.to_radians());
+        let direction = Vec3::new(
+            (altitude_rad.cos() * azimuth_rad.sin()) as f32,
+            altitude_rad.sin() as f32,
+            -(altitude_rad.cos() * azimuth_rad.cos()) as f32,
+        );
+        MoonState {
+            secs,
+            phase: moon_phase.phase,
+            phase_name: moon_phase.phase_name,
+            illumination: moon_phase.illumination,
+            age: moon_phase.age,
+            distance_km: moon_phase.distance * EARTH_RADIUS,
+            altitude,
+            azimuth,
+            direction,
+            lit_limb: moon_phase.lit_limb(observer.hemisphere()),
+        }
+    }
+}
+
+impl Plugin for MoonPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MoonClock {
+            secs: self.start,
+            time_scale: self.time_scale,
+            observer: self.observer,
+        })
+        .insert_resource(MoonState::new(self.start, &self.observer))
+        .add_systems(Update, (advance_clock, update_state).chain());
+    }
+}
+
+fn advance_clock(time: Res<Time>, mut clock: ResMut<MoonClock>) {
+    clock.secs += time.delta_secs_f64() * clock.time_scale;
+}
+
+fn update_state(clock: Res<MoonClock>, mut state: ResMut<MoonState>) {
+    if clock.is_changed() {
+        *state = MoonState::new(clock.secs, &clock.observer);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn plugin() {
+        // Full moon on 2022-01-17T23:48:00+00:00, high in the south at
+        // Greenwich around midnight.
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(MoonPlugin {
+            start: 1642460400.,
+            time_scale: 3600.,
+            observer: Observer::new(51.48, 0.),
+        });
+        app.update();
+        let state = *app.world().resource::<MoonState>();
+        assert_eq!(state.phase_name, Phase::Full);
+        assert!(state.altitude > 40., "Failed for {}", state.altitude);
+        assert!(state.direction.z > 0. && state.direction.y > 0.5, "Failed for {}", state.direction);
+        assert!((state.direction.length() - 1.).abs() < 1e-5);
+
+        // Six real seconds are six hours, the moon is low in the west.
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs(6));
+        app.update();
+        let later = *app.world().resource::<MoonState>();
+        assert!((later.secs - (1642460400. + 6. * 3600.)).abs() < 1., "Failed for {}", later.secs);
+        assert!((200.0..340.).contains(&later.azimuth), "Failed for {}", later.azimuth);
+        assert!(later.altitude < state.altitude - 10., "Failed for {}", later.altitude);
+        assert!(later.direction.x < 0., "Failed for {}", later.direction);
+    }
+}
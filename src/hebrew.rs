@@ -0,0 +1,234 @@
+// The molad, the mean conjunction of the Hebrew calendar, and the dates of
+// Rosh Chodesh. The calendar counts in parts (chalakim) of 1/1080 hour, and
+// days begin at 6 pm Jerusalem mean time. The arithmetic of the year follows
+// E. M. Reingold and N. Dershowitz, "Calendrical Calculations".
+
+use crate::calendar::civil_from_days;
+use crate::ephemeris::Ephemeris;
+use crate::events::new_moons;
+
+const PARTS_PER_HOUR: i64 = 1080;
+const PARTS_PER_DAY: i64 = 24 * PARTS_PER_HOUR;
+// 29 days, 12 hours and 793 parts.
+const MONTH_PARTS: i64 = 29 * PARTS_PER_DAY + 12 * PARTS_PER_HOUR + 793;
+// Molad of Tishrei in the year 1 (BaHaRaD: day 2, 5 hours, 204 parts), in
+// parts since the start of the first day of that week.
+const BAHARAD: i64 = PARTS_PER_DAY + 5 * PARTS_PER_HOUR + 204;
+// Start of that week, 6 pm on Saturday, in days since 1970-01-01.
+const WEEK_START: f64 = -1373428.25 - 719163.;
+// Jerusalem mean time is 2:20:56 ahead of UTC.
+const JERUSALEM: f64 = 8456.;
+// Rata Die of the day before 1 Tishrei 1, in days since 1970-01-01.
+const EPOCH_DAY: i64 = -1373428 - 719163;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HebrewMonth {
+    Tishrei,
+    Cheshvan,
+    Kislev,
+    Tevet,
+    Shevat,
+    Adar,    // Adar I in leap years
+    AdarII,  // Only in leap years, taken as Adar in common years
+    Nisan,
+    Iyar,
+    Sivan,
+    Tammuz,
+    Av,
+    Elul,
+}
+
+// A molad as announced, and as unix seconds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Molad {
+    pub secs: f64,
+    pub weekday: u32,  // 0 for Sunday, which starts at 6 pm on Saturday
+    pub hours: u32,    // Since 6 pm of the evening before
+    pub parts: u32,    // 0 - 1079, 18 to a minute
+}
+
+impl Molad {
+    fn from_parts(parts: i64) -> Self {
+        Molad {
+            secs: WEEK_START * 86400. + parts as f64 * 86400. / PARTS_PER_DAY as f64 - JERUSALEM,
+            weekday: (parts / PARTS_PER_DAY % 7) as u32,
+            hours: (parts % PARTS_PER_DAY / PARTS_PER_HOUR) as u32,
+            parts: (parts % PARTS_PER_HOUR) as u32,
+        }
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (7 * year as i64 + 1).rem_euclid(19) < 7
+}
+
+// The months of `year` (Anno Mundi) in order, starting with Tishrei.
+pub fn months(year: i32) -> Vec<HebrewMonth> {
+    use HebrewMonth::*;
+    let mut months = vec![Tishrei, Cheshvan, Kislev, Tevet, Shevat, Adar, AdarII, Nisan, Iyar, Sivan, Tammuz, Av, Elul];
+    if !is_leap_year(year) {
+        months.retain(|month| *month != AdarII);
+    }
+    months
+}
+
+// Months from the first Tishrei up to that of `year`.
+fn months_elapsed(year: i32) -> i64 {
+    (235 * year as i64 - 234).div_euclid(19)
+}
+
+// AdarII in common years is Adar, the month in its place.
+fn in_year(year: i32, month: HebrewMonth) -> HebrewMonth {
+    if month == HebrewMonth::AdarII && !is_leap_year(year) {
+        HebrewMonth::Adar
+    } else {
+        month
+    }
+}
+
+fn month_index(year: i32, month: HebrewMonth) -> usize {
+    let month = in_year(year, month);
+    months(year).iter().position(|m| *m == month).unwrap()
+}
+
+// The traditional molad of `month` in `year`.
+pub fn molad(year: i32, month: HebrewMonth) -> Molad {
+    let months = months_elapsed(year) + month_index(year, month) as i64;
+    Molad::from_parts(BAHARAD + months * MONTH_PARTS)
+}
+
+// The true conjunction closest to the molad, in unix seconds.
+pub fn conjunction<E: Ephemeris + ?Sized>(year: i32, month: HebrewMonth, ephemeris: &E) -> f64 {
+    let molad = molad(year, month).secs;
+    new_moons(molad - 86400., molad + 86400., ephemeris)
+        .into_iter()
+        .min_by(|a, b| (a - molad).abs().partial_cmp(&(b - molad).abs()).unwrap())
+        .unwrap()
+}
+
+// Days from the epoch to Rosh Hashanah, with the postponements for the day
+// of the week.
+fn elapsed_days(year: i32) -> i64 {
+    let months = months_elapsed(year);
+    let parts = 12084 + 13753 * months;
+    let days = 29 * months + parts.div_euclid(25920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+// Days since 1970-01-01 of 1 Tishrei.
+fn new_year(year: i32) -> i64 {
+    let (previous, this, next) = (elapsed_days(year - 1), elapsed_days(year), elapsed_days(year + 1));
+    let correction = if next - this == 356 {
+        2
+    } else if this - previous == 382 {
+        1
+    } else {
+        0
+    };
+    EPOCH_DAY + this + correction + 1
+}
+
+pub fn days_in_year(year: i32) -> u32 {
+    (new_year(year + 1) - new_year(year)) as u32
+}
+
+pub fn days_in_month(year: i32, month: HebrewMonth) -> u32 {
+    use HebrewMonth::*;
+    match in_year(year, month) {
+        Cheshvan if days_in_year(year) % 10 == 5 => 30,
+        Kislev if days_in_year(year) % 10 == 3 => 29,
+        Adar if is_leap_year(year) => 30,
+        Tishrei | Kislev | Shevat | Nisan | Sivan | Av => 30,
+        _ => 29,
+    }
+}
+
+// Days since 1970-01-01 of the first of `month`.
+fn first_day(year: i32, month: HebrewMonth) -> i64 {
+    new_year(year)
+        + months(year)[..month_index(year, month)]
+            .iter()
+            .map(|m| days_in_month(year, *m) as i64)
+            .sum::<i64>()
+}
+
+// The Gregorian (year, month, day) of Rosh Chodesh of `month`. That is the
+// first of the month, preceded by the 30th of the previous month if it has
+// one.
+pub fn rosh_chodesh(year: i32, month: HebrewMonth) -> Vec<(i32, u32, u32)> {
+    let first = first_day(year, month);
+    let previous_days = match month {
+        HebrewMonth::Tishrei => days_in_month(year - 1, HebrewMonth::Elul),
+        _ => days_in_month(year, months(year)[month_index(year, month) - 1]),
+    };
+    let days = if previous_days == 30 { vec![first - 1, first] } else { vec![first] };
+    days.into_iter()
+        .map(|day| {
+            let (y, m, d) = civil_from_days(day);
+            (y as i32, m, d)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    #[test]
+    fn baharad() {
+        let molad = molad(1, HebrewMonth::Tishrei);
+        assert_eq!((molad.weekday, molad.hours, molad.parts), (1, 5, 204));
+        // Each molad is 1 day, 12 hours and 793 parts later in the week.
+        let next = super::molad(1, HebrewMonth::Cheshvan);
+        assert_eq!((next.weekday, next.hours, next.parts), (2, 17, 997));
+    }
+
+    #[test]
+    fn molad_5783() {
+        // Sunday night 2022-09-25, 9:00 pm and 6 parts in Jerusalem, which is
+        // already Monday.
+        let molad = molad(5783, HebrewMonth::Tishrei);
+        assert_eq!((molad.weekday, molad.hours, molad.parts), (1, 3, 6));
+        assert!((molad.secs - (1664139600. + 20. - JERUSALEM)).abs() < 1e-3);
+        let conjunction = conjunction(5783, HebrewMonth::Tishrei, &Meeus);
+        assert!((molad.secs - conjunction).abs() < 86400., "Failed for {}", conjunction);
+        assert_eq!(super::molad(5783, HebrewMonth::AdarII), super::molad(5783, HebrewMonth::Adar));
+    }
+
+    #[test]
+    fn years() {
+        assert!(is_leap_year(5782));
+        assert!(!is_leap_year(5783));
+        assert_eq!(months(5782).len(), 13);
+        assert_eq!(days_in_year(5783), 355);
+        assert_eq!(days_in_year(5782), 384);
+        // Rosh Hashanah
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::Tishrei), vec![(2022, 9, 26)]);
+        assert_eq!(rosh_chodesh(5784, HebrewMonth::Tishrei), vec![(2023, 9, 16)]);
+        assert_eq!(rosh_chodesh(5785, HebrewMonth::Tishrei), vec![(2024, 10, 3)]);
+    }
+
+    #[test]
+    fn rosh_chodesh_5783() {
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::Cheshvan), vec![(2022, 10, 25), (2022, 10, 26)]);
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::Kislev), vec![(2022, 11, 24), (2022, 11, 25)]);
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::Nisan), vec![(2023, 3, 23)]);
+    }
+
+    #[test]
+    fn adar_in_common_years() {
+        // 5783 has no Adar II, it is taken as Adar (2023-02-21/22) in every
+        // function rather than running into the next year.
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::Adar), vec![(2023, 2, 21), (2023, 2, 22)]);
+        assert_eq!(rosh_chodesh(5783, HebrewMonth::AdarII), rosh_chodesh(5783, HebrewMonth::Adar));
+        assert_eq!(days_in_month(5783, HebrewMonth::AdarII), 29);
+        // In the leap year 5784 Adar II follows Adar I of 30 days.
+        assert_eq!(rosh_chodesh(5784, HebrewMonth::Adar), vec![(2024, 2, 9), (2024, 2, 10)]);
+        assert_eq!(rosh_chodesh(5784, HebrewMonth::AdarII), vec![(2024, 3, 10), (2024, 3, 11)]);
+    }
+}
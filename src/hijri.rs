@@ -0,0 +1,326 @@
+// Estimating the Islamic (Hijri) date from the computed new moons. Whether a
+// month has begun is decided by a `MonthStart` rule, as the actual calendars
+// differ by country and are set by sighting committees. The result is a
+// prediction, which may be a day off from the announced date.
+
+use crate::calendar::{civil_from_days, days_from_civil};
+use crate::ephemeris::Ephemeris;
+use crate::events::new_moons;
+use crate::MOON_SYNODIC_PERIOD;
+
+// New moon of 2022-07-28T17:55:00+00:00, before 1 Muharram 1444.
+const REFERENCE_NEW_MOON: f64 = 1659117300.;
+const REFERENCE_MONTH: i64 = 1443 * 12;
+
+// How many days after the local day of its new moon a month can start. Rules
+// starting months later are outside of what is searched for.
+const MAX_START_DAYS: i64 = 5;
+
+pub const MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi' al-Awwal",
+    "Rabi' al-Thani",
+    "Jumada al-Ula",
+    "Jumada al-Thaniyah",
+    "Rajab",
+    "Sha'ban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qa'dah",
+    "Dhu al-Hijjah",
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HijriDate {
+    pub year: i32,   // Anno Hegirae
+    pub month: u32,  // 1 - 12
+    pub day: u32,    // 1 - 30
+}
+
+impl HijriDate {
+    pub fn month_name(&self) -> &'static str {
+        MONTHS[self.month as usize - 1]
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Holiday {
+    Ramadan,    // First day of fasting
+    EidAlFitr,
+    EidAlAdha,
+}
+
+impl Holiday {
+    pub const ALL: [Holiday; 3] = [Holiday::Ramadan, Holiday::EidAlFitr, Holiday::EidAlAdha];
+
+    // Month and day in the Hijri calendar.
+    pub fn hijri_month_day(self) -> (u32, u32) {
+        match self {
+            Holiday::Ramadan => (9, 1),
+            Holiday::EidAlFitr => (10, 1),
+            Holiday::EidAlAdha => (12, 10),
+        }
+    }
+}
+
+// Decides on which day a month begins. Days are counted from 1970-01-01 in
+// the local time of the place the calendar is kept for. Closures taking the
+// new moon and returning the day can be used for UTC.
+pub trait MonthStart {
+    // Seconds ahead of UTC.
+    fn utc_offset(&self) -> i32 {
+        0
+    }
+
+    // The first day of the month following the new moon at `new_moon`
+    // (unix seconds).
+    fn first_day(&self, new_moon: f64) -> i64;
+}
+
+impl<F: Fn(f64) -> i64> MonthStart for F {
+    fn first_day(&self, new_moon: f64) -> i64 {
+        self(new_moon)
+    }
+}
+
+// The month begins on the day after the new moon.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct AfterConjunction {
+    pub utc_offset: i32,  // Seconds ahead of UTC
+}
+
+impl MonthStart for AfterConjunction {
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn first_day(&self, new_moon: f64) -> i64 {
+        local_day(new_moon, self.utc_offset) + 1
+    }
+}
+
+// The month begins on the day after the first evening on which the moon is at
+// least `hours` old, a simple stand-in for a sighting. With 0 hours the new
+// moon only has to happen before the evening.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MinimumAge {
+    pub hours: f64,
+    pub utc_offset: i32,  // Seconds ahead of UTC
+    pub evening: u32,     // Seconds after local midnight, e.g. the usual sunset
+}
+
+impl MinimumAge {
+    // Approximates the Umm al-Qura calendar of Saudi Arabia: the month
+    // begins after the new moon if it happens before sunset in Mecca. The
+    // condition that the moon sets after the sun is left out.
+    pub fn umm_al_qura() -> Self {
+        MinimumAge {
+            hours: 0.,
+            utc_offset: 3 * 3600,
+            evening: 18 * 3600 + 40 * 60,
+        }
+    }
+}
+
+impl MonthStart for MinimumAge {
+    fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    fn first_day(&self, new_moon: f64) -> i64 {
+        let offset = self.evening as f64 - self.utc_offset as f64;
+        let mut day = local_day(new_moon, self.utc_offset);
+        while (day as f64 * 86400. + offset - new_moon) / 3600. < self.hours {
+            day += 1;
+        }
+        day + 1
+    }
+}
+
+fn local_day(secs: f64, utc_offset: i32) -> i64 {
+    ((secs + utc_offset as f64) / 86400.).floor() as i64
+}
+
+// The first day of the month following `new_moon` by `rule`, None if the rule
+// starts it before the new moon or more than `MAX_START_DAYS` after.
+fn start_day<R: MonthStart + ?Sized>(new_moon: f64, rule: &R) -> Option<i64> {
+    let first_day = rule.first_day(new_moon);
+    let days = first_day - local_day(new_moon, rule.utc_offset());
+    (0..=MAX_START_DAYS).contains(&days).then_some(first_day)
+}
+
+// Number of the Hijri month following the new moon, 0 for Muharram 1 AH.
+fn month_number(new_moon: f64) -> i64 {
+    let lunations = (new_moon - REFERENCE_NEW_MOON) / (MOON_SYNODIC_PERIOD * 86400.);
+    REFERENCE_MONTH + lunations.round() as i64
+}
+
+// The Hijri date at `secs` (unix seconds), on the local day of `rule`. None if
+// the rule starts a month too late (see `MAX_START_DAYS`) or makes it longer
+// than 30 days.
+pub fn hijri_date<R, E>(secs: f64, rule: &R, ephemeris: &E) -> Option<HijriDate>
+where
+    R: MonthStart + ?Sized,
+    E: Ephemeris + ?Sized,
+{
+    let day = local_day(secs, rule.utc_offset());
+    let from = (day - 31 - MAX_START_DAYS) as f64 * 86400.;
+    let until = (day + 1) as f64 * 86400.;
+    let starts = new_moons(from, until, ephemeris)
+        .into_iter()
+        .map(|new_moon| Some((new_moon, start_day(new_moon, rule)?)))
+        .collect::<Option<Vec<_>>>()?;
+    let (new_moon, first_day) = starts.into_iter().rev().find(|(_, first_day)| *first_day <= day)?;
+    if day - first_day >= 30 {
+        return None;
+    }
+    let month = month_number(new_moon);
+    Some(HijriDate {
+        year: month.div_euclid(12) as i32 + 1,
+        month: month.rem_euclid(12) as u32 + 1,
+        day: (day - first_day) as u32 + 1,
+    })
+}
+
+// First day of the Hijri month, in days since 1970-01-01 local time.
+fn first_day_of_month<R, E>(year: i32, month: u32, rule: &R, ephemeris: &E) -> Option<i64>
+where
+    R: MonthStart + ?Sized,
+    E: Ephemeris + ?Sized,
+{
+    let number = (year as i64 - 1) * 12 + month as i64 - 1;
+    let estimate = REFERENCE_NEW_MOON + (number - REFERENCE_MONTH) as f64 * MOON_SYNODIC_PERIOD * 86400.;
+    // The true new moon is within about 14 hours of the mean one.
+    let new_moon = new_moons(estimate - 2. * 86400., estimate + 2. * 86400., ephemeris)
+        .into_iter()
+        .find(|new_moon| month_number(*new_moon) == number)?;
+    start_day(new_moon, rule)
+}
+
+// The Gregorian dates (year, month, day) of `holiday` in the Gregorian
+// `year`, as kept by `rule`. Usually there is one, but as the Hijri year is
+// shorter some years have two. None if the rule starts a month too late.
+pub fn holiday_dates<R, E>(holiday: Holiday, year: i32, rule: &R, ephemeris: &E) -> Option<Vec<(i32, u32, u32)>>
+where
+    R: MonthStart + ?Sized,
+    E: Ephemeris + ?Sized,
+{
+    let (month, day) = holiday.hijri_month_day();
+    let first_year = hijri_date_of_day(year, 1, 1, rule, ephemeris)?.year;
+    let mut dates = Vec::new();
+    for hijri_year in first_year..=first_year + 1 {
+        let (y, m, d) = civil_from_days(first_day_of_month(hijri_year, month, rule, ephemeris)? + day as i64 - 1);
+        if y == year as i64 {
+            dates.push((y as i32, m, d));
+        }
+    }
+    Some(dates)
+}
+
+// The Hijri date of a Gregorian calendar day, as kept by `rule`, see
+// `hijri_date`.
+pub fn hijri_date_of_day<R, E>(year: i32, month: u32, day: u32, rule: &R, ephemeris: &E) -> Option<HijriDate>
+where
+    R: MonthStart + ?Sized,
+    E: Ephemeris + ?Sized,
+{
+    let noon = days_from_civil(year as i64, month, day) as f64 * 86400. + 43200.;
+    hijri_date(noon - rule.utc_offset() as f64, rule, ephemeris)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    const MECCA: i32 = 3 * 3600;
+
+    #[test]
+    fn after_conjunction() {
+        let rule = AfterConjunction { utc_offset: MECCA };
+        // New moon on 2022-07-28 at 20:55 local time.
+        let date = hijri_date_of_day(2022, 7, 29, &rule, &Meeus).unwrap();
+        assert_eq!(date, HijriDate { year: 1444, month: 1, day: 1 });
+        assert_eq!(date.month_name(), "Muharram");
+        let date = hijri_date_of_day(2022, 7, 28, &rule, &Meeus).unwrap();
+        assert_eq!((date.year, date.month), (1443, 12));
+    }
+
+    #[test]
+    fn minimum_age() {
+        // Close to the Umm al-Qura calendar, which had 2 Ramadan 1443 on
+        // 2022-04-03 and 1 Muharram 1444 on 2022-07-30.
+        let rule = MinimumAge {
+            hours: 0.,
+            utc_offset: MECCA,
+            evening: 18 * 3600,
+        };
+        let date = hijri_date_of_day(2022, 4, 3, &rule, &Meeus);
+        assert_eq!(date, Some(HijriDate { year: 1443, month: 9, day: 2 }));
+        let date = hijri_date_of_day(2022, 7, 30, &rule, &Meeus);
+        assert_eq!(date, Some(HijriDate { year: 1444, month: 1, day: 1 }));
+
+        // A day later with a day old moon.
+        let rule = MinimumAge { hours: 24., ..rule };
+        let date = hijri_date_of_day(2022, 4, 3, &rule, &Meeus);
+        assert_eq!(date, Some(HijriDate { year: 1443, month: 9, day: 1 }));
+    }
+
+    #[test]
+    fn late_start() {
+        // Months starting more than `MAX_START_DAYS` after the new moon.
+        let rule = MinimumAge {
+            hours: 120.,
+            utc_offset: MECCA,
+            evening: 18 * 3600,
+        };
+        assert_eq!(hijri_date_of_day(2022, 4, 3, &rule, &Meeus), None);
+        assert_eq!(holiday_dates(Holiday::Ramadan, 2022, &rule, &Meeus), None);
+        let rule = |new_moon: f64| (new_moon / 86400.).floor() as i64 + 40;
+        assert_eq!(hijri_date(947116800., &rule, &Meeus), None);
+    }
+
+    #[test]
+    fn holidays() {
+        let rule = MinimumAge::umm_al_qura();
+        // As announced in Saudi Arabia.
+        assert_eq!(holiday_dates(Holiday::Ramadan, 2022, &rule, &Meeus), Some(vec![(2022, 4, 2)]));
+        assert_eq!(holiday_dates(Holiday::EidAlFitr, 2022, &rule, &Meeus), Some(vec![(2022, 5, 2)]));
+        assert_eq!(holiday_dates(Holiday::EidAlAdha, 2022, &rule, &Meeus), Some(vec![(2022, 7, 9)]));
+        // Ramadan 1451 and 1452.
+        let ramadan = holiday_dates(Holiday::Ramadan, 2030, &AfterConjunction::default(), &Meeus).unwrap();
+        assert_eq!(ramadan.len(), 2);
+        assert_eq!((ramadan[0].1, ramadan[1].1), (1, 12));
+        for (y, m, d) in ramadan {
+            let date = hijri_date_of_day(y, m, d, &AfterConjunction::default(), &Meeus).unwrap();
+            assert_eq!((date.month, date.day), (9, 1));
+        }
+    }
+
+    #[test]
+    fn closure_rule() {
+        // Month starts with the UTC day of the new moon.
+        let rule = |new_moon: f64| (new_moon / 86400.).floor() as i64;
+        // New moon on 2000-01-06T18:14:00+00:00, 29 Ramadan 1420 in Saudi Arabia.
+        let date = hijri_date(947116800., &rule, &Meeus);
+        assert_eq!(date, Some(HijriDate { year: 1420, month: 10, day: 1 }));
+    }
+
+    #[test]
+    fn month_lengths() {
+        let rule = AfterConjunction::default();
+        let mut previous = hijri_date_of_day(2020, 1, 1, &rule, &Meeus).unwrap();
+        for day in 1..1000 {
+            let secs = 1577880000. + day as f64 * 86400.;
+            let date = hijri_date(secs, &rule, &Meeus).unwrap();
+            if date.day == 1 {
+                assert!(previous.day == 29 || previous.day == 30, "Failed for {}", secs);
+                assert_eq!((date.month + 10) % 12 + 1, previous.month);
+            } else {
+                assert_eq!(date.day, previous.day + 1, "Failed for {}", secs);
+            }
+            previous = date;
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Names of the phases, zodiac signs, months and weekdays in a few languages,
+// and dates written out the way they are in each.
+
+use crate::{Phase, PrimaryPhase, Zodiac};
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+// In cycle order, starting at new moon.
+const PHASES: [[&str; 8]; 4] = [
+    [
+        "New Moon", "Waxing Crescent", "First Quarter", "Waxing Gibbous",
+        "Full Moon", "Waning Gibbous", "Last Quarter", "Waning Crescent",
+    ],
+    [
+        "Neumond", "Zunehmende Sichel", "Erstes Viertel", "Zunehmender Mond",
+        "Vollmond", "Abnehmender Mond", "Letztes Viertel", "Abnehmende Sichel",
+    ],
+    [
+        "Nouvelle lune", "Premier croissant", "Premier quartier", "Gibbeuse croissante",
+        "Pleine lune", "Gibbeuse décroissante", "Dernier quartier", "Dernier croissant",
+    ],
+    [
+        "Luna nueva", "Luna creciente", "Cuarto creciente", "Gibosa creciente",
+        "Luna llena", "Gibosa menguante", "Cuarto menguante", "Luna menguante",
+    ],
+];
+
+// In the order of `Zodiac`, Pisces first.
+const ZODIAC: [[&str; 12]; 4] = [
+    [
+        "Pisces", "Aries", "Taurus", "Gemini", "Cancer", "Leo",
+        "Virgo", "Libra", "Scorpio", "Sagittarius", "Capricorn", "Aquarius",
+    ],
+    [
+        "Fische", "Widder", "Stier", "Zwillinge", "Krebs", "Löwe",
+        "Jungfrau", "Waage", "Skorpion", "Schütze", "Steinbock", "Wassermann",
+    ],
+    [
+        "Poissons", "Bélier", "Taureau", "Gémeaux", "Cancer", "Lion",
+        "Vierge", "Balance", "Scorpion", "Sagittaire", "Capricorne", "Verseau",
+    ],
+    [
+        "Piscis", "Aries", "Tauro", "Géminis", "Cáncer", "Leo",
+        "Virgo", "Libra", "Escorpio", "Sagitario", "Capricornio", "Acuario",
+    ],
+];
+
+const MONTHS: [[&str; 12]; 4] = [
+    [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+    [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+    [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ],
+];
+
+// Monday first.
+const WEEKDAYS: [[&str; 7]; 4] = [
+    ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+    ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+    ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+    ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+];
+
+impl Language {
+    pub const ALL: [Language; 4] = [Language::English, Language::German, Language::French, Language::Spanish];
+
+    // The language of a tag such as "de", "fr-CA" or a POSIX locale such as
+    // "es_ES.UTF-8". None for other languages and for "C" and "POSIX".
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let code = tag.split(['_', '-', '.', '@']).next()?;
+        Some(match code.to_lowercase().as_str() {
+            "en" => Language::English,
+            "de" => Language::German,
+            "fr" => Language::French,
+            "es" => Language::Spanish,
+            _ => return None,
+        })
+    }
+
+    // The ISO 639-1 code.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn phase(self, phase: Phase) -> &'static str {
+        PHASES[self as usize][phase.index()]
+    }
+
+    pub fn primary_phase(self, phase: PrimaryPhase) -> &'static str {
+        self.phase(phase.into())
+    }
+
+    pub fn zodiac(self, zodiac: Zodiac) -> &'static str {
+        ZODIAC[self as usize][zodiac as usize]
+    }
+
+    // `month` from 1 (January) to 12.
+    pub fn month(self, month: u32) -> &'static str {
+        MONTHS[self as usize][(month as usize + 11) % 12]
+    }
+
+    // `weekday` from 0 (Monday) to 6.
+    pub fn weekday(self, weekday: usize) -> &'static str {
+        WEEKDAYS[self as usize][weekday % 7]
+    }
+
+    // Whether numbers are written with a decimal comma.
+    pub const fn decimal_comma(self) -> bool {
+        !matches!(self, Language::English)
+    }
+
+    // The date written out in full, e.g. "Tuesday, January 18, 2022" or
+    // "Dienstag, 18. Januar 2022". `weekday` as for `weekday`.
+    pub fn date(self, year: i32, month: u32, day: u32, weekday: usize) -> String {
+        let (weekday, month) = (self.weekday(weekday), self.month(month));
+        match self {
+            Language::English => format!("{}, {} {}, {}", weekday, month, day, year),
+            Language::German => format!("{}, {}. {} {}", weekday, day, month, year),
+            Language::French => format!("{} {} {} {}", weekday, day, month, year),
+            Language::Spanish => format!("{}, {} de {} de {}", weekday, day, month, year),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(Language::German.phase(Phase::Full), "Vollmond");
+        assert_eq!(Language::French.primary_phase(PrimaryPhase::LastQuarter), "Dernier quartier");
+        assert_eq!(Language::Spanish.zodiac(Zodiac::Sagittarius), "Sagitario");
+        assert_eq!(Language::English.month(12), "December");
+        assert_eq!(Language::German.weekday(6), "Sonntag");
+        for phase in Phase::ALL.iter() {
+            assert_eq!(Language::English.phase(*phase), phase.to_string(), "Failed for {:?}", phase);
+        }
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(Language::from_tag("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_tag("fr-CA"), Some(Language::French));
+        assert_eq!(Language::from_tag("ES"), Some(Language::Spanish));
+        assert_eq!(Language::from_tag("en_US"), Some(Language::English));
+        assert_eq!(Language::from_tag("C"), None);
+        assert_eq!(Language::from_tag("ja_JP.UTF-8"), None);
+        for language in Language::ALL.iter() {
+            assert_eq!(Language::from_tag(language.code()), Some(*language));
+        }
+    }
+
+    #[test]
+    fn dates() {
+        let dates = [
+            (Language::English, "Tuesday, January 18, 2022"),
+            (Language::German, "Dienstag, 18. Januar 2022"),
+            (Language::French, "mardi 18 janvier 2022"),
+            (Language::Spanish, "martes, 18 de enero de 2022"),
+        ];
+        for (language, date) in dates.iter() {
+            assert_eq!(language.date(2022, 1, 18, 1), *date, "Failed for {:?}", language);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// iCalendar (RFC 5545) output of the new, full and quarter moons, to
+// subscribe to from calendar apps. The events are computed with `Meeus` and
+// have no duration. DTSTAMP is set to the event itself, so the same range
+// always gives the same output.
+
+use std::io;
+
+use crate::algorithm::Meeus;
+use crate::calendar::format_utc;
+use crate::events::primary_phases;
+use crate::naming::PrimaryPhase;
+
+fn summary(phase: PrimaryPhase) -> &'static str {
+    match phase {
+        PrimaryPhase::New => "New Moon",
+        PrimaryPhase::FirstQuarter => "First Quarter",
+        PrimaryPhase::Full => "Full Moon",
+        PrimaryPhase::LastQuarter => "Last Quarter",
+    }
+}
+
+// UTC in the basic format, e.g. 20220117T234800Z.
+fn utc(secs: f64) -> String {
+    format_utc(secs).replace(['-', ':'], "")
+}
+
+// Writes a VCALENDAR with one VEVENT per phase in from..until (unix seconds).
+pub fn write_ics<W: io::Write>(writer: &mut W, from: f64, until: f64) -> io::Result<()> {
+    write!(writer, "BEGIN:VCALENDAR\r\n")?;
+    write!(writer, "VERSION:2.0\r\n")?;
+    write!(writer, "PRODID:-//moon-phase//moon-phase {}//EN\r\n", env!("CARGO_PKG_VERSION"))?;
+    write!(writer, "CALSCALE:GREGORIAN\r\n")?;
+    for (phase, secs) in primary_phases(from, until, &Meeus) {
+        let time = utc(secs);
+        write!(writer, "BEGIN:VEVENT\r\n")?;
+        write!(writer, "UID:{}-{:?}@moon-phase\r\n", time, phase)?;
+        write!(writer, "DTSTAMP:{}\r\n", time)?;
+        write!(writer, "DTSTART:{}\r\n", time)?;
+        write!(writer, "SUMMARY:{}\r\n", summary(phase))?;
+        write!(writer, "TRANSP:TRANSPARENT\r\n")?;
+        write!(writer, "END:VEVENT\r\n")?;
+    }
+    write!(writer, "END:VCALENDAR\r\n")
+}
+
+pub fn to_ics(from: f64, until: f64) -> String {
+    let mut ics = Vec::new();
+    write_ics(&mut ics, from, until).unwrap();
+    String::from_utf8(ics).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn january_2022() {
+        let ics = to_ics(1640995200., 1643673600.);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 4);
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let full = ics.lines().position(|line| line == "SUMMARY:Full Moon").unwrap();
+        let start = ics.lines().nth(full - 1).unwrap();
+        assert!(start.starts_with("DTSTART:20220117T23"), "Failed for {}", start);
+        assert!(ics.lines().all(|line| line.len() <= 75));
+        assert_eq!(utc(0.), "19700101T000000Z");
+    }
+}
@@ -0,0 +1,304 @@
+// `Ephemeris` backed by a JPL development ephemeris (DE430, DE440, ...) in
+// SPICE kernel (SPK) format, e.g. de440s.bsp from
+// https://naif.jpl.nasa.gov/pub/naif/generic_kernels/spk/planets/
+//
+// The kernel is read by ANISE (the `anise` crate), which has to be in the
+// byte order of the machine, as the published little endian kernels are on
+// x86 and ARM. Positions are geometric, rotated from the ICRF to the mean
+// ecliptic and equinox of date to match the other models. Dates are UT, like
+// for every `Ephemeris`, and converted to the kernel's TDB with `delta_t`.
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anise::constants::frames::{EARTH_J2000, MOON_J2000, SUN_J2000};
+use anise::naif::daf::datatypes::{Type2ChebyshevSet, Type3ChebyshevSet};
+use anise::naif::daf::{DafDataType, NAIFDataSet, NAIFSummaryRecord};
+use anise::prelude::{Almanac, Epoch, Frame, SPK};
+
+use crate::ephemeris::{Ephemeris, MoonPosition, AU};
+use crate::time::{delta_t, TimeScale};
+
+const J2000: f64 = 2451545.0;
+const RECORD_LEN: usize = 1024;
+
+#[derive(Clone)]
+pub struct JplEphemeris {
+    almanac: Almanac,
+}
+
+impl fmt::Debug for JplEphemeris {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JplEphemeris").finish_non_exhaustive()
+    }
+}
+
+impl JplEphemeris {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    // Checks the summary records and every Chebyshev segment up front, so a
+    // truncated or damaged kernel is an error here rather than a wrong
+    // position or a panic later.
+    pub fn from_bytes(data: Vec<u8>) -> io::Result<Self> {
+        let records = summary_records(&data)?;
+        let spk = SPK::parse(data).map_err(invalid)?;
+        for record in records {
+            for (index, summary) in spk.data_summaries(Some(record)).map_err(invalid)?.iter().enumerate() {
+                if summary.is_empty() {
+                    continue;
+                }
+                match summary.data_type().map_err(invalid)? {
+                    DafDataType::Type2ChebyshevTriplet => spk
+                        .nth_data::<Type2ChebyshevSet>(Some(record), index)
+                        .map_err(invalid)?
+                        .check_integrity()
+                        .map_err(invalid)?,
+                    DafDataType::Type3ChebyshevSextuplet => spk
+                        .nth_data::<Type3ChebyshevSet>(Some(record), index)
+                        .map_err(invalid)?
+                        .check_integrity()
+                        .map_err(invalid)?,
+                    _ => {}
+                }
+            }
+        }
+        Ok(JplEphemeris {
+            almanac: Almanac::from_spk(spk),
+        })
+    }
+
+    // Whether the moon and the sun are both available at the Julian date.
+    pub fn covers(&self, j_date: f64) -> bool {
+        self.geocentric(j_date).is_some()
+    }
+
+    // `Ephemeris::moon`, None if the kernel doesn't cover the date.
+    pub fn try_moon(&self, j_date: f64) -> Option<MoonPosition> {
+        let (moon, _) = self.geocentric(j_date)?;
+        let (longitude, latitude, distance) = ecliptic_of_date(moon, j_date);
+        Some(MoonPosition {
+            longitude,
+            latitude,
+            distance,
+        })
+    }
+
+    // Longitude in degrees and distance in AU of the sun, None if the kernel
+    // doesn't cover the date.
+    pub fn try_sun(&self, j_date: f64) -> Option<(f64, f64)> {
+        let (_, sun) = self.geocentric(j_date)?;
+        let (longitude, _, distance) = ecliptic_of_date(sun, j_date);
+        Some((longitude, distance / AU))
+    }
+
+    // Position of `target` seen from the center of the earth in km, ICRF.
+    fn position(&self, target: Frame, epoch: Epoch) -> Option<[f64; 3]> {
+        let state = self.almanac.translate_geometric(target, EARTH_J2000, epoch).ok()?;
+        let radius = state.radius_km;
+        Some([radius.x, radius.y, radius.z])
+    }
+
+    // Geocentric positions of the moon and the sun, in km, ICRF, at a
+    // Julian date in UT. The kernels are in TDB, which differs from TT by
+    // less than 2 ms.
+    fn geocentric(&self, j_date: f64) -> Option<([f64; 3], [f64; 3])> {
+        let epoch = Epoch::from_et_seconds((TimeScale::Utc.to_tt(j_date) - J2000) * 86400.);
+        Some((self.position(MOON_J2000, epoch)?, self.position(SUN_J2000, epoch)?))
+    }
+}
+
+// Panics if the kernel doesn't cover the date, see `covers` and the `try_`
+// methods.
+impl Ephemeris for JplEphemeris {
+    fn moon(&self, j_date: f64) -> MoonPosition {
+        self.try_moon(j_date).unwrap_or_else(|| panic!("kernel has no data at JD {}", j_date))
+    }
+
+    fn sun_longitude(&self, j_date: f64) -> f64 {
+        self.try_sun(j_date).unwrap_or_else(|| panic!("kernel has no data at JD {}", j_date)).0
+    }
+
+    fn sun_distance(&self, j_date: f64) -> f64 {
+        self.try_sun(j_date).unwrap_or_else(|| panic!("kernel has no data at JD {}", j_date)).1
+    }
+}
+
+// The (1 based) summary records of a DAF in native byte order, following the
+// forward pointers from the file record. ANISE follows them without a bound,
+// so a loop has to be caught before it parses the file.
+fn summary_records(data: &[u8]) -> io::Result<Vec<usize>> {
+    let word = |offset: usize, len: usize| {
+        data.get(offset..offset.saturating_add(len))
+            .ok_or_else(|| invalid("unexpected end of file"))
+    };
+    let mut records = Vec::new();
+    let first = i32::from_ne_bytes(word(76, 4)?.try_into().unwrap());
+    let mut record = usize::try_from(first).map_err(|_| invalid("summary record is corrupt"))?;
+    while record != 0 {
+        // No chain can have more records than the file.
+        if records.len() >= data.len() / RECORD_LEN {
+            return Err(invalid("summary records form a loop"));
+        }
+        records.push(record);
+        let next = f64::from_ne_bytes(word((record - 1).saturating_mul(RECORD_LEN), 8)?.try_into().unwrap());
+        if !(0. ..=(data.len() / RECORD_LEN) as f64).contains(&next) || next.fract() != 0. {
+            return Err(invalid("summary record is corrupt"));
+        }
+        record = next as usize;
+    }
+    Ok(records)
+}
+
+fn invalid<E: fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+// Precesses an ICRF vector to the mean equator of date (chapter 21 of Meeus,
+// "Astronomical Algorithms") and rotates it onto the mean ecliptic of date.
+// Returns longitude and latitude in degrees and the length of the vector.
+fn ecliptic_of_date(vector: [f64; 3], j_date: f64) -> (f64, f64, f64) {
+    let t = (j_date - J2000) / 36525.;
+    let arcsec = |value: f64| (value / 3600.).to_radians();
+    let zeta = arcsec(2306.2181 * t + 0.30188 * t * t + 0.017998 * t * t * t);
+    let z = arcsec(2306.2181 * t + 1.09468 * t * t + 0.018203 * t * t * t);
+    let theta = arcsec(2004.3109 * t - 0.42665 * t * t - 0.041833 * t * t * t);
+    let epsilon = arcsec(84381.448 - 46.8150 * t - 0.00059 * t * t + 0.001813 * t * t * t);
+
+    let [x, y, z0] = vector;
+    // Rotate by zeta around the pole, then by theta towards it, then by z.
+    let (x, y) = (x * zeta.cos() - y * zeta.sin(), x * zeta.sin() + y * zeta.cos());
+    let (x, z0) = (x * theta.cos() - z0 * theta.sin(), x * theta.sin() + z0 * theta.cos());
+    let (x, y) = (x * z.cos() - y * z.sin(), x * z.sin() + y * z.cos());
+    // Equatorial to ecliptic.
+    let (y, z0) = (
+        y * epsilon.cos() + z0 * epsilon.sin(),
+        -y * epsilon.sin() + z0 * epsilon.cos(),
+    );
+
+    let distance = (x * x + y * y + z0 * z0).sqrt();
+    let longitude = y.atan2(x).to_degrees().rem_euclid(360.);
+    let latitude = (z0 / distance).asin().to_degrees();
+    (longitude, latitude, distance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MoonPhase;
+
+    const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+    const EARTH_MOON_BARYCENTER: i32 = 3;
+    const SUN: i32 = 10;
+    const MOON: i32 = 301;
+    const EARTH: i32 = 399;
+
+    // Builds a little endian SPK with one constant (degree 0) segment per
+    // (target, center, position), valid from -1e10 to 1e10 seconds.
+    fn kernel(segments: &[(i32, i32, [f64; 3])]) -> Vec<u8> {
+        let mut data = vec![0u8; 3 * RECORD_LEN];
+        data[..8].copy_from_slice(b"DAF/SPK ");
+        data[8..12].copy_from_slice(&2i32.to_le_bytes());
+        data[12..16].copy_from_slice(&6i32.to_le_bytes());
+        data[76..80].copy_from_slice(&2i32.to_le_bytes());
+        data[88..96].copy_from_slice(b"LTL-IEEE");
+
+        let summaries = RECORD_LEN;
+        data[summaries + 16..summaries + 24].copy_from_slice(&(segments.len() as f64).to_le_bytes());
+        for (i, (target, center, position)) in segments.iter().enumerate() {
+            let first = data.len() / 8 + 1;
+            // One record: mid, radius and one coefficient per axis, then the
+            // directory with init, interval, record length and count.
+            for value in [0., 1e10, position[0], position[1], position[2], -1e10, 2e10, 5., 1.].iter() {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            let last = data.len() / 8;
+            let summary = summaries + 24 + i * 40;
+            data[summary..summary + 8].copy_from_slice(&(-1e10f64).to_le_bytes());
+            data[summary + 8..summary + 16].copy_from_slice(&1e10f64.to_le_bytes());
+            let ints = [*target, *center, 1, 2, first as i32, last as i32];
+            for (j, value) in ints.iter().enumerate() {
+                let offset = summary + 16 + j * 4;
+                data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    // The moon 384400 km along the x axis, the sun along -x.
+    fn full_moon_kernel() -> Vec<u8> {
+        kernel(&[
+            (MOON, EARTH_MOON_BARYCENTER, [380000., 0., 0.]),
+            (EARTH, EARTH_MOON_BARYCENTER, [-4400., 0., 0.]),
+            (EARTH_MOON_BARYCENTER, SOLAR_SYSTEM_BARYCENTER, [AU + 4400., 0., 0.]),
+            (SUN, SOLAR_SYSTEM_BARYCENTER, [0., 0., 0.]),
+        ])
+    }
+
+    #[test]
+    fn constant_positions() {
+        let ephemeris = JplEphemeris::from_bytes(full_moon_kernel()).unwrap();
+        assert!(ephemeris.covers(J2000));
+        assert!(!ephemeris.covers(J2000 + 200_000.));
+
+        let moon = ephemeris.moon(J2000);
+        assert!((moon.distance - 384400.).abs() < 1e-6);
+        assert!(moon.longitude.abs() < 1e-9 && moon.latitude.abs() < 1e-9, "{:?}", moon);
+        assert!((ephemeris.sun_longitude(J2000) - 180.).abs() < 1e-9);
+        assert!((ephemeris.sun_distance(J2000) - 1.).abs() < 1e-6);
+
+        let moon_phase = MoonPhase::from_ephemeris(J2000, &ephemeris);
+        assert_eq!(moon_phase.phase_name, crate::Phase::Full);
+
+        assert_eq!(ephemeris.try_moon(J2000 + 200_000.), None);
+        assert_eq!(ephemeris.try_sun(J2000 + 200_000.), None);
+        assert!(ephemeris.try_sun(J2000).is_some());
+    }
+
+    #[test]
+    fn reads_ut() {
+        // The segments end 1e10 s (TDB) after J2000, in 2316, when ΔT is
+        // over 12 minutes. A UT date 400 s before that is already past it.
+        let ephemeris = JplEphemeris::from_bytes(full_moon_kernel()).unwrap();
+        let end = J2000 + 1e10 / 86400.;
+        assert!(delta_t(2316.) > 700.);
+        assert!(!ephemeris.covers(end - 400. / 86400.));
+        assert!(ephemeris.covers(end - 2. * delta_t(2316.) / 86400.));
+    }
+
+    #[test]
+    fn damaged_kernel() {
+        // Cut off in the middle of the last segment.
+        let mut data = full_moon_kernel();
+        data.truncate(data.len() - 16);
+        assert!(JplEphemeris::from_bytes(data).is_err());
+
+        // The summary record pointing back at itself, and a forward pointer
+        // out of the file.
+        let mut data = full_moon_kernel();
+        data[RECORD_LEN..RECORD_LEN + 8].copy_from_slice(&2f64.to_le_bytes());
+        assert!(JplEphemeris::from_bytes(data).is_err());
+        let mut data = full_moon_kernel();
+        data[76..80].copy_from_slice(&(-5i32).to_le_bytes());
+        assert!(JplEphemeris::from_bytes(data).is_err());
+
+        // A record size too small for a coefficient per axis, and more
+        // records than there is data, in the first segment's directory.
+        for (word, value) in [(7, 2.), (8, 3.)] {
+            let mut data = full_moon_kernel();
+            let offset = 3 * RECORD_LEN + word * 8;
+            data[offset..offset + 8].copy_from_slice(&f64::to_le_bytes(value));
+            assert!(JplEphemeris::from_bytes(data).is_err(), "Failed for {}", word);
+        }
+    }
+
+    #[test]
+    fn not_a_kernel() {
+        assert!(JplEphemeris::from_bytes(vec![0; 2048]).is_err());
+        assert!(JplEphemeris::from_bytes(b"DAF/SPK ".to_vec()).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// JSON output of a `MoonPhase` for other tools. The field names and their
+// meaning are fixed for a given `JSON_SCHEMA_VERSION`: fields may be added,
+// but renaming, removing or changing one bumps the version. Names of phases
+// and zodiac signs are spelled out here rather than taken from the enums, so
+// renaming a variant doesn't change the output.
+//
+// Version 1:
+//   schema_version        1
+//   timestamp             RFC 3339 in UTC, whole seconds
+//   julian_date
+//   phase                 0 - 1, 0.5 = full
+//   age_days              Days since new moon
+//   illumination          0 - 1
+//   illumination_percent  0 - 100
+//   phase_name            "new", "waxing_crescent", "first_quarter",
+//                         "waxing_gibbous", "full", "waning_gibbous",
+//                         "last_quarter" or "waning_crescent"
+//   emoji                 Moon emoji of the phase
+//   distance_km
+//   distance_earth_radii
+//   latitude              Ecliptic, degrees
+//   longitude             Ecliptic, degrees
+//   zodiac                Lowercase English name of the constellation
+//
+// Numbers that aren't finite are written as null.
+//
+// `to_home_assistant` and `to_waybar` give the shapes Home Assistant and
+// status bars expect instead, which follow those tools rather than the
+// version above.
+
+use std::fmt::Write;
+
+use crate::algorithm::Meeus;
+use crate::calendar::format_utc;
+use crate::ephemeris::EARTH_RADIUS;
+use crate::events::primary_phases;
+use crate::observer::Observer;
+use crate::{MoonPhase, Phase, Zodiac};
+
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+// Builds a flat JSON object, one field at a time.
+pub(crate) struct JsonObject {
+    json: String,
+}
+
+impl JsonObject {
+    pub(crate) fn new() -> Self {
+        JsonObject { json: String::from("{") }
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.json.len() > 1 {
+            self.json.push(',');
+        }
+        write_string(&mut self.json, key);
+        self.json.push(':');
+    }
+
+    pub(crate) fn string(mut self, key: &str, value: &str) -> Self {
+        self.key(key);
+        write_string(&mut self.json, value);
+        self
+    }
+
+    pub(crate) fn number(mut self, key: &str, value: f64) -> Self {
+        self.key(key);
+        if value.is_finite() {
+            write!(self.json, "{}", value).unwrap();
+        } else {
+            self.json.push_str("null");
+        }
+        self
+    }
+
+    pub(crate) fn boolean(mut self, key: &str, value: bool) -> Self {
+        self.key(key);
+        self.json.push_str(if value { "true" } else { "false" });
+        self
+    }
+
+    // `value` has to be valid JSON already, e.g. a nested object.
+    pub(crate) fn raw(mut self, key: &str, value: &str) -> Self {
+        self.key(key);
+        self.json.push_str(value);
+        self
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.json.push('}');
+        self.json
+    }
+}
+
+fn write_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+// `value` as a quoted JSON string.
+pub fn json_string(value: &str) -> String {
+    let mut json = String::new();
+    write_string(&mut json, value);
+    json
+}
+
+// The name of `phase` in the `phase_name` field.
+pub fn phase_key(phase: Phase) -> &'static str {
+    match phase {
+        Phase::New => "new",
+        Phase::WaxingCrescent => "waxing_crescent",
+        Phase::FirstQuarter => "first_quarter",
+        Phase::WaxingGibbous => "waxing_gibbous",
+        Phase::Full => "full",
+        Phase::WaningGibbous => "waning_gibbous",
+        Phase::LastQuarter => "last_quarter",
+        Phase::WaningCrescent => "waning_crescent",
+    }
+}
+
+// States of Home Assistant's moon sensor.
+fn home_assistant_state(phase: Phase) -> &'static str {
+    match phase {
+        Phase::New => "new_moon",
+        Phase::Full => "full_moon",
+        phase => phase_key(phase),
+    }
+}
+
+// The name of `zodiac` in the `zodiac` field.
+pub fn zodiac_key(zodiac: Zodiac) -> &'static str {
+    match zodiac {
+        Zodiac::Pisces => "pisces",
+        Zodiac::Aries => "aries",
+        Zodiac::Taurus => "taurus",
+        Zodiac::Gemini => "gemini",
+        Zodiac::Cancer => "cancer",
+        Zodiac::Leo => "leo",
+        Zodiac::Virgo => "virgo",
+        Zodiac::Libra => "libra",
+        Zodiac::Scorpio => "scorpio",
+        Zodiac::Sagittarius => "sagittarius",
+        Zodiac::Capricorn => "capricorn",
+        Zodiac::Aquarius => "aquarius",
+    }
+}
+
+impl MoonPhase {
+    // A single line JSON object, see the top of this file for the fields.
+    pub fn to_json(&self) -> String {
+        JsonObject::new()
+            .number("schema_version", JSON_SCHEMA_VERSION as f64)
+            .string("timestamp", &format_utc((self.j_date - 2440587.5) * 86400.))
+            .number("julian_date", self.j_date)
+            .number("phase", self.phase)
+            .number("age_days", self.age)
+            .number("illumination", self.illumination)
+            .number("illumination_percent", self.illumination * 100.)
+            .string("phase_name", phase_key(self.phase_name))
+            .string("emoji", &self.phase_name.emoji().to_string())
+            .number("distance_km", self.distance * EARTH_RADIUS)
+            .number("distance_earth_radii", self.distance)
+            .number("latitude", self.latitude)
+            .number("longitude", self.longitude)
+            .string("zodiac", zodiac_key(self.zodiac_name))
+            .finish()
+    }
+
+    // `{"state": ..., "attributes": {...}}` as Home Assistant's moon sensor
+    // reports it, for a template or MQTT sensor. Rising is whether the moon
+    // climbs in the sky of `observer`, a minute later it stands higher.
+    pub fn to_home_assistant(&self, observer: &Observer) -> String {
+        let altitude = observer.moon_altitude(self);
+        let later = observer.moon_altitude(&MoonPhase::from_julian_date(self.j_date + 60. / 86400.));
+        let icon = format!("mdi:moon-{}", home_assistant_state(self.phase_name).replace("_moon", "").replace('_', "-"));
+        let attributes = JsonObject::new()
+            .string("friendly_name", "Moon")
+            .string("icon", &icon)
+            .number("illumination", (self.illumination * 1000.).round() / 10.)
+            .number("age", (self.age * 100.).round() / 100.)
+            .number("altitude", (altitude * 100.).round() / 100.)
+            .boolean("above_horizon", altitude > 0.)
+            .boolean("rising", later > altitude)
+            .boolean("waxing", self.phase < 0.5)
+            .finish();
+        JsonObject::new()
+            .string("state", home_assistant_state(self.phase_name))
+            .raw("attributes", &attributes)
+            .finish()
+    }
+
+    // A line for a Waybar custom module (`"return-type": "json"`) or an
+    // i3status-rust custom block (`json = true`): the emoji as text, the
+    // phase as alt and CSS class, and details and the next phases in the
+    // tooltip.
+    pub fn to_waybar(&self) -> String {
+        let secs = (self.j_date - 2440587.5) * 86400.;
+        let mut tooltip = format!(
+            "{}\nIllumination: {:.0}%\nAge: {:.1} days\nDistance: {:.0} km\nZodiac: {:?}",
+            self.phase_name,
+            self.illumination * 100.,
+            self.age,
+            self.distance * EARTH_RADIUS,
+            self.zodiac_name
+        );
+        for (phase, secs) in primary_phases(secs, secs + 30. * 86400., &Meeus).into_iter().take(4) {
+            let time = format_utc(secs);
+            write!(tooltip, "\n{}: {} {} UTC", phase, &time[..10], &time[11..16]).unwrap();
+        }
+        JsonObject::new()
+            .string("text", &self.phase_name.emoji().to_string())
+            .string("alt", phase_key(self.phase_name))
+            .string("tooltip", &tooltip)
+            .string("class", phase_key(self.phase_name))
+            .number("percentage", (self.illumination * 100.).round())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let moon_phase = MoonPhase::from_secs(1642464000);
+        let json = moon_phase.to_json();
+        assert!(json.starts_with("{\"schema_version\":1,\"timestamp\":\"2022-01-18T00:00:00Z\","), "Failed for {}", json);
+        assert!(json.ends_with('}'));
+        let keys = [
+            "schema_version",
+            "timestamp",
+            "julian_date",
+            "phase",
+            "age_days",
+            "illumination",
+            "illumination_percent",
+            "phase_name",
+            "emoji",
+            "distance_km",
+            "distance_earth_radii",
+            "latitude",
+            "longitude",
+            "zodiac",
+        ];
+        for key in keys.iter() {
+            assert!(json.contains(&format!("\"{}\":", key)), "Failed for {}", key);
+        }
+        assert!(json.contains("\"phase_name\":\"full\""));
+        assert!(json.contains("\"emoji\":\"🌕\""));
+        assert!(json.contains(&format!("\"distance_km\":{},", moon_phase.distance * EARTH_RADIUS)));
+    }
+
+    #[test]
+    fn escaping() {
+        let json = JsonObject::new()
+            .string("text", "a \"quoted\"\n\\ \u{1}")
+            .number("nan", f64::NAN)
+            .boolean("flag", true)
+            .raw("empty", &JsonObject::new().finish())
+            .finish();
+        assert_eq!(json, r#"{"text":"a \"quoted\"\n\\ \u0001","nan":null,"flag":true,"empty":{}}"#);
+    }
+
+    #[test]
+    fn waybar() {
+        let json = MoonPhase::from_secs(1642464000).to_waybar();
+        assert!(json.starts_with(r#"{"text":"🌕","alt":"full","tooltip":"Full Moon\nIllumination: 100%\nAge: 14.9 days"#), "Failed for {}", json);
+        // The next new moon is on 2022-02-01T05:46:00+00:00.
+        assert!(json.contains(r#"\nNew Moon: 2022-02-01 05:"#), "Failed for {}", json);
+        assert!(json.ends_with(r#","class":"full","percentage":100}"#), "Failed for {}", json);
+        assert_eq!(json.matches("\\n").count(), 8);
+    }
+
+    #[test]
+    fn home_assistant() {
+        // Full moon on 2022-01-17T23:48:00+00:00, high in the sky over
+        // Greenwich at midnight and past its culmination a little later.
+        let greenwich = Observer::new(51.48, 0.);
+        let json = MoonPhase::from_secs(1642464000).to_home_assistant(&greenwich);
+        assert!(json.starts_with(r#"{"state":"full_moon","attributes":{"friendly_name":"Moon","icon":"mdi:moon-full","#), "Failed for {}", json);
+        assert!(json.contains(r#""above_horizon":true,"#));
+        assert!(json.ends_with("}}"));
+        let json = MoonPhase::from_secs(1642464000 + 2 * 3600).to_home_assistant(&greenwich);
+        assert!(json.contains(r#""rising":false,"#), "Failed for {}", json);
+        let json = MoonPhase::from_secs(1643500000).to_home_assistant(&greenwich);
+        assert!(json.starts_with(r#"{"state":"waning_crescent","attributes":{"friendly_name":"Moon","icon":"mdi:moon-waning-crescent","#), "Failed for {}", json);
+    }
+}
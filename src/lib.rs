@@ -3,30 +3,190 @@ use chrono::{DateTime, offset::TimeZone};
 #[cfg(not(feature="chrono"))]
 use std::time::SystemTime;
 
-// Copied from the std libary, that way we are not limited to a minimum of rust 1.47
-pub const TAU: f64 = 6.28318530717958647692528676655900577_f64;
-
-const MOON_SYNODIC_PERIOD: f64 = 29.530588853; // Period of moon cycle in days.
-const MOON_SYNODIC_OFFSET: f64 = 2451550.26; // Reference cycle offset in days.
-const MOON_DISTANCE_PERIOD: f64 = 27.55454988; // Period of distance oscillation
-const MOON_DISTANCE_OFFSET: f64 = 2451562.2;
-const MOON_LATITUDE_PERIOD: f64 = 27.212220817; // Latitude oscillation
-const MOON_LATITUDE_OFFSET: f64 = 2451565.2;
-const MOON_LONGITUDE_PERIOD: f64 = 27.321582241; // Longitude oscillation
-const MOON_LONGITUDE_OFFSET: f64 = 2451555.8;
-
-// Names of lunar phases
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub mod algorithm;
+pub mod almanac;
+pub mod aspect;
+pub mod buddhist;
+pub mod brightness;
+pub mod builder;
+pub mod cache;
+mod calendar;
+pub mod chinese;
+pub mod clair_obscur;
+pub mod clock;
+pub mod columns;
+pub mod compact;
+pub mod computus;
+mod constellation;
+pub mod crescent;
+pub mod csv;
+mod daily;
+pub mod dark_sky;
+pub mod earthshine;
+#[cfg(feature="high-precision")]
+pub mod elp;
+pub mod ephemeris;
+mod error;
+mod events;
+#[cfg(feature="bevy")]
+pub mod game;
+#[cfg(feature="ffi")]
+pub mod ffi;
+#[cfg(feature="fixed")]
+pub mod fixed;
+pub mod hebrew;
+pub mod hijri;
+pub mod i18n;
+#[cfg(feature="ical")]
+pub mod ical;
+#[cfg(feature="jpl")]
+pub mod jpl;
+mod json;
+mod mansion;
+#[cfg(feature="uniffi")]
+mod mobile;
+mod nakshatra;
+pub mod naming;
+#[cfg(feature="node")]
+mod node;
+pub mod observer;
+pub mod photography;
+#[cfg(feature="plotters")]
+pub mod plot;
+#[cfg(feature="parsing")]
+mod parse;
+#[cfg(feature="prometheus")]
+pub mod prometheus;
+#[cfg(feature="python")]
+mod python;
+pub mod selenographic;
+pub mod single;
+#[cfg(feature="tokio")]
+pub mod stream;
+pub mod sun;
+#[cfg(feature="svg")]
+pub mod svg;
+#[cfg(feature="table")]
+pub mod table;
+pub mod time;
+pub mod tithi;
+mod trend;
+#[cfg(feature="validation")]
+pub mod validation;
+#[cfg(feature="vectorized")]
+pub mod vectorized;
+pub mod void_of_course;
+#[cfg(feature="wasm")]
+pub mod wasm;
+mod zodiac;
+mod elements;
+
+pub use crate::elements::TAU;
+pub use crate::builder::{BuiltMoonPhase, MoonPhaseBuilder};
+pub use crate::clock::{Clock, SystemClock};
+pub use crate::constellation::Constellation;
+pub use crate::daily::TimeOfDay;
+pub use crate::error::Error;
+pub use crate::json::{json_string, phase_key, zodiac_key, JSON_SCHEMA_VERSION};
+pub use crate::mansion::{Mansion, MansionNaming};
+pub use crate::nakshatra::{Nakshatra, NakshatraPosition};
+pub use crate::naming::{FinePhase, ParsePhaseError, PhaseNaming, PrimaryPhase, Proximity, Steps};
+pub use crate::observer::{Hemisphere, Observer};
+pub use crate::trend::Trend;
+pub use crate::zodiac::{Ayanamsa, Ingress, SignPosition, ZodiacMode, ZodiacScheme};
+#[cfg(feature="parsing")]
+pub use crate::parse::ParseError;
+use crate::elements::*;
+
+#[cfg(feature="uniffi")]
+uniffi::setup_scaffolding!();
+
+// Names of lunar phases, ordered as they follow each other in a cycle
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Phase {
     New,
     WaxingCrescent,
     FirstQuarter,
     WaxingGibbous,
     Full,
-    WainingGibbous,
+    // Data written before the rename may still use the old spelling.
+    #[cfg_attr(feature="serde", serde(alias = "WainingGibbous"))]
+    WaningGibbous,
     LastQuarter,
     WaningCrescent,
 }
+impl Phase {
+    // In cycle order, starting at new moon.
+    pub const ALL: [Phase; 8] = [
+        Phase::New,
+        Phase::WaxingCrescent,
+        Phase::FirstQuarter,
+        Phase::WaxingGibbous,
+        Phase::Full,
+        Phase::WaningGibbous,
+        Phase::LastQuarter,
+        Phase::WaningCrescent,
+    ];
+
+    // The old, misspelled name of `WaningGibbous`.
+    #[deprecated(note = "renamed to `WaningGibbous`")]
+    #[allow(non_upper_case_globals)]
+    pub const WainingGibbous: Phase = Phase::WaningGibbous;
+
+    // Position in `ALL`, wrapping around for larger indices.
+    pub const fn from_index(index: usize) -> Self {
+        Self::ALL[index % 8]
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    // Name for a position in the cycle, 0 - 1 with 0.5 = full, same as
+    // `MoonPhase::phase_name`. Values outside of 0 - 1 are wrapped around.
+    pub const fn from_cycle_fraction(fraction: f64) -> Self {
+        phase_name(fraction)
+    }
+
+    // Name for the age of the moon in days since new moon.
+    pub const fn from_age_days(age: f64) -> Self {
+        phase_name(age / MOON_SYNODIC_PERIOD)
+    }
+
+    // Same as `MoonPhase::from_julian_date(j_date).phase_name`, but usable
+    // in const contexts, since naming the phase needs no trig:
+    //
+    //     const FULL: Phase = Phase::from_secs(948429600);
+    //
+    // Only the naming is const: the rest of the model (`Elements::new` and
+    // with it `MoonPhase::new`) needs sine and cosine through the `Trig`
+    // trait, which can't be called in const fns on stable Rust.
+    pub const fn from_julian_date(j_date: f64) -> Self {
+        phase_name(synodic_phase(ut_to_tt(j_date)))
+    }
+
+    // Same as `MoonPhase::from_secs(secs).phase_name`, see `from_julian_date`.
+    pub const fn from_secs(secs: i64) -> Self {
+        Self::from_julian_date(julian_date_from_seconds(secs as f64))
+    }
+
+    // The phase that follows, New after WaningCrescent.
+    pub const fn next(self) -> Self {
+        Self::from_index(self.index() + 1)
+    }
+
+    // The phase before, WaningCrescent before New.
+    pub const fn previous(self) -> Self {
+        Self::from_index(self.index() + 7)
+    }
+
+    // The moon emoji of the phase, as seen from the northern hemisphere.
+    pub const fn emoji(self) -> char {
+        ['🌑', '🌒', '🌓', '🌔', '🌕', '🌖', '🌗', '🌘'][self.index()]
+    }
+}
+
 // Names of Zodiac constellations
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Zodiac {
@@ -44,40 +204,37 @@ pub enum Zodiac {
     Aquarius,
 }
 
-// Ecliptic angles of Zodiac constellations
-const ZODIAC_ANGLES: [f64; 12] = [
-    33.18, 51.16, 93.44, 119.48, 135.30, 173.34, 224.17, 242.57, 271.26,
-    302.49, 311.72, 348.58,
+// Ecliptic angles at which the Zodiac constellations end. Pisces spans the
+// end of the circle as well as its start.
+const ZODIAC_ANGLES: [(f64, Zodiac); 12] = [
+    (33.18, Zodiac::Pisces),
+    (51.16, Zodiac::Aries),
+    (93.44, Zodiac::Taurus),
+    (119.48, Zodiac::Gemini),
+    (135.30, Zodiac::Cancer),
+    (173.34, Zodiac::Leo),
+    (224.17, Zodiac::Virgo),
+    (242.57, Zodiac::Libra),
+    (271.26, Zodiac::Scorpio),
+    (302.49, Zodiac::Sagittarius),
+    (311.72, Zodiac::Capricorn),
+    (348.58, Zodiac::Aquarius),
 ];
 
 impl Zodiac {
+    // Longitudes outside of 0 - 360 degrees are wrapped around. NaN and
+    // infinite longitudes give Pisces, the sign at 0 degrees, use
+    // `try_from_long` to reject them instead.
     pub fn from_long(long: f64) -> Self {
-        use crate::Zodiac::*;
-        ZODIAC_ANGLES
-            .iter()
-            .enumerate()
-            .find_map(|(i, angle)| {
-                if long < *angle {
-                    Some(match i {
-                        0 => Pisces,
-                        1 => Aries,
-                        2 => Taurus,
-                        3 => Gemini,
-                        4 => Cancer,
-                        5 => Leo,
-                        6 => Virgo,
-                        7 => Libra,
-                        8 => Scorpio,
-                        9 => Sagittarius,
-                        10 => Capricorn,
-                        11 => Aquarius,
-                        _ => unimplemented!(),
-                    })
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| Pisces)
+        ZodiacScheme::constellations().find(long)
+    }
+
+    pub fn try_from_long(long: f64) -> Result<Self, Error> {
+        if long.is_finite() {
+            Ok(Self::from_long(long))
+        } else {
+            Err(Error::NotFinite)
+        }
     }
 }
 
@@ -86,7 +243,9 @@ pub struct MoonPhase {
     pub j_date: f64,
     pub phase: f64,                // 0 - 1, 0.5 = full
     pub age: f64,                  // Age in days of current cycle
-    pub fraction: f64,             // Fraction of illuminated disk
+    #[deprecated(note = "wrong for the default model, use `illumination` instead")]
+    pub fraction: f64,             // Kept for compatibility, see `illumination`
+    pub illumination: f64,         // Fraction of illuminated disk, 0 - 1
     pub distance: f64,             // Moon distance in earth radii
     pub latitude: f64,             // Moon ecliptic latitude
     pub longitude: f64,            // Moon ecliptic longitude
@@ -96,23 +255,56 @@ pub struct MoonPhase {
 
 #[cfg(feature="chrono")]
 fn julian_date<Tz: TimeZone>(time: DateTime<Tz>) -> f64 {
-    let secs = time.timestamp_micros() as f64 / 1_000_000.0;
-    julian_date_from_seconds(secs)
+    julian_date_from_seconds(unix_seconds(time))
+}
+
+#[cfg(feature="chrono")]
+fn unix_seconds<Tz: TimeZone>(time: DateTime<Tz>) -> f64 {
+    time.timestamp_micros() as f64 / 1_000_000.0
 }
 
 #[cfg(not(feature="chrono"))]
 fn julian_date(time: SystemTime) -> f64 {
-    let secs = match time.duration_since(SystemTime::UNIX_EPOCH) {
+    julian_date_from_seconds(unix_seconds(time))
+}
+
+#[cfg(not(feature="chrono"))]
+fn unix_seconds(time: SystemTime) -> f64 {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
         Ok(duration) => duration.as_secs_f64(),
-        Err(earlier) => -1. * earlier.duration().as_secs_f64(),
-    };
-    julian_date_from_seconds(secs)
+        Err(earlier) => -earlier.duration().as_secs_f64(),
+    }
 }
 
-fn julian_date_from_seconds(secs: f64) -> f64 {
+// Difference between the Julian date and the modified Julian date.
+const MJD_OFFSET: f64 = 2400000.5;
+
+const fn julian_date_from_seconds(secs: f64) -> f64 {
     secs / 86400. + 2440587.5
 }
 
+// Range checked by the `try_` constructors, in unix seconds. The model gets
+// noticeably worse outside of the years 1000 to 3000, see `Accuracy`.
+pub const VALID_FROM: f64 = -30_610_224_000.; // 1000-01-01T00:00:00+00:00
+pub const VALID_UNTIL: f64 = 32_503_680_000.; // 3000-01-01T00:00:00+00:00
+
+// Unix seconds of a date and time (UTC), in floating point so that no
+// value overflows.
+fn ymd_hms_seconds(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> f64 {
+    let days = calendar::days_from_civil(year as i64, month, day);
+    days as f64 * 86400. + hour as f64 * 3600. + minute as f64 * 60. + second
+}
+
+fn check_secs(secs: f64) -> Result<f64, Error> {
+    if !secs.is_finite() {
+        Err(Error::NotFinite)
+    } else if !(VALID_FROM..VALID_UNTIL).contains(&secs) {
+        Err(Error::OutOfRange(secs))
+    } else {
+        Ok(secs)
+    }
+}
+
 impl MoonPhase {
     #[cfg(feature="chrono")]
     pub fn new<Tz: TimeZone>(time: DateTime<Tz>) -> Self {
@@ -126,6 +318,11 @@ impl MoonPhase {
         Self::_new(j_date)
     }
 
+    // The phase at the current system time, see `now_with` for other clocks.
+    pub fn now() -> Self {
+        Self::now_with(&clock::SystemClock)
+    }
+
     pub fn from_secs(secs: i64) -> Self {
         Self::from_secs_float(secs as f64)
     }
@@ -135,61 +332,143 @@ impl MoonPhase {
         Self::_new(j_date)
     }
 
-    fn _new(j_date: f64) -> Self {
-        // Calculate illumination (synodic) phase.
-        // From number of days since new moon on Julian date MOON_SYNODIC_OFFSET
-        // (1815UTC January 6, 2000), determine remainder of incomplete cycle.
-        let phase =
-            ((j_date - MOON_SYNODIC_OFFSET) / MOON_SYNODIC_PERIOD).fract();
-        // Calculate age and illuination fraction.
-        let age = phase * MOON_SYNODIC_PERIOD;
-        let fraction = (1. - (TAU * phase)).cos() / 2.;
-        let mut phase_mod = (phase * 8.).round() % 8.;
-        if phase_mod < 0. { // Otherwise, values lower than 0 would simply cause New
-            phase_mod += 8.;
+    // Milliseconds since the epoch, as used by JavaScript and many databases.
+    pub fn from_millis(millis: i64) -> Self {
+        let secs = millis.div_euclid(1000) as f64 + millis.rem_euclid(1000) as f64 / 1e3;
+        Self::from_secs_float(secs)
+    }
+
+    // Nanoseconds since the epoch, as used by tracing systems. Splitting off
+    // the whole seconds first keeps the sub second part exact.
+    pub fn from_nanos(nanos: i128) -> Self {
+        let secs = nanos.div_euclid(1_000_000_000) as f64
+            + nanos.rem_euclid(1_000_000_000) as f64 / 1e9;
+        Self::from_secs_float(secs)
+    }
+
+    // For a date and time (UTC) in the proleptic Gregorian calendar, without
+    // needing chrono. The month is 1 - 12, the day 1 - 31. Out of range
+    // values carry over, e.g. hour 24 is midnight of the next day, month 13
+    // January of the next year and month 0 December of the previous one, see
+    // `try_from_ymd_hms` for a checked version.
+    pub fn from_ymd_hms(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> Self {
+        Self::from_secs_float(ymd_hms_seconds(year, month, day, hour, minute, second))
+    }
+
+    // For times that are already given as a Julian date (UT).
+    pub fn from_julian_date(j_date: f64) -> Self {
+        Self::_new(j_date)
+    }
+
+    // Modified Julian date, i.e. days since 1858-11-17T00:00:00+00:00.
+    pub fn from_mjd(mjd: f64) -> Self {
+        Self::from_julian_date(mjd + MJD_OFFSET)
+    }
+
+    #[cfg(feature="chrono")]
+    pub fn try_new<Tz: TimeZone>(time: DateTime<Tz>) -> Result<Self, Error> {
+        Self::try_from_secs_float(unix_seconds(time))
+    }
+
+    #[cfg(not(feature="chrono"))]
+    pub fn try_new(time: SystemTime) -> Result<Self, Error> {
+        Self::try_from_secs_float(unix_seconds(time))
+    }
+
+    pub fn try_from_secs(secs: i64) -> Result<Self, Error> {
+        Self::try_from_secs_float(secs as f64)
+    }
+
+    pub fn try_from_secs_float(secs: f64) -> Result<Self, Error> {
+        check_secs(secs).map(Self::from_secs_float)
+    }
+
+    // `from_ymd_hms` for dates and times that exist, the second may be up to
+    // 60.999 for a leap second.
+    pub fn try_from_ymd_hms(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> Result<Self, Error> {
+        if !second.is_finite() {
+            return Err(Error::NotFinite);
         }
-        let phase_name = match phase_mod as usize {
-            0 => Phase::New,
-            1 => Phase::WaxingCrescent,
-            2 => Phase::FirstQuarter,
-            3 => Phase::WaxingGibbous,
-            4 => Phase::Full,
-            5 => Phase::WainingGibbous,
-            6 => Phase::LastQuarter,
-            7 => Phase::WaningCrescent,
-            _ => {panic!("This should be unreachable")}
-        };
-        // Calculate distance fro anoalistic phase.
-        let distance_phase =
-            ((j_date - MOON_DISTANCE_OFFSET) / MOON_DISTANCE_PERIOD).fract();
-        let distance_phase_tau = TAU * distance_phase;
-        let phase_tau = 2. * TAU * phase;
-        let phase_distance_tau_difference = phase_tau - distance_phase_tau;
-        let distance = 60.4
-            - 3.3 * distance_phase_tau.cos()
-            - 0.6 * (phase_distance_tau_difference).cos()
-            - 0.5 * (phase_tau).cos();
-
-        // Calculate ecliptic latitude from nodal (draconic) phase.
-        let lat_phase =
-            ((j_date - MOON_LATITUDE_OFFSET) / MOON_LATITUDE_PERIOD).fract();
-        let latitude = 5.1 * (TAU * lat_phase).sin();
-
-        // Calculate ecliptic longitude ffrom sidereal motion.
-        let long_phase =
-            ((j_date - MOON_LONGITUDE_OFFSET) / MOON_LONGITUDE_PERIOD).fract();
-        let longitude = (360. * long_phase
-            + 6.3 * (distance_phase_tau).sin()
-            + 1.3 * (phase_distance_tau_difference).sin()
-            + 0.7 * (phase_tau).sin())
-            % 360.;
+        let days = calendar::days_from_civil(year as i64, month, day);
+        if !(1..=12).contains(&month)
+            || calendar::civil_from_days(days) != (year as i64, month, day)
+            || hour > 23
+            || minute > 59
+            || !(0. ..61.).contains(&second)
+        {
+            return Err(Error::InvalidDate);
+        }
+        Self::try_from_secs_float(ymd_hms_seconds(year, month, day, hour, minute, second))
+    }
+
+    // Calculates the phases for a whole slice of timestamps at once.
+    pub fn batch(secs: &[i64]) -> Vec<Self> {
+        let mut phases = Vec::with_capacity(secs.len());
+        phases.extend(Self::batch_iter(secs.iter().copied()));
+        phases
+    }
+
+    // Same as `batch`, but one vector per field instead of one per row.
+    pub fn batch_columns(secs: &[i64]) -> columns::MoonPhaseColumns {
+        let mut columns = columns::MoonPhaseColumns::with_capacity(secs.len());
+        columns.extend(Self::batch_iter(secs.iter().copied()));
+        columns
+    }
 
+    // Lazy version of `batch`, for timestamps that are not in a slice.
+    pub fn batch_iter<I>(secs: I) -> impl Iterator<Item = Self>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        secs.into_iter().map(Self::from_secs)
+    }
+
+    // Same as `batch`, but spread over rayon's thread pool.
+    #[cfg(feature="rayon")]
+    pub fn par_batch(secs: &[i64]) -> Vec<Self> {
+        use rayon::prelude::*;
+        secs.par_iter().map(|secs| Self::from_secs(*secs)).collect()
+    }
+
+    #[cfg(feature="rayon")]
+    pub fn par_batch_iter<I>(secs: I) -> impl rayon::iter::ParallelIterator<Item = Self>
+    where
+        I: rayon::iter::IntoParallelIterator<Item = i64>,
+    {
+        use rayon::prelude::*;
+        secs.into_par_iter().map(Self::from_secs)
+    }
+
+    // Illuminated part of the disk in percent, 0 - 100.
+    pub fn percent_illuminated(&self) -> f64 {
+        self.illumination * 100.
+    }
+
+    // For a Julian date in UT, evaluated at TT.
+    fn _new(j_date: f64) -> Self {
+        Self::_new_tt(j_date, ut_to_tt(j_date))
+    }
+
+    // The model evaluated at `tt`, a Julian date in TT, keeping `j_date`.
+    #[allow(deprecated)]
+    pub(crate) fn _new_tt(j_date: f64, tt: f64) -> Self {
+        let Elements {
+            phase,
+            age,
+            fraction,
+            illumination,
+            distance,
+            latitude,
+            longitude,
+        } = Elements::new_tt(tt);
+        let phase_name = phase_name(phase);
         let zodiac_name = Zodiac::from_long(longitude);
         MoonPhase {
             j_date,
             phase,
             age,
             fraction,
+            illumination,
             distance,
             latitude,
             longitude,
@@ -199,11 +478,27 @@ impl MoonPhase {
     }
 }
 
+// Defaults to the current phase, same as `MoonPhase::now`.
+impl Default for MoonPhase {
+    fn default() -> Self {
+        Self::now()
+    }
+}
+
+const fn phase_name(phase: f64) -> Phase {
+    // Euclidean remainder, otherwise values lower than 0 would simply cause
+    // New. Written out, since f64::rem_euclid isn't a const fn.
+    let index = round(phase * 8.) % 8.;
+    let index = if index < 0. { index + 8. } else { index };
+    Phase::from_index(index as usize)
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
     use super::Phase::*;
+    use crate::elements::Trig;
     #[cfg(feature="chrono")]
     use chrono::prelude::*;
     #[cfg(not(feature="chrono"))]
@@ -227,7 +522,7 @@ mod test {
         ("2022-01-16T00:00:00+00:00", Full),
         ("2022-01-17T23:48:00+00:00", Full),
         ("2022-01-18T23:59:00+00:00", Full),
-        ("2022-01-19T16:45:00+00:00", WainingGibbous),
+        ("2022-01-19T16:45:00+00:00", WaningGibbous),
     ];
 
     #[test]
@@ -275,7 +570,7 @@ mod test {
             (1642291200.0, Full),               // 2022-01-16T00:00:00+00:00
             (1642463280.0, Full),               // 2022-01-17T23:48:00+00:00
             (1642550340.0, Full),               // 2022-01-18T23:59:00+00:00
-            (1642610700.0, WainingGibbous),     // 2022-01-19T16:45:00+00:00
+            (1642610700.0, WaningGibbous),     // 2022-01-19T16:45:00+00:00
         ];
 
         for (secs, exp) in &testcases {
@@ -284,6 +579,184 @@ mod test {
         }
     }
 
+    #[test]
+    fn phase_order() {
+        assert!(New < WaxingCrescent && LastQuarter < WaningCrescent);
+        for (i, phase) in Phase::ALL.iter().enumerate() {
+            assert_eq!(Phase::from_index(i), *phase);
+            assert_eq!(phase.index(), i);
+            assert_eq!(phase.next().previous(), *phase);
+        }
+        assert_eq!(WaningCrescent.next(), New);
+        assert_eq!(New.previous(), WaningCrescent);
+        assert_eq!(Phase::from_index(12), Full);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn illumination() {
+        let full = MoonPhase::from_secs(948429600); // 2000-01-21T04:40:00+00:00
+        assert!(full.percent_illuminated() > 99.);
+        let new = MoonPhase::from_secs(947182380); // 2000-01-06T18:13:00+00:00
+        assert!(new.percent_illuminated() < 1.);
+        for secs in (900_000_000..1_000_000_000).step_by(86_400 * 3) {
+            let moon_phase = MoonPhase::from_secs(secs);
+            assert!((0. ..=1.).contains(&moon_phase.illumination), "Failed for {}", secs);
+            // The old value is still there, unchanged. Computed with `Trig`
+            // like the model, as with `deterministic` that is libm's cosine,
+            // which can differ from `f64::cos` in the last bit.
+            assert_eq!(moon_phase.fraction, (1. - TAU * moon_phase.phase).cosine() / 2., "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn phase_from_values() {
+        assert_eq!(Phase::from_cycle_fraction(0.), New);
+        assert_eq!(Phase::from_cycle_fraction(0.5), Full);
+        assert_eq!(Phase::from_cycle_fraction(0.97), New);
+        assert_eq!(Phase::from_cycle_fraction(-0.25), LastQuarter);
+        assert_eq!(Phase::from_cycle_fraction(1.25), FirstQuarter);
+        assert_eq!(Phase::from_age_days(3.), WaxingCrescent);
+        assert_eq!(Phase::from_age_days(14.8), Full);
+        assert_eq!(Phase::from_age_days(29.), New);
+        let moon_phase = MoonPhase::from_secs(1642610700);
+        assert_eq!(Phase::from_cycle_fraction(moon_phase.phase), moon_phase.phase_name);
+        assert_eq!(Phase::from_age_days(moon_phase.age), moon_phase.phase_name);
+        assert_eq!(Phase::from_cycle_fraction(f64::NAN), New);
+    }
+
+    #[test]
+    fn const_phase() {
+        const FULL: Phase = Phase::from_secs(948429600); // 2000-01-21T04:40:00+00:00
+        const CRESCENT: Phase = Phase::from_julian_date(2415025.5); // 1900-01-06
+        assert_eq!(FULL, Full);
+        assert_eq!(CRESCENT, WaxingCrescent);
+        assert_eq!(CRESCENT, MoonPhase::from_julian_date(2415025.5).phase_name);
+        // Every 5 hours over a few years, including before the reference new moon.
+        for secs in (900_000_000..1_000_000_000).step_by(18_000) {
+            assert_eq!(Phase::from_secs(secs), MoonPhase::from_secs(secs).phase_name, "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn zodiac_from_long() {
+        assert_eq!(Zodiac::from_long(0.), Zodiac::Pisces);
+        assert_eq!(Zodiac::from_long(33.18), Zodiac::Aries);
+        assert_eq!(Zodiac::from_long(348.57), Zodiac::Aquarius);
+        assert_eq!(Zodiac::from_long(348.58), Zodiac::Pisces);
+        assert_eq!(Zodiac::from_long(359.99), Zodiac::Pisces);
+        assert_eq!(Zodiac::from_long(360. + 100.), Zodiac::Gemini);
+        assert_eq!(Zodiac::from_long(-20.), Zodiac::Aquarius);
+        assert_eq!(Zodiac::from_long(f64::NAN), Zodiac::Pisces);
+        assert_eq!(Zodiac::from_long(f64::INFINITY), Zodiac::Pisces);
+        assert_eq!(Zodiac::try_from_long(f64::NAN), Err(crate::Error::NotFinite));
+        assert_eq!(Zodiac::try_from_long(f64::NEG_INFINITY), Err(crate::Error::NotFinite));
+        assert_eq!(Zodiac::try_from_long(-20.), Ok(Zodiac::Aquarius));
+    }
+
+    #[test]
+    fn longitude_normalized() {
+        // Long before and after the reference dates.
+        for secs in (-20_000_000_000..20_000_000_000).step_by(86_400 * 97) {
+            let moon_phase = MoonPhase::from_secs(secs);
+            assert!((0. ..360.).contains(&moon_phase.longitude), "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn try_from_secs() {
+        assert_eq!(MoonPhase::try_from_secs(1642291200), Ok(MoonPhase::from_secs(1642291200)));
+        assert_eq!(MoonPhase::try_from_secs_float(f64::NAN), Err(crate::Error::NotFinite));
+        assert_eq!(MoonPhase::try_from_secs_float(f64::INFINITY), Err(crate::Error::NotFinite));
+        assert_eq!(MoonPhase::try_from_secs(i64::MAX), Err(crate::Error::OutOfRange(i64::MAX as f64)));
+        assert!(MoonPhase::try_from_secs(-40_000_000_000).is_err());
+    }
+
+    #[test]
+    #[cfg(feature="chrono")]
+    fn try_new() {
+        let time = DateTime::parse_from_rfc3339("2022-01-16T00:00:00+00:00").unwrap();
+        assert_eq!(MoonPhase::try_new(time), Ok(MoonPhase::new(time)));
+        let time = DateTime::parse_from_rfc3339("0900-01-16T00:00:00+00:00").unwrap();
+        assert!(MoonPhase::try_new(time).is_err());
+    }
+
+    #[test]
+    fn from_julian_date() {
+        let moon_phase = MoonPhase::from_secs(948429600); // 2000-01-21T04:40:00+00:00
+        assert_eq!(MoonPhase::from_julian_date(moon_phase.j_date), moon_phase);
+        assert_eq!(MoonPhase::from_julian_date(2451564.6944444445).phase_name, Full);
+        assert_eq!(MoonPhase::from_mjd(51564.194444444445).phase_name, Full);
+        assert!((MoonPhase::from_mjd(51564.19444).j_date - moon_phase.j_date).abs() < 1e-5);
+    }
+
+    #[test]
+    fn from_millis_nanos() {
+        let moon_phase = MoonPhase::from_secs(948429600);
+        assert_eq!(MoonPhase::from_millis(948429600000), moon_phase);
+        assert_eq!(MoonPhase::from_nanos(948429600000000000), moon_phase);
+        assert_eq!(MoonPhase::from_millis(-1500), MoonPhase::from_secs_float(-1.5));
+        assert_eq!(MoonPhase::from_nanos(-1_500_000_000), MoonPhase::from_secs_float(-1.5));
+        assert_eq!(MoonPhase::from_nanos(1_500_000_000), MoonPhase::from_millis(1500));
+    }
+
+    #[test]
+    fn from_ymd_hms() {
+        assert_eq!(MoonPhase::from_ymd_hms(2000, 1, 21, 4, 40, 0.), MoonPhase::from_secs(948429600));
+        assert_eq!(MoonPhase::from_ymd_hms(1999, 1, 2, 2, 49, 0.), MoonPhase::from_secs(915245340));
+        assert_eq!(MoonPhase::from_ymd_hms(2022, 1, 19, 16, 45, 0.).phase_name, WaningGibbous);
+        // Julian date 0 is noon of November 24th, 4714 BC in the proleptic Gregorian calendar.
+        assert_eq!(MoonPhase::from_ymd_hms(-4713, 11, 24, 12, 0, 0.).j_date, 0.);
+        // Carried over rather than overflowing.
+        assert_eq!(MoonPhase::from_ymd_hms(2000, 1, 20, 28, 40, 0.), MoonPhase::from_secs(948429600));
+        assert!(MoonPhase::from_ymd_hms(2000, 1, 1, u32::MAX, u32::MAX, 0.).j_date.is_finite());
+        // The month carries over into the year in both directions.
+        assert_eq!(MoonPhase::from_ymd_hms(2000, 25, 1, 0, 0, 0.).j_date, 2452275.5); // 2002-01-01
+        assert_eq!(MoonPhase::from_ymd_hms(2000, 0, 1, 0, 0, 0.).j_date, 2451513.5); // 1999-12-01
+        assert!(MoonPhase::from_ymd_hms(2000, u32::MAX, 1, 0, 0, 0.).j_date.is_finite());
+
+        assert_eq!(MoonPhase::try_from_ymd_hms(2000, 1, 21, 4, 40, 0.), Ok(MoonPhase::from_secs(948429600)));
+        assert!(MoonPhase::try_from_ymd_hms(2016, 12, 31, 23, 59, 60.5).is_ok());
+        assert!(MoonPhase::try_from_ymd_hms(2000, 2, 29, 0, 0, 0.).is_ok());
+        for (month, day, hour, minute, second) in [
+            (2, 30, 0, 0, 0.),
+            (0, 1, 0, 0, 0.),
+            (13, 1, 0, 0, 0.),
+            (1, 0, 0, 0, 0.),
+            (4, 31, 0, 0, 0.),
+            (1, 1, 24, 0, 0.),
+            (1, 1, 0, 60, 0.),
+            (1, 1, 0, 0, 61.),
+            (1, 1, 0, 0, -1.),
+        ] {
+            let result = MoonPhase::try_from_ymd_hms(2000, month, day, hour, minute, second);
+            assert_eq!(result, Err(Error::InvalidDate), "Failed for {}-{} {}:{}:{}", month, day, hour, minute, second);
+        }
+        assert_eq!(MoonPhase::try_from_ymd_hms(2000, 1, 1, 0, 0, f64::NAN), Err(Error::NotFinite));
+        assert!(matches!(MoonPhase::try_from_ymd_hms(3001, 1, 1, 0, 0, 0.), Err(Error::OutOfRange(_))));
+    }
+
+    #[test]
+    fn batch_same() {
+        let secs: Vec<i64> = (0..100).map(|i| 947182380 + i * 3600 * 7).collect();
+        let batch = MoonPhase::batch(&secs);
+        assert_eq!(batch.len(), secs.len());
+        for (moon_phase, secs) in batch.iter().zip(&secs) {
+            assert_eq!(*moon_phase, MoonPhase::from_secs(*secs));
+        }
+        assert_eq!(MoonPhase::batch_iter(secs).collect::<Vec<_>>(), batch);
+    }
+
+    #[test]
+    #[cfg(feature="rayon")]
+    fn par_batch_same() {
+        use rayon::prelude::*;
+        let secs: Vec<i64> = (0..10_000).map(|i| 947182380 + i * 3600).collect();
+        let batch = MoonPhase::batch(&secs);
+        assert_eq!(MoonPhase::par_batch(&secs), batch);
+        assert_eq!(MoonPhase::par_batch_iter(secs).collect::<Vec<_>>(), batch);
+    }
+
     #[test]
     #[cfg(feature="chrono")]
     fn test_create() {
@@ -296,4 +769,13 @@ mod test {
     fn test_create() {
         MoonPhase::new(SystemTime::now()); // Just make sure it's not crashing
     }
+
+    #[test]
+    fn now() {
+        let before = MoonPhase::now();
+        let default = MoonPhase::default();
+        // A few seconds at most between the two.
+        assert!(default.j_date >= before.j_date);
+        assert!(default.j_date - before.j_date < 1. / 86400. * 10.);
+    }
 }
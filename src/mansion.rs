@@ -0,0 +1,192 @@
+// The 28 lunar mansions, the stations the moon passes in about one day each.
+// They are taken as equal divisions of 360/28 degrees from the vernal
+// equinox, as in the Arabic tradition. The Chinese xiu are matched to the
+// manazil sharing their stars, so Jiao (Spica) is al-Simak.
+
+use crate::zodiac::ZodiacScheme;
+use crate::MoonPhase;
+
+const MANSION_DEGREES: f64 = 360. / 28.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Mansion {
+    AlSharatain,
+    AlButain,
+    AlThurayya,
+    AlDabaran,
+    AlHaqah,
+    AlHanah,
+    AlDhira,
+    AlNathrah,
+    AlTarf,
+    AlJabhah,
+    AlZubrah,
+    AlSarfah,
+    AlAwwa,
+    AlSimak,
+    AlGhafr,
+    AlZubana,
+    AlIklil,
+    AlQalb,
+    AlShaulah,
+    AlNaaim,
+    AlBaldah,
+    SadAlDhabih,
+    SadBula,
+    SadAlSuud,
+    SadAlAkhbiyah,
+    AlFarghAlMuqdim,
+    AlFarghAlMuakhkhar,
+    BatnAlHut,
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum MansionNaming {
+    #[default]
+    Arabic,   // manazil al-qamar
+    Chinese,  // xiu, in pinyin
+}
+
+const ARABIC: [&str; 28] = [
+    "al-Sharatain",
+    "al-Butain",
+    "al-Thurayya",
+    "al-Dabaran",
+    "al-Haq'ah",
+    "al-Han'ah",
+    "al-Dhira'",
+    "al-Nathrah",
+    "al-Tarf",
+    "al-Jabhah",
+    "al-Zubrah",
+    "al-Sarfah",
+    "al-'Awwa'",
+    "al-Simak",
+    "al-Ghafr",
+    "al-Zubana",
+    "al-Iklil",
+    "al-Qalb",
+    "al-Shaulah",
+    "al-Na'a'im",
+    "al-Baldah",
+    "Sa'd al-Dhabih",
+    "Sa'd Bula'",
+    "Sa'd al-Su'ud",
+    "Sa'd al-Akhbiyah",
+    "al-Fargh al-Muqdim",
+    "al-Fargh al-Mu'akhkhar",
+    "Batn al-Hut",
+];
+
+// In their own order, starting with Jiao.
+const CHINESE: [&str; 28] = [
+    "Jiao", "Kang", "Di", "Fang", "Xin", "Wei", "Ji",
+    "Dou", "Niu", "Nü", "Xu", "Wei", "Shi", "Bi",
+    "Kui", "Lou", "Wei", "Mao", "Bi", "Zi", "Shen",
+    "Jing", "Gui", "Liu", "Xing", "Zhang", "Yi", "Zhen",
+];
+
+// Position of Jiao among the manazil.
+const CHINESE_OFFSET: usize = Mansion::AlSimak as usize;
+
+impl Mansion {
+    pub const ALL: [Mansion; 28] = [
+        Mansion::AlSharatain,
+        Mansion::AlButain,
+        Mansion::AlThurayya,
+        Mansion::AlDabaran,
+        Mansion::AlHaqah,
+        Mansion::AlHanah,
+        Mansion::AlDhira,
+        Mansion::AlNathrah,
+        Mansion::AlTarf,
+        Mansion::AlJabhah,
+        Mansion::AlZubrah,
+        Mansion::AlSarfah,
+        Mansion::AlAwwa,
+        Mansion::AlSimak,
+        Mansion::AlGhafr,
+        Mansion::AlZubana,
+        Mansion::AlIklil,
+        Mansion::AlQalb,
+        Mansion::AlShaulah,
+        Mansion::AlNaaim,
+        Mansion::AlBaldah,
+        Mansion::SadAlDhabih,
+        Mansion::SadBula,
+        Mansion::SadAlSuud,
+        Mansion::SadAlAkhbiyah,
+        Mansion::AlFarghAlMuqdim,
+        Mansion::AlFarghAlMuakhkhar,
+        Mansion::BatnAlHut,
+    ];
+
+    // For an ecliptic longitude in degrees (equinox of date). Longitudes
+    // outside of 0 - 360 degrees are wrapped around.
+    pub fn from_long(longitude: f64) -> Self {
+        let index = (longitude.rem_euclid(360.) / MANSION_DEGREES) as usize;
+        Mansion::ALL[index.min(27)]
+    }
+
+    // 0 for al-Sharatain up to 27 for Batn al-Hut.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self, naming: MansionNaming) -> &'static str {
+        match naming {
+            MansionNaming::Arabic => ARABIC[self.index()],
+            MansionNaming::Chinese => CHINESE[(self.index() + 28 - CHINESE_OFFSET) % 28],
+        }
+    }
+
+    // The mansions as a `ZodiacScheme`, e.g. to find the times the moon
+    // enters the next one.
+    pub fn scheme() -> ZodiacScheme<Mansion> {
+        let ends = Mansion::ALL
+            .iter()
+            .map(|mansion| ((mansion.index() + 1) as f64 * MANSION_DEGREES, *mansion))
+            .collect();
+        ZodiacScheme::new(ends).unwrap()
+    }
+}
+
+impl MoonPhase {
+    pub fn mansion(&self) -> Mansion {
+        Mansion::from_long(self.longitude)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_long() {
+        assert_eq!(Mansion::from_long(0.), Mansion::AlSharatain);
+        assert_eq!(Mansion::from_long(13.), Mansion::AlButain);
+        assert_eq!(Mansion::from_long(359.9), Mansion::BatnAlHut);
+        assert_eq!(Mansion::from_long(-1.), Mansion::BatnAlHut);
+        let scheme = Mansion::scheme();
+        for longitude in 0..720 {
+            let longitude = longitude as f64 / 2. + 0.25;
+            assert_eq!(scheme.find(longitude), Mansion::from_long(longitude), "Failed for {}", longitude);
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Mansion::AlThurayya.name(MansionNaming::Arabic), "al-Thurayya");
+        assert_eq!(Mansion::AlSimak.name(MansionNaming::Chinese), "Jiao");
+        // Both are the Pleiades.
+        assert_eq!(Mansion::AlThurayya.name(MansionNaming::Chinese), "Mao");
+        assert_eq!(Mansion::AlSharatain.name(MansionNaming::Chinese), "Lou");
+        assert_eq!(Mansion::AlAwwa.name(MansionNaming::Chinese), "Zhen");
+    }
+
+    #[test]
+    fn moon_phase_mansion() {
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        assert_eq!(moon_phase.mansion(), Mansion::from_long(moon_phase.longitude));
+    }
+}
@@ -0,0 +1,182 @@
+// Swift and Kotlin bindings through UniFFI. Build the library with
+//
+//     cargo rustc --release --lib --features uniffi --crate-type cdylib
+//
+// (or staticlib for iOS) and generate the bindings from it with uniffi-bindgen
+// of the same UniFFI version:
+//
+//     uniffi-bindgen generate --library target/release/libmoon_phase.so --language kotlin --out-dir out
+//
+// and then, in Kotlin:
+//
+//     val moon = MoonPhase(System.currentTimeMillis() / 1000.0)
+//     println("${moon.emoji()} ${moon.phaseName()}")
+//     val riseSet = moonRiseSet(52.5, 13.4, midnight)
+//
+// Times are unix seconds, names are the keys of `MoonPhase::to_json`.
+
+use std::sync::Arc;
+
+use crate::almanac::{self, PhaseEvent as AlmanacEvent};
+use crate::ephemeris::EARTH_RADIUS;
+use crate::json::{phase_key, zodiac_key};
+use crate::observer::Observer;
+
+#[derive(Debug, Clone, PartialEq, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum MoonPhaseError {
+    // NaN or outside of `VALID_FROM..VALID_UNTIL`.
+    InvalidTime(String),
+    InvalidArgument(String),
+}
+
+impl std::fmt::Display for MoonPhaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoonPhaseError::InvalidTime(message) | MoonPhaseError::InvalidArgument(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl From<crate::Error> for MoonPhaseError {
+    fn from(error: crate::Error) -> Self {
+        MoonPhaseError::InvalidTime(error.to_string())
+    }
+}
+
+#[derive(Debug, uniffi::Object)]
+pub struct MoonPhase(crate::MoonPhase);
+
+#[uniffi::export]
+impl MoonPhase {
+    #[uniffi::constructor]
+    pub fn new(secs: f64) -> Result<Arc<Self>, MoonPhaseError> {
+        Ok(Arc::new(MoonPhase(crate::MoonPhase::try_from_secs_float(secs)?)))
+    }
+
+    #[uniffi::constructor]
+    pub fn now() -> Arc<Self> {
+        Arc::new(MoonPhase(crate::MoonPhase::now()))
+    }
+
+    pub fn timestamp(&self) -> f64 {
+        (self.0.j_date - 2440587.5) * 86400.
+    }
+
+    pub fn julian_date(&self) -> f64 {
+        self.0.j_date
+    }
+
+    // 0 - 1, 0.5 = full
+    pub fn phase(&self) -> f64 {
+        self.0.phase
+    }
+
+    // Days
+    pub fn age(&self) -> f64 {
+        self.0.age
+    }
+
+    // 0 - 1
+    pub fn illumination(&self) -> f64 {
+        self.0.illumination
+    }
+
+    pub fn phase_name(&self) -> String {
+        phase_key(self.0.phase_name).to_string()
+    }
+
+    pub fn emoji(&self) -> String {
+        self.0.phase_name.emoji().to_string()
+    }
+
+    pub fn distance_km(&self) -> f64 {
+        self.0.distance * EARTH_RADIUS
+    }
+
+    // Ecliptic, degrees
+    pub fn latitude(&self) -> f64 {
+        self.0.latitude
+    }
+
+    // Ecliptic, degrees
+    pub fn longitude(&self) -> f64 {
+        self.0.longitude
+    }
+
+    pub fn zodiac(&self) -> String {
+        zodiac_key(self.0.zodiac_name).to_string()
+    }
+
+    pub fn to_json(&self) -> String {
+        self.0.to_json()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct PhaseEvent {
+    pub phase: String,   // "new", "first_quarter", "full" or "last_quarter"
+    pub timestamp: f64,  // Unix seconds
+}
+
+impl From<&AlmanacEvent> for PhaseEvent {
+    fn from(event: &AlmanacEvent) -> Self {
+        PhaseEvent {
+            phase: phase_key(event.phase.into()).to_string(),
+            timestamp: event.secs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct RiseSet {
+    pub rise: Option<f64>,  // Unix seconds
+    pub set: Option<f64>,   // Unix seconds
+}
+
+// New, full and quarter moons from `start` up to `end`.
+#[uniffi::export]
+pub fn events(start: f64, end: f64) -> Vec<PhaseEvent> {
+    almanac::events(start, end).iter().map(PhaseEvent::from).collect()
+}
+
+// The first moonrise and moonset in the 24 hours from `start`, usually local
+// midnight, at `latitude` and `longitude` (degrees, north and east positive).
+#[uniffi::export]
+pub fn moon_rise_set(latitude: f64, longitude: f64, start: f64) -> Result<RiseSet, MoonPhaseError> {
+    if !(-90. ..=90.).contains(&latitude) || !(-180. ..=180.).contains(&longitude) {
+        return Err(MoonPhaseError::InvalidArgument("latitude or longitude out of range".to_string()));
+    }
+    crate::MoonPhase::try_from_secs_float(start)?;
+    let rise_set = Observer::new(latitude, longitude).moon_rise_set(start);
+    Ok(RiseSet {
+        rise: rise_set.rise,
+        set: rise_set.set,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn api() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let moon_phase = MoonPhase::new(1642463280.).unwrap();
+        assert_eq!(moon_phase.phase_name(), "full");
+        assert!((moon_phase.timestamp() - 1642463280.).abs() < 1e-3);
+        assert_eq!(moon_phase.to_json(), crate::MoonPhase::from_secs(1642463280).to_json());
+        assert!(matches!(MoonPhase::new(f64::NAN), Err(MoonPhaseError::InvalidTime(_))));
+
+        let events = events(1640995200., 1643673600.);
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2].phase, "full");
+
+        let rise_set = moon_rise_set(51.48, 0., 1642377600.).unwrap();
+        assert!(rise_set.rise.is_some() && rise_set.set.is_some());
+        assert!(moon_rise_set(91., 0., 1642377600.).is_err());
+        assert!(moon_rise_set(0., 0., f64::NAN).is_err());
+    }
+}
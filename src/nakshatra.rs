@@ -0,0 +1,128 @@
+// The 27 nakshatras of Vedic astrology, equal divisions of 13°20' of the
+// sidereal zodiac starting with Ashwini at 0° Aries. Each is split into four
+// padas of 3°20'.
+
+use crate::zodiac::Ayanamsa;
+use crate::MoonPhase;
+
+const NAKSHATRA_DEGREES: f64 = 360. / 27.;
+const PADA_DEGREES: f64 = NAKSHATRA_DEGREES / 4.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Nakshatra {
+    Ashwini,
+    Bharani,
+    Krittika,
+    Rohini,
+    Mrigashira,
+    Ardra,
+    Punarvasu,
+    Pushya,
+    Ashlesha,
+    Magha,
+    PurvaPhalguni,
+    UttaraPhalguni,
+    Hasta,
+    Chitra,
+    Swati,
+    Vishakha,
+    Anuradha,
+    Jyeshtha,
+    Mula,
+    PurvaAshadha,
+    UttaraAshadha,
+    Shravana,
+    Dhanishtha,
+    Shatabhisha,
+    PurvaBhadrapada,
+    UttaraBhadrapada,
+    Revati,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct NakshatraPosition {
+    pub nakshatra: Nakshatra,
+    pub pada: u32,  // 1 - 4
+}
+
+impl Nakshatra {
+    pub const ALL: [Nakshatra; 27] = [
+        Nakshatra::Ashwini,
+        Nakshatra::Bharani,
+        Nakshatra::Krittika,
+        Nakshatra::Rohini,
+        Nakshatra::Mrigashira,
+        Nakshatra::Ardra,
+        Nakshatra::Punarvasu,
+        Nakshatra::Pushya,
+        Nakshatra::Ashlesha,
+        Nakshatra::Magha,
+        Nakshatra::PurvaPhalguni,
+        Nakshatra::UttaraPhalguni,
+        Nakshatra::Hasta,
+        Nakshatra::Chitra,
+        Nakshatra::Swati,
+        Nakshatra::Vishakha,
+        Nakshatra::Anuradha,
+        Nakshatra::Jyeshtha,
+        Nakshatra::Mula,
+        Nakshatra::PurvaAshadha,
+        Nakshatra::UttaraAshadha,
+        Nakshatra::Shravana,
+        Nakshatra::Dhanishtha,
+        Nakshatra::Shatabhisha,
+        Nakshatra::PurvaBhadrapada,
+        Nakshatra::UttaraBhadrapada,
+        Nakshatra::Revati,
+    ];
+}
+
+impl NakshatraPosition {
+    // For a sidereal ecliptic longitude in degrees. Longitudes outside of
+    // 0 - 360 degrees are wrapped around.
+    pub fn from_sidereal_long(longitude: f64) -> Self {
+        let padas = (longitude.rem_euclid(360.) / PADA_DEGREES) as usize;
+        let padas = padas.min(27 * 4 - 1);
+        NakshatraPosition {
+            nakshatra: Nakshatra::ALL[padas / 4],
+            pada: (padas % 4) as u32 + 1,
+        }
+    }
+}
+
+impl MoonPhase {
+    pub fn nakshatra(&self, ayanamsa: Ayanamsa) -> NakshatraPosition {
+        NakshatraPosition::from_sidereal_long(self.longitude - ayanamsa.degrees(self.j_date))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::zodiac::{sign, ZodiacMode};
+
+    #[test]
+    fn from_sidereal_long() {
+        let position = NakshatraPosition::from_sidereal_long(0.);
+        assert_eq!((position.nakshatra, position.pada), (Nakshatra::Ashwini, 1));
+        let position = NakshatraPosition::from_sidereal_long(10.);
+        assert_eq!((position.nakshatra, position.pada), (Nakshatra::Ashwini, 4));
+        // Spica at 180 degrees of the Lahiri zodiac is in Chitra.
+        let position = NakshatraPosition::from_sidereal_long(180.);
+        assert_eq!((position.nakshatra, position.pada), (Nakshatra::Chitra, 3));
+        let position = NakshatraPosition::from_sidereal_long(-0.1);
+        assert_eq!((position.nakshatra, position.pada), (Nakshatra::Revati, 4));
+    }
+
+    #[test]
+    fn moon_phase_nakshatra() {
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        for ayanamsa in &[Ayanamsa::Lahiri, Ayanamsa::Raman] {
+            let position = moon_phase.nakshatra(*ayanamsa);
+            // Nine padas to a sign.
+            let padas = position.nakshatra as usize * 4 + position.pada as usize - 1;
+            let sign = sign((padas as f64 + 0.5) * PADA_DEGREES);
+            assert_eq!(moon_phase.zodiac(ZodiacMode::Sidereal(*ayanamsa)), sign, "Failed for {:?}", ayanamsa);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+// Policies for turning the continuous phase into a `Phase` name. Calendars
+// tend to call the moon full only on the day of the full moon, while the
+// default splits the cycle into eight equal parts.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::i18n::Language;
+use crate::{phase_name, MoonPhase, Phase, MOON_SYNODIC_PERIOD};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum PhaseNaming {
+    // Every name covers an eighth of the cycle, centered on its exact
+    // phase. Same as `MoonPhase::phase_name`.
+    #[default]
+    Octants,
+    // New, first quarter, full and last quarter only within the given
+    // number of hours of their exact instant, crescent or gibbous otherwise.
+    Exact { hours: f64 },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ParsePhaseError;
+
+impl fmt::Display for ParsePhaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown moon phase name")
+    }
+}
+
+impl std::error::Error for ParsePhaseError {}
+
+// Accepts the variant names in any case, with or without spaces, dashes or
+// underscores between the words ("Waxing Crescent", "waxing_crescent"), as
+// well as the old spelling "WainingGibbous".
+impl FromStr for Phase {
+    type Err = ParsePhaseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name: String = name
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        Ok(match name.as_str() {
+            "new" | "newmoon" => Phase::New,
+            "waxingcrescent" => Phase::WaxingCrescent,
+            "firstquarter" => Phase::FirstQuarter,
+            "waxinggibbous" => Phase::WaxingGibbous,
+            "full" | "fullmoon" => Phase::Full,
+            "waninggibbous" | "waininggibbous" => Phase::WaningGibbous,
+            "lastquarter" | "thirdquarter" => Phase::LastQuarter,
+            "waningcrescent" => Phase::WaningCrescent,
+            _ => return Err(ParsePhaseError),
+        })
+    }
+}
+
+// The four principal phases, for calendars that mark only these.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PrimaryPhase {
+    New,
+    FirstQuarter,
+    Full,
+    LastQuarter,
+}
+
+impl PrimaryPhase {
+    pub const ALL: [PrimaryPhase; 4] = [
+        PrimaryPhase::New,
+        PrimaryPhase::FirstQuarter,
+        PrimaryPhase::Full,
+        PrimaryPhase::LastQuarter,
+    ];
+
+    // The principal phase closest to `phase`, if it is within the given
+    // number of hours of its exact instant.
+    pub fn nearest(phase: f64, hours: f64) -> Option<Self> {
+        let phase = phase.rem_euclid(1.);
+        let quarter = (phase * 4.).round();
+        let hours_off = (phase - quarter / 4.).abs() * MOON_SYNODIC_PERIOD * 24.;
+        if hours_off <= hours {
+            Some(Self::ALL[quarter as usize % 4])
+        } else {
+            None
+        }
+    }
+}
+
+// English names, e.g. "Waxing Crescent" and "Full Moon". See `i18n` for
+// other languages.
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(Language::English.phase(*self))
+    }
+}
+
+impl fmt::Display for PrimaryPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Phase::from(*self).fmt(f)
+    }
+}
+
+impl From<PrimaryPhase> for Phase {
+    fn from(phase: PrimaryPhase) -> Self {
+        Phase::from_index(phase as usize * 2)
+    }
+}
+
+impl MoonPhase {
+    // See `PrimaryPhase::nearest`.
+    pub fn primary_phase(&self, hours: f64) -> Option<PrimaryPhase> {
+        PrimaryPhase::nearest(self.phase, hours)
+    }
+}
+
+// Number of equal parts the cycle is divided into by `FinePhase`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Steps {
+    TwentyEight = 28,  // Roughly one per day, like lunar mansions
+    Thirty = 30,       // Like tithis or the days of a lunar month
+}
+
+// Finer grained position in the cycle than the eight phase names.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FinePhase {
+    pub step: u8,      // 1 - steps, 1 starts at the new moon
+    pub steps: u8,     // 28 or 30
+    pub phase: Phase,  // The coarse name, as in `MoonPhase::phase_name`
+}
+
+impl FinePhase {
+    pub fn new(phase: f64, steps: Steps) -> Self {
+        let count = steps as u8;
+        let step = (phase.rem_euclid(1.) * count as f64).floor() as u8;
+        FinePhase {
+            step: step.min(count - 1) + 1,
+            steps: count,
+            phase: phase_name(phase),
+        }
+    }
+}
+
+impl MoonPhase {
+    pub fn fine_phase(&self, steps: Steps) -> FinePhase {
+        FinePhase {
+            phase: self.phase_name,
+            ..FinePhase::new(self.phase, steps)
+        }
+    }
+}
+
+// Where in the current phase the moon is, in days, for countdowns such as
+// "full in 3 hours" or "was full 6 hours ago". Uses the default naming.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Proximity {
+    pub since_start: f64,            // Days since `phase_name` began
+    pub until_next: f64,             // Days until `next_phase` begins
+    pub next_phase: Phase,
+    pub primary: PrimaryPhase,       // Closest principal phase
+    pub primary_offset: f64,         // Days since its exact instant, negative if still to come
+}
+
+impl MoonPhase {
+    pub fn proximity(&self) -> Proximity {
+        let phase = self.phase.rem_euclid(1.);
+        // Names change half way between the exact phases at multiples of 1/8.
+        let octant = (phase * 8. + 0.5).floor();
+        let start = (octant - 0.5) / 8.;
+        let quarter = (phase * 4.).round();
+        Proximity {
+            since_start: (phase - start) * MOON_SYNODIC_PERIOD,
+            until_next: (start + 1. / 8. - phase) * MOON_SYNODIC_PERIOD,
+            next_phase: Phase::from_index(octant as usize + 1),
+            primary: PrimaryPhase::ALL[quarter as usize % 4],
+            primary_offset: (phase - quarter / 4.) * MOON_SYNODIC_PERIOD,
+        }
+    }
+}
+
+impl PhaseNaming {
+    pub fn name(self, phase: f64) -> Phase {
+        match self {
+            PhaseNaming::Octants => phase_name(phase),
+            PhaseNaming::Exact { hours } => match PrimaryPhase::nearest(phase, hours) {
+                Some(primary) => primary.into(),
+                // The intermediate phase of the quarter of the cycle.
+                None => Phase::from_index((phase.rem_euclid(1.) * 4.).floor() as usize * 2 + 1),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn from_str() {
+        for phase in &Phase::ALL {
+            assert_eq!(format!("{:?}", phase).parse(), Ok(*phase));
+        }
+        assert_eq!("waxing crescent".parse(), Ok(Phase::WaxingCrescent));
+        assert_eq!("LAST_QUARTER".parse(), Ok(Phase::LastQuarter));
+        assert_eq!("Full Moon".parse(), Ok(Phase::Full));
+        assert_eq!("WainingGibbous".parse(), Ok(Phase::WaningGibbous));
+        assert_eq!(Phase::WainingGibbous, Phase::WaningGibbous);
+        assert_eq!("gibbous".parse::<Phase>(), Err(ParsePhaseError));
+        for phase in &Phase::ALL {
+            assert_eq!(phase.to_string().parse(), Ok(*phase));
+        }
+        assert_eq!(PrimaryPhase::LastQuarter.to_string(), "Last Quarter");
+    }
+
+    #[test]
+    #[cfg(feature="serde")]
+    fn serde() {
+        use serde::de::value::{Error, StrDeserializer};
+        use serde::de::{Deserialize, IntoDeserializer};
+
+        let deserialize = |name: &str| {
+            let deserializer: StrDeserializer<Error> = name.into_deserializer();
+            Phase::deserialize(deserializer).ok()
+        };
+        for phase in &Phase::ALL {
+            assert_eq!(deserialize(&format!("{:?}", phase)), Some(*phase));
+        }
+        assert_eq!(deserialize("WainingGibbous"), Some(Phase::WaningGibbous));
+        assert_eq!(deserialize("waning gibbous"), None);
+    }
+
+    #[test]
+    fn octants() {
+        for i in 0..100 {
+            let phase = i as f64 / 100.;
+            assert_eq!(PhaseNaming::Octants.name(phase), phase_name(phase));
+        }
+    }
+
+    #[test]
+    fn primary_phase() {
+        let hour = 1. / MOON_SYNODIC_PERIOD / 24.;
+        assert_eq!(PrimaryPhase::nearest(0.5 + 5. * hour, 6.), Some(PrimaryPhase::Full));
+        assert_eq!(PrimaryPhase::nearest(0.5 + 7. * hour, 6.), None);
+        assert_eq!(PrimaryPhase::nearest(-5. * hour, 6.), Some(PrimaryPhase::New));
+        assert_eq!(PrimaryPhase::nearest(0.7, 48.), Some(PrimaryPhase::LastQuarter));
+        assert_eq!(Phase::from(PrimaryPhase::FirstQuarter), Phase::FirstQuarter);
+        // 2000-01-21T04:40:00+00:00, the model is off by a few hours.
+        let full = MoonPhase::from_secs(948429600);
+        assert_eq!(full.primary_phase(12.), Some(PrimaryPhase::Full));
+        assert_eq!(MoonPhase::from_secs(948429600 + 4 * 86400).primary_phase(12.), None);
+    }
+
+    #[test]
+    fn fine_phase() {
+        assert_eq!(
+            FinePhase::new(0., Steps::Thirty),
+            FinePhase { step: 1, steps: 30, phase: Phase::New }
+        );
+        assert_eq!(FinePhase::new(0.5, Steps::Thirty).step, 16);
+        assert_eq!(FinePhase::new(0.5, Steps::TwentyEight).step, 15);
+        assert_eq!(FinePhase::new(0.999, Steps::TwentyEight).step, 28);
+        assert_eq!(FinePhase::new(-0.001, Steps::Thirty).step, 30);
+        let moon_phase = MoonPhase::from_secs(948429600);
+        let fine = moon_phase.fine_phase(Steps::Thirty);
+        assert_eq!(fine.phase, moon_phase.phase_name);
+        assert!((15..=16).contains(&fine.step));
+    }
+
+    #[test]
+    fn proximity() {
+        let octant = MOON_SYNODIC_PERIOD / 8.;
+        let moon_phase = |phase: f64| MoonPhase { phase, ..MoonPhase::from_secs(0) };
+
+        let full = moon_phase(0.5).proximity();
+        assert!((full.since_start - octant / 2.).abs() < 1e-9);
+        assert!((full.until_next - octant / 2.).abs() < 1e-9);
+        assert_eq!(full.next_phase, Phase::WaningGibbous);
+        assert_eq!(full.primary, PrimaryPhase::Full);
+        assert_eq!(full.primary_offset, 0.);
+
+        let before_full = moon_phase(0.45).proximity();
+        assert_eq!(before_full.next_phase, Phase::WaningGibbous);
+        assert!((before_full.primary_offset + 0.05 * MOON_SYNODIC_PERIOD).abs() < 1e-9);
+
+        let crescent = moon_phase(0.9).proximity();
+        assert_eq!(crescent.next_phase, Phase::New);
+        assert!((crescent.until_next - (0.9375 - 0.9) * MOON_SYNODIC_PERIOD).abs() < 1e-9);
+        assert_eq!(crescent.primary, PrimaryPhase::New);
+
+        let before_new = moon_phase(-0.01).proximity();
+        assert_eq!(before_new.next_phase, Phase::WaxingCrescent);
+        assert!((before_new.primary_offset + 0.01 * MOON_SYNODIC_PERIOD).abs() < 1e-9);
+        for i in 0..100 {
+            let moon_phase = moon_phase(i as f64 / 100.);
+            let proximity = moon_phase.proximity();
+            assert!((proximity.since_start + proximity.until_next - octant).abs() < 1e-9);
+            assert_eq!(proximity.next_phase, Phase::from_cycle_fraction(moon_phase.phase).next(), "Failed for {}", i);
+        }
+    }
+
+    #[test]
+    fn exact() {
+        let naming = PhaseNaming::Exact { hours: 12. };
+        let hour = 1. / MOON_SYNODIC_PERIOD / 24.;
+        assert_eq!(naming.name(0.), Phase::New);
+        assert_eq!(naming.name(-11. * hour), Phase::New);
+        assert_eq!(naming.name(13. * hour), Phase::WaxingCrescent);
+        assert_eq!(naming.name(0.25 - 11. * hour), Phase::FirstQuarter);
+        assert_eq!(naming.name(0.25 + 13. * hour), Phase::WaxingGibbous);
+        assert_eq!(naming.name(0.5 - 13. * hour), Phase::WaxingGibbous);
+        assert_eq!(naming.name(0.5 + 11. * hour), Phase::Full);
+        assert_eq!(naming.name(0.6), Phase::WaningGibbous);
+        assert_eq!(naming.name(0.75), Phase::LastQuarter);
+        assert_eq!(naming.name(0.9), Phase::WaningCrescent);
+        assert_eq!(naming.name(1. - 11. * hour), Phase::New);
+        // With the window of an octant, it is the same as the default.
+        let octant = PhaseNaming::Exact { hours: MOON_SYNODIC_PERIOD * 1.5 };
+        for i in 0..100 {
+            let phase = i as f64 / 100. + 0.001;
+            assert_eq!(octant.name(phase), phase_name(phase), "Failed for {}", phase);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Node.js addon. Built with
+//
+//     cargo rustc --release --lib --features node --crate-type cdylib
+//     cp target/release/libmoon_phase.so moon_phase.node
+//
+// and used as
+//
+//     const { MoonPhase, events } = require("./moon_phase.node");
+//     const moon = new MoonPhase(Date.now());
+//     console.log(moon.emoji, moon.phaseName, moon.illumination);
+//     events(Date.now(), Date.now() + 30 * 86400000).map((event) => new Date(event.timestamp));
+//
+// Times are milliseconds since the epoch like `Date.now()`, names are the
+// keys of `MoonPhase::to_json`.
+
+use napi::bindgen_prelude::{Error, Result, Status};
+use napi_derive::napi;
+
+use crate::almanac::{self, Day};
+use crate::ephemeris::EARTH_RADIUS;
+use crate::json::{phase_key, zodiac_key};
+
+#[napi(js_name = "MoonPhase")]
+pub struct JsMoonPhase {
+    inner: crate::MoonPhase,
+}
+
+#[napi]
+impl JsMoonPhase {
+    // Throws for NaN and times outside of `VALID_FROM..VALID_UNTIL`.
+    #[napi(constructor)]
+    pub fn new(millis: f64) -> Result<Self> {
+        crate::MoonPhase::try_from_secs_float(millis / 1000.)
+            .map(|inner| JsMoonPhase { inner })
+            .map_err(|error| Error::new(Status::InvalidArg, error.to_string()))
+    }
+
+    #[napi(factory)]
+    pub fn now() -> Self {
+        JsMoonPhase {
+            inner: crate::MoonPhase::now(),
+        }
+    }
+
+    #[napi(getter)]
+    pub fn timestamp(&self) -> f64 {
+        (self.inner.j_date - 2440587.5) * 86400000.
+    }
+
+    #[napi(getter)]
+    pub fn julian_date(&self) -> f64 {
+        self.inner.j_date
+    }
+
+    // 0 - 1, 0.5 = full
+    #[napi(getter)]
+    pub fn phase(&self) -> f64 {
+        self.inner.phase
+    }
+
+    // Days
+    #[napi(getter)]
+    pub fn age(&self) -> f64 {
+        self.inner.age
+    }
+
+    // 0 - 1
+    #[napi(getter)]
+    pub fn illumination(&self) -> f64 {
+        self.inner.illumination
+    }
+
+    #[napi(getter)]
+    pub fn phase_name(&self) -> String {
+        phase_key(self.inner.phase_name).to_string()
+    }
+
+    #[napi(getter)]
+    pub fn emoji(&self) -> String {
+        self.inner.phase_name.emoji().to_string()
+    }
+
+    #[napi(getter)]
+    pub fn distance_km(&self) -> f64 {
+        self.inner.distance * EARTH_RADIUS
+    }
+
+    // Ecliptic, degrees
+    #[napi(getter)]
+    pub fn latitude(&self) -> f64 {
+        self.inner.latitude
+    }
+
+    // Ecliptic, degrees
+    #[napi(getter)]
+    pub fn longitude(&self) -> f64 {
+        self.inner.longitude
+    }
+
+    #[napi(getter)]
+    pub fn zodiac(&self) -> String {
+        zodiac_key(self.inner.zodiac_name).to_string()
+    }
+
+    #[napi]
+    pub fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseEvent {
+    pub phase: String,   // "new", "first_quarter", "full" or "last_quarter"
+    pub timestamp: f64,  // Milliseconds since the epoch
+}
+
+impl From<&almanac::PhaseEvent> for PhaseEvent {
+    fn from(event: &almanac::PhaseEvent) -> Self {
+        PhaseEvent {
+            phase: phase_key(event.phase.into()).to_string(),
+            timestamp: event.secs * 1000.,
+        }
+    }
+}
+
+#[napi(object, js_name = "Day")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsDay {
+    pub date: String,        // Local YYYY-MM-DD
+    pub phase_name: String,
+    pub illumination: f64,   // 0 - 1, at local noon
+    pub age: f64,            // Days
+    pub zodiac: String,
+    pub events: Vec<PhaseEvent>,
+}
+
+impl From<&Day> for JsDay {
+    fn from(day: &Day) -> Self {
+        JsDay {
+            date: format!("{:04}-{:02}-{:02}", day.date.0, day.date.1, day.date.2),
+            phase_name: phase_key(day.phase_name).to_string(),
+            illumination: day.illumination,
+            age: day.age,
+            zodiac: zodiac_key(day.zodiac).to_string(),
+            events: day.events.iter().map(PhaseEvent::from).collect(),
+        }
+    }
+}
+
+// New, full and quarter moons from `start` up to `end`.
+#[napi]
+pub fn events(start: f64, end: f64) -> Vec<PhaseEvent> {
+    almanac::events(start / 1000., end / 1000.).iter().map(PhaseEvent::from).collect()
+}
+
+// Every day of `month` (1 - 12) in a time zone `utcOffset` minutes ahead of
+// UTC, 0 if left out. Note that `Date.getTimezoneOffset` counts the other way.
+#[napi]
+pub fn month(year: i32, month: u32, utc_offset: Option<i32>) -> Result<Vec<JsDay>> {
+    if !(1..=12).contains(&month) {
+        return Err(Error::new(Status::InvalidArg, "month must be 1 - 12".to_string()));
+    }
+    let utc_offset = utc_offset.unwrap_or(0).saturating_mul(60);
+    #[cfg(feature="chrono")]
+    let days = {
+        let utc_offset = chrono::FixedOffset::east_opt(utc_offset)
+            .ok_or_else(|| Error::new(Status::InvalidArg, "UTC offset out of range".to_string()))?;
+        almanac::month(year, month, &utc_offset)
+    };
+    #[cfg(not(feature="chrono"))]
+    let days = almanac::month(year, month, utc_offset);
+    Ok(days.iter().map(JsDay::from).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn api() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let moon_phase = JsMoonPhase::new(1642463280000.).unwrap();
+        assert_eq!(moon_phase.phase_name(), "full");
+        assert!((moon_phase.timestamp() - 1642463280000.).abs() < 1.);
+        assert_eq!(moon_phase.to_json(), crate::MoonPhase::from_secs(1642463280).to_json());
+        assert!(JsMoonPhase::new(f64::NAN).is_err());
+
+        let events = events(1640995200000., 1643673600000.);
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2].phase, "full");
+        let days = month(2022, 1, Some(60)).unwrap();
+        assert_eq!(days.len(), 31);
+        assert_eq!(days[0].date, "2022-01-01");
+        assert_eq!(days.iter().flat_map(|day| &day.events).count(), 4);
+        assert!(month(2022, 13, None).is_err());
+    }
+}
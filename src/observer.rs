@@ -0,0 +1,264 @@
+// A place on the earth the moon is looked at from. The moon is close enough
+// for the observer's position to shift it against the stars by up to a
+// degree (parallax), and which way the lit side points depends on the
+// hemisphere.
+
+use crate::events::zero_crossings;
+use crate::{MoonPhase, Zodiac};
+
+const J2000: f64 = 2451545.0;
+// Altitude of the center at rise and set, for refraction and semidiameter.
+const HORIZON: f64 = -0.833;
+const RISE_SET_STEP_SECS: f64 = 600.;
+const EARTH_FLATTENING: f64 = 0.99664719; // Ratio of polar to equatorial radius
+const EARTH_RADIUS_METERS: f64 = 6378140.;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Observer {
+    pub latitude: f64,     // Geodetic latitude in degrees, north positive
+    pub longitude: f64,    // Degrees, east positive
+    pub elevation: f64,    // Meters above sea level
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Hemisphere {
+    #[default]
+    North,
+    South,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Twilight {
+    Evening,  // After sunset, the waxing crescent in the west
+    Morning,  // Before sunrise, the waning crescent in the east
+}
+
+// Moonrise and moonset within a day. The moon rises about 50 minutes later
+// every day, so one of them is missing on about one day a month, and both can
+// be missing near the poles.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct RiseSet {
+    pub rise: Option<f64>,  // Unix seconds
+    pub set: Option<f64>,   // Unix seconds
+}
+
+// Side of the disk that is lit, as seen by an observer facing the moon.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Limb {
+    Left,
+    Right,
+}
+
+impl Observer {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Observer {
+            latitude,
+            longitude,
+            elevation: 0.,
+        }
+    }
+
+    pub fn with_elevation(self, elevation: f64) -> Self {
+        Observer { elevation, ..self }
+    }
+
+    pub fn hemisphere(&self) -> Hemisphere {
+        if self.latitude < 0. {
+            Hemisphere::South
+        } else {
+            Hemisphere::North
+        }
+    }
+
+    // Local mean sidereal time in degrees, after Meeus (12.4).
+    pub fn sidereal_time(&self, j_date: f64) -> f64 {
+        let t = (j_date - J2000) / 36525.;
+        let gmst = 280.46061837 + 360.98564736629 * (j_date - J2000) + 0.000387933 * t * t
+            - t * t * t / 38710000.;
+        (gmst + self.longitude).rem_euclid(360.)
+    }
+
+    // Altitude above the horizon and azimuth (from north through east), in
+    // degrees and without refraction, of a body at the given ecliptic
+    // longitude and latitude.
+    pub fn horizontal(&self, j_date: f64, longitude: f64, latitude: f64) -> (f64, f64) {
+        let obliquity = obliquity(j_date);
+        let (longitude, beta) = (longitude.to_radians(), latitude.to_radians());
+        let right_ascension = (longitude.sin() * obliquity.cos() - beta.tan() * obliquity.sin())
+            .atan2(longitude.cos());
+        let declination =
+            (beta.sin() * obliquity.cos() + beta.cos() * obliquity.sin() * longitude.sin()).asin();
+        let hour_angle = self.sidereal_time(j_date).to_radians() - right_ascension;
+        let phi = self.latitude.to_radians();
+        let altitude = (phi.sin() * declination.sin()
+            + phi.cos() * declination.cos() * hour_angle.cos())
+        .asin();
+        let azimuth = hour_angle
+            .sin()
+            .atan2(hour_angle.cos() * phi.sin() - declination.tan() * phi.cos());
+        (altitude.to_degrees(), (azimuth.to_degrees() + 180.).rem_euclid(360.))
+    }
+
+    // Altitude of the center of the moon above the horizon in degrees, seen
+    // from the observer and without refraction.
+    pub fn moon_altitude(&self, moon_phase: &MoonPhase) -> f64 {
+        let topocentric = self.topocentric(moon_phase);
+        self.horizontal(topocentric.j_date, topocentric.longitude, topocentric.latitude).0
+    }
+
+    // The first moonrise and moonset in the 24 hours from `start` (unix
+    // seconds), usually local midnight.
+    pub fn moon_rise_set(&self, start: f64) -> RiseSet {
+        let altitude = |secs| self.moon_altitude(&MoonPhase::from_secs_float(secs)) - HORIZON;
+        let mut rise_set = RiseSet::default();
+        for secs in zero_crossings(altitude, start, start + 86400., RISE_SET_STEP_SECS) {
+            let slot = if altitude(secs + 1.) > 0. { &mut rise_set.rise } else { &mut rise_set.set };
+            slot.get_or_insert(secs);
+        }
+        rise_set
+    }
+
+    // Converts the geocentric position of `moon_phase` into the one seen by
+    // the observer, after Meeus (40.6 - 40.7) in ecliptic coordinates.
+    pub fn topocentric(&self, moon_phase: &MoonPhase) -> MoonPhase {
+        let j_date = moon_phase.j_date;
+        let obliquity = obliquity(j_date);
+        let sidereal = self.sidereal_time(j_date).to_radians();
+
+        let latitude = self.latitude.to_radians();
+        let u = (EARTH_FLATTENING * latitude.tan()).atan();
+        let height = self.elevation / EARTH_RADIUS_METERS;
+        let rho_sin = EARTH_FLATTENING * u.sin() + height * latitude.sin();
+        let rho_cos = u.cos() + height * latitude.cos();
+
+        let sin_parallax = 1. / moon_phase.distance;
+        let longitude = moon_phase.longitude.to_radians();
+        let beta = moon_phase.latitude.to_radians();
+        // Geocentric direction of the moon minus the observer's position,
+        // in units of the moon's geocentric distance.
+        let x = longitude.cos() * beta.cos() - sin_parallax * rho_cos * sidereal.cos();
+        let y = longitude.sin() * beta.cos()
+            - sin_parallax * (rho_sin * obliquity.sin() + rho_cos * obliquity.cos() * sidereal.sin());
+        let z = beta.sin()
+            - sin_parallax * (rho_sin * obliquity.cos() - rho_cos * obliquity.sin() * sidereal.sin());
+        let topo_longitude = y.atan2(x);
+        let topo_latitude = z.atan2(x.hypot(y));
+        let distance = moon_phase.distance * (x * x + y * y + z * z).sqrt();
+
+        let longitude = topo_longitude.to_degrees().rem_euclid(360.);
+        MoonPhase {
+            distance,
+            latitude: topo_latitude.to_degrees(),
+            longitude,
+            zodiac_name: Zodiac::from_long(longitude),
+            ..*moon_phase
+        }
+    }
+}
+
+// Mean obliquity of the ecliptic in radians.
+fn obliquity(j_date: f64) -> f64 {
+    let t = (j_date - J2000) / 36525.;
+    (23.439291 - 0.0130042 * t).to_radians()
+}
+
+impl MoonPhase {
+    // Waxing moons are lit on the right in the northern hemisphere and on the
+    // left in the southern one, waning moons the other way around.
+    pub fn lit_limb(&self, hemisphere: Hemisphere) -> Limb {
+        let waxing = self.phase.rem_euclid(1.) < 0.5;
+        match (waxing, hemisphere) {
+            (true, Hemisphere::North) | (false, Hemisphere::South) => Limb::Right,
+            (true, Hemisphere::South) | (false, Hemisphere::North) => Limb::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sidereal_time() {
+        // Meeus example 12.a, 1987-04-10T00:00:00 UT.
+        let greenwich = Observer::default();
+        assert!((greenwich.sidereal_time(2446895.5) - 197.693195).abs() < 1e-5);
+        let east = Observer::new(0., 10.);
+        assert!((east.sidereal_time(2446895.5) - 207.693195).abs() < 1e-5);
+    }
+
+    #[test]
+    fn horizontal() {
+        // Sun at the March equinox 2000-03-20T07:35:00+00:00 is in the zenith
+        // where it is noon on the equator.
+        let j_date = 2451623.8159722;
+        let observer = Observer::new(0., 0.);
+        let longitude = -observer.sidereal_time(j_date);
+        let (altitude, _) = Observer::new(0., longitude).horizontal(j_date, 0., 0.);
+        assert!((altitude - 90.).abs() < 0.1, "Failed for {}", altitude);
+        // The north celestial pole stands at the observer's latitude, due north.
+        let (altitude, azimuth) = Observer::new(52.5, 13.4).horizontal(j_date, 90., 66.560709);
+        assert!((altitude - 52.5).abs() < 1e-3, "Failed for {}", altitude);
+        assert!(azimuth.min(360. - azimuth) < 0.5, "Failed for {}", azimuth);
+    }
+
+    #[test]
+    fn moon_altitude() {
+        // The full moon of 2022-01-17T23:48:00+00:00 culminates around
+        // midnight and is below the horizon at noon.
+        let greenwich = Observer::new(51.48, 0.);
+        let midnight = greenwich.moon_altitude(&MoonPhase::from_secs(1642464000));
+        assert!((40.0..70.).contains(&midnight), "Failed for {}", midnight);
+        let noon = greenwich.moon_altitude(&MoonPhase::from_secs(1642507200));
+        assert!(noon < -10., "Failed for {}", noon);
+    }
+
+    #[test]
+    fn moon_rise_set() {
+        // Around the full moon of 2022-01-17T23:48:00+00:00 the moon rises
+        // near sunset and sets near sunrise.
+        let greenwich = Observer::new(51.48, 0.);
+        let midnight = 1642377600.;
+        let rise_set = greenwich.moon_rise_set(midnight);
+        let rise = (rise_set.rise.unwrap() - midnight) / 3600.;
+        let set = (rise_set.set.unwrap() - midnight) / 3600.;
+        assert!((14.0..18.).contains(&rise), "Failed for {}", rise);
+        assert!((6.0..10.).contains(&set), "Failed for {}", set);
+        let rise = rise_set.rise.unwrap();
+        assert!(greenwich.moon_altitude(&MoonPhase::from_secs_float(rise)).abs() < 1.5);
+        // Circumpolar near the pole around the northern winter full moon.
+        let rise_set = Observer::new(85., 0.).moon_rise_set(1642377600.);
+        assert_eq!(rise_set, RiseSet::default());
+    }
+
+    #[test]
+    fn topocentric() {
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        // At the center of the earth, nothing changes.
+        let center = Observer::new(0., 0.).with_elevation(-EARTH_RADIUS_METERS);
+        let topocentric = center.topocentric(&moon_phase);
+        assert!((topocentric.longitude - moon_phase.longitude).abs() < 1e-3);
+        assert!((topocentric.distance - moon_phase.distance).abs() < 1e-2);
+
+        // The parallax is at most about a degree, or one earth radius.
+        for secs in (1642291200..1642291200 + 86400).step_by(3600) {
+            let moon_phase = MoonPhase::from_secs(secs);
+            let topocentric = Observer::new(52.5, 13.4).topocentric(&moon_phase);
+            let diff = (topocentric.longitude - moon_phase.longitude + 180.).rem_euclid(360.) - 180.;
+            assert!(diff.abs() < 1.1, "Failed for {}", secs);
+            assert!((topocentric.latitude - moon_phase.latitude).abs() < 1.1, "Failed for {}", secs);
+            assert!((topocentric.distance - moon_phase.distance).abs() <= 1., "Failed for {}", secs);
+            assert_eq!(topocentric.phase, moon_phase.phase);
+        }
+    }
+
+    #[test]
+    fn lit_limb() {
+        let waxing = MoonPhase::from_secs(947856840); // 2000-01-14T13:34:00+00:00
+        let waning = MoonPhase::from_secs(949046160); // 2000-01-28T07:56:00+00:00
+        assert_eq!(waxing.lit_limb(Hemisphere::North), Limb::Right);
+        assert_eq!(waxing.lit_limb(Hemisphere::South), Limb::Left);
+        assert_eq!(waning.lit_limb(Hemisphere::North), Limb::Left);
+        assert_eq!(Observer::new(-33.9, 151.2).hemisphere(), Hemisphere::South);
+    }
+}
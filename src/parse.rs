@@ -0,0 +1,158 @@
+// Minimal RFC 3339 parser, so a timestamp string can be turned into a phase
+// without a datetime crate. Accepts `YYYY-MM-DDTHH:MM:SS[.frac]` followed by
+// `Z` or a `+HH:MM` / `-HH:MM` offset. A space may be used instead of the `T`.
+
+use std::fmt;
+
+use crate::calendar::days_from_civil;
+use crate::MoonPhase;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParseError {
+    // The string does not have the shape of an RFC 3339 timestamp.
+    InvalidFormat,
+    // A field is out of its range, e.g. month 13 or February 30th.
+    OutOfRange,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidFormat => write!(f, "not an RFC 3339 timestamp"),
+            ParseError::OutOfRange => write!(f, "timestamp field out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl MoonPhase {
+    pub fn from_rfc3339(time: &str) -> Result<Self, ParseError> {
+        parse_rfc3339(time).map(Self::from_secs_float)
+    }
+}
+
+// Returns the unix seconds of the timestamp.
+pub(crate) fn parse_rfc3339(time: &str) -> Result<f64, ParseError> {
+    let bytes = time.as_bytes();
+    if bytes.len() < 20 {
+        return Err(ParseError::InvalidFormat);
+    }
+    let expect = |i: usize, c: u8| {
+        if bytes[i] == c {
+            Ok(())
+        } else {
+            Err(ParseError::InvalidFormat)
+        }
+    };
+    let year = digits(&bytes[0..4])? as i64;
+    expect(4, b'-')?;
+    let month = digits(&bytes[5..7])?;
+    expect(7, b'-')?;
+    let day = digits(&bytes[8..10])?;
+    if !matches!(bytes[10], b'T' | b't' | b' ') {
+        return Err(ParseError::InvalidFormat);
+    }
+    let hour = digits(&bytes[11..13])?;
+    expect(13, b':')?;
+    let minute = digits(&bytes[14..16])?;
+    expect(16, b':')?;
+    let second = digits(&bytes[17..19])?;
+
+    let mut rest = &bytes[19..];
+    let mut fraction = 0.;
+    if rest[0] == b'.' {
+        let len = rest[1..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if len == 0 {
+            return Err(ParseError::InvalidFormat);
+        }
+        // Only digits, so this can't fail.
+        fraction = std::str::from_utf8(&rest[..len + 1]).unwrap().parse().unwrap();
+        rest = &rest[len + 1..];
+    }
+
+    let offset = match rest {
+        [b'Z'] | [b'z'] => 0,
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let hours = digits(&[*h1, *h2])?;
+            let minutes = digits(&[*m1, *m2])?;
+            if hours > 23 || minutes > 59 {
+                return Err(ParseError::OutOfRange);
+            }
+            let offset = (hours * 3600 + minutes * 60) as i64;
+            if *sign == b'-' { -offset } else { offset }
+        }
+        _ => return Err(ParseError::InvalidFormat),
+    };
+
+    // A second of 60 is allowed for leap seconds.
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return Err(ParseError::OutOfRange);
+    }
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86400 + (hour * 3600 + minute * 60 + second) as i64 - offset;
+    Ok(secs as f64 + fraction)
+}
+
+fn digits(bytes: &[u8]) -> Result<u32, ParseError> {
+    bytes.iter().try_fold(0, |value, c| {
+        if c.is_ascii_digit() {
+            Ok(value * 10 + (c - b'0') as u32)
+        } else {
+            Err(ParseError::InvalidFormat)
+        }
+    })
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_rfc3339("2000-01-21T04:40:00+00:00"), Ok(948429600.));
+        assert_eq!(parse_rfc3339("2000-01-21T04:40:00Z"), Ok(948429600.));
+        assert_eq!(parse_rfc3339("2000-01-21 05:40:00+01:00"), Ok(948429600.));
+        assert_eq!(parse_rfc3339("2000-01-20t23:10:00-05:30"), Ok(948429600.));
+        assert_eq!(parse_rfc3339("1969-12-31T23:59:59.25Z"), Ok(-0.75));
+        assert_eq!(parse_rfc3339("2000-02-29T00:00:00Z"), Ok(951782400.));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_rfc3339(""), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000-01-21"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000-01-21T04:40:00"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000/01/21T04:40:00Z"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000-01-21T04:40:00.Z"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000-01-21T04:40:00+0100"), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_rfc3339("2000-13-21T04:40:00Z"), Err(ParseError::OutOfRange));
+        assert_eq!(parse_rfc3339("2001-02-29T04:40:00Z"), Err(ParseError::OutOfRange));
+        assert_eq!(parse_rfc3339("2000-01-21T24:40:00Z"), Err(ParseError::OutOfRange));
+    }
+
+    #[test]
+    fn from_rfc3339() {
+        assert_eq!(
+            MoonPhase::from_rfc3339("2000-01-21T04:40:00+00:00"),
+            Ok(MoonPhase::from_secs(948429600))
+        );
+        assert!(MoonPhase::from_rfc3339("yesterday").is_err());
+    }
+}
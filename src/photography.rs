@@ -0,0 +1,98 @@
+// Exposure for photographing the moon, after the "Looney 11" rule: the full
+// moon takes 1/ISO seconds at f/11, and every other phase as much more as
+// its lit surface is fainter. The surface brightness is the apparent
+// magnitude spread over the lit part of the disk, and the atmosphere dims a
+// low moon further.
+
+use crate::observer::Observer;
+use crate::MoonPhase;
+
+// The full moon high in the sky, for which Looney 11 holds, in V
+// mag/arcsec^2 seen from the ground.
+const LOONEY_11_SURFACE_BRIGHTNESS: f64 = 3.6;
+const LOONEY_11_APERTURE: f64 = 11.;
+// Extinction in V in magnitudes per airmass.
+const EXTINCTION: f64 = 0.2;
+// Radius of the moon over the earth's equatorial radius.
+pub(crate) const MOON_RADIUS_RATIO: f64 = 0.27245;
+
+impl MoonPhase {
+    // Geocentric apparent radius of the disk in arc seconds.
+    pub fn semidiameter(&self) -> f64 {
+        (MOON_RADIUS_RATIO / self.distance).asin().to_degrees() * 3600.
+    }
+
+    // Mean brightness of the lit part of the disk outside the atmosphere, in
+    // V mag/arcsec^2. About 3.4 at full moon and 5.3 at the quarters.
+    pub fn surface_brightness(&self) -> f64 {
+        let radius = self.semidiameter();
+        let lit_area = self.illumination.max(1e-6) * std::f64::consts::PI * radius * radius;
+        self.apparent_magnitude() + 2.5 * lit_area.log10()
+    }
+}
+
+impl Observer {
+    // `MoonPhase::surface_brightness` through the atmosphere, as seen by the
+    // observer. None while the moon is below the horizon.
+    pub fn moon_surface_brightness(&self, moon_phase: &MoonPhase) -> Option<f64> {
+        let altitude = self.moon_altitude(moon_phase);
+        if altitude <= 0. {
+            return None;
+        }
+        // Airmass after Kasten and Young (1989), fine down to the horizon.
+        let airmass = 1. / (altitude.to_radians().sin() + 0.50572 * (altitude + 6.07995).powf(-1.6364));
+        Some(moon_phase.surface_brightness() + EXTINCTION * airmass)
+    }
+
+    // Suggested shutter time in seconds for `iso` and the f-number
+    // `aperture`, or None while the moon is below the horizon.
+    pub fn moon_exposure(&self, moon_phase: &MoonPhase, iso: f64, aperture: f64) -> Option<f64> {
+        self.moon_surface_brightness(moon_phase)
+            .map(|surface_brightness| exposure_time(surface_brightness, iso, aperture))
+    }
+}
+
+// Shutter time in seconds for a surface of the given brightness in V
+// mag/arcsec^2, for custom calculators.
+pub fn exposure_time(surface_brightness: f64, iso: f64, aperture: f64) -> f64 {
+    let looney_11 = (aperture / LOONEY_11_APERTURE).powi(2) / iso;
+    looney_11 * 10f64.powf(0.4 * (surface_brightness - LOONEY_11_SURFACE_BRIGHTNESS))
+}
+
+// Exposure differences in stops, positive for more light.
+pub fn stops(from_secs: f64, to_secs: f64) -> f64 {
+    (to_secs / from_secs).log2()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn surface_brightness() {
+        // Full moon on 2022-01-17T23:48:00+00:00, first quarter on the 9th.
+        let full = MoonPhase::from_secs(1642463280);
+        assert!((1760.0..2010.).contains(&(2. * full.semidiameter())), "Failed for {}", full.semidiameter());
+        let full_brightness = full.surface_brightness();
+        assert!((3.0..3.8).contains(&full_brightness), "Failed for {}", full_brightness);
+        let quarter = MoonPhase::from_secs(1641751860).surface_brightness();
+        assert!((1.4..2.4).contains(&(quarter - full_brightness)), "Failed for {}", quarter);
+    }
+
+    #[test]
+    fn looney_11() {
+        // The full moon high over Greenwich at midnight needs about 1/100 s
+        // at ISO 100 and f/11, the first quarter in the evening about two
+        // stops more.
+        let greenwich = Observer::new(51.48, 0.);
+        let full = greenwich.moon_exposure(&MoonPhase::from_secs(1642464000), 100., 11.).unwrap();
+        assert!(stops(1. / 100., full).abs() < 0.5, "Failed for {}", full);
+        let quarter = greenwich.moon_exposure(&MoonPhase::from_secs(1641754800), 100., 11.).unwrap();
+        assert!((stops(full, quarter) - 2.).abs() < 0.8, "Failed for {}", stops(full, quarter));
+        // Two stops of aperture, two stops of time.
+        let wide = greenwich.moon_exposure(&MoonPhase::from_secs(1642464000), 100., 5.6).unwrap();
+        assert!((stops(wide, full) - 1.95).abs() < 0.05, "Failed for {}", stops(wide, full));
+        // Below the horizon at noon.
+        assert_eq!(greenwich.moon_exposure(&MoonPhase::from_secs(1642507200), 100., 11.), None);
+    }
+}
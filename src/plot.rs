@@ -0,0 +1,112 @@
+// Charts of the moon over a range of time with plotters, drawn on any of its
+// backends (bitmap, SVG, canvas, ...). The x axis is in days since the start
+// of the range, values are those of `MoonPhase::from_secs_float`.
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::ephemeris::EARTH_RADIUS;
+use crate::observer::Observer;
+use crate::MoonPhase;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Quantity {
+    Illumination,         // Percent of the disk
+    Distance,             // km
+    Altitude(Observer),   // Degrees above the horizon of the observer
+}
+
+impl Quantity {
+    pub fn value(&self, moon_phase: &MoonPhase) -> f64 {
+        match self {
+            Quantity::Illumination => moon_phase.illumination * 100.,
+            Quantity::Distance => moon_phase.distance * EARTH_RADIUS,
+            Quantity::Altitude(observer) => observer.moon_altitude(moon_phase),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Quantity::Illumination => "Illumination (%)",
+            Quantity::Distance => "Distance (km)",
+            Quantity::Altitude(_) => "Altitude (°)",
+        }
+    }
+}
+
+// Points (days since `from`, value) every `step` seconds from `from` up to
+// and including `until` (unix seconds). Empty for a step that isn't
+// positive.
+pub fn sample(quantity: Quantity, from: f64, until: f64, step: f64) -> Vec<(f64, f64)> {
+    if step.is_nan() || step <= 0. {
+        return Vec::new();
+    }
+    (0..)
+        .map(|i| from + i as f64 * step)
+        .take_while(|secs| *secs <= until)
+        .map(|secs| ((secs - from) / 86400., quantity.value(&MoonPhase::from_secs_float(secs))))
+        .collect()
+}
+
+// Draws a line chart of `quantity` into `area`, sampled every `step`
+// seconds between `from` and `until` (unix seconds).
+pub fn draw<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    quantity: Quantity,
+    from: f64,
+    until: f64,
+    step: f64,
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let points = sample(quantity, from, until, step);
+    let (low, high) = match quantity {
+        Quantity::Illumination => (0., 100.),
+        Quantity::Altitude(_) => (-90., 90.),
+        Quantity::Distance => points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), (_, km)| {
+            (low.min(*km), high.max(*km))
+        }),
+    };
+    let (low, high) = if low < high { (low, high) } else { (low - 1., high + 1.) };
+    let days = ((until - from) / 86400.).max(f64::MIN_POSITIVE);
+
+    area.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(area)
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0f64..days, low..high)?;
+    chart
+        .configure_mesh()
+        .x_desc("Days")
+        .y_desc(quantity.label())
+        .draw()?;
+    chart.draw_series(LineSeries::new(points, &BLUE))?;
+    area.present()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn samples() {
+        let points = sample(Quantity::Illumination, 1642464000., 1642464000. + 86400., 3600.);
+        assert_eq!(points.len(), 25);
+        assert_eq!(points[24].0, 1.);
+        assert!(points.iter().all(|(_, percent)| *percent > 98.), "Failed for {:?}", points);
+        let points = sample(Quantity::Distance, 1642464000., 1642464000., 3600.);
+        assert!((350_000. ..410_000.).contains(&points[0].1), "Failed for {:?}", points);
+        assert!(sample(Quantity::Distance, 0., 1., 0.).is_empty());
+    }
+
+    #[test]
+    fn svg_chart() {
+        let mut svg = String::new();
+        {
+            let area = SVGBackend::with_string(&mut svg, (640, 480)).into_drawing_area();
+            let greenwich = Observer::new(51.48, 0.);
+            draw(&area, Quantity::Altitude(greenwich), 1642464000., 1642464000. + 3. * 86400., 1800.).unwrap();
+        }
+        assert!(svg.contains("<polyline"), "Failed for {}", svg);
+        assert!(svg.contains("Altitude"));
+    }
+}
@@ -0,0 +1,76 @@
+// The moon in the Prometheus text exposition format, to be served on a
+// /metrics endpoint with `CONTENT_TYPE` and scraped into dashboards:
+//
+//     # HELP moon_illumination_ratio Lit fraction of the disk.
+//     # TYPE moon_illumination_ratio gauge
+//     moon_illumination_ratio 0.9999
+//     ...
+//     moon_phase_info{phase="full",zodiac="cancer"} 1
+
+use std::fmt::Write;
+
+use crate::almanac::events;
+use crate::ephemeris::EARTH_RADIUS;
+use crate::json::{phase_key, zodiac_key};
+use crate::naming::PrimaryPhase;
+use crate::MoonPhase;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// Longer than a synodic month, so a full moon is always found.
+const FULL_MOON_SEARCH_DAYS: f64 = 31.;
+
+fn gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    // Writing to a String can't fail.
+    let _ = write!(out, "# HELP {} {}\n# TYPE {} gauge\n{} {}\n", name, help, name, name, value);
+}
+
+impl MoonPhase {
+    pub fn to_prometheus(&self) -> String {
+        let secs = (self.j_date - 2440587.5) * 86400.;
+        let days_to_full = events(secs, secs + FULL_MOON_SEARCH_DAYS * 86400.)
+            .iter()
+            .find(|event| event.phase == PrimaryPhase::Full)
+            .map_or(f64::NAN, |event| (event.secs - secs) / 86400.);
+
+        let mut out = String::new();
+        gauge(&mut out, "moon_illumination_ratio", "Lit fraction of the disk.", self.illumination);
+        gauge(&mut out, "moon_phase_ratio", "Position in the synodic month, 0 new and 0.5 full.", self.phase);
+        gauge(&mut out, "moon_age_days", "Days since the new moon.", self.age);
+        gauge(&mut out, "moon_days_to_full", "Days until the next full moon.", days_to_full);
+        gauge(&mut out, "moon_distance_kilometers", "Distance from the center of the earth.", self.distance * EARTH_RADIUS);
+        let _ = write!(
+            out,
+            "# HELP moon_phase_info Name of the phase and zodiac sign.\n# TYPE moon_phase_info gauge\n\
+             moon_phase_info{{phase=\"{}\",zodiac=\"{}\"}} 1\n",
+            phase_key(self.phase_name),
+            zodiac_key(self.zodiac_name)
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn value(text: &str, name: &str) -> f64 {
+        let line = text.lines().find(|line| line.starts_with(&format!("{} ", name))).unwrap();
+        line[name.len() + 1..].parse().unwrap()
+    }
+
+    #[test]
+    fn to_prometheus() {
+        // Eight days before the full moon of 2022-01-17T23:48:00+00:00.
+        let text = MoonPhase::from_secs(1642463280 - 8 * 86400).to_prometheus();
+        assert!((value(&text, "moon_days_to_full") - 8.).abs() < 0.01, "Failed for {}", text);
+        assert!((value(&text, "moon_illumination_ratio") - 0.5).abs() < 0.1, "Failed for {}", text);
+        assert!((350000.0..410000.).contains(&value(&text, "moon_distance_kilometers")), "Failed for {}", text);
+        assert!(text.contains("moon_phase_info{phase=\"first_quarter\",zodiac="), "Failed for {}", text);
+        // Every sample has its HELP and TYPE.
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {} gauge", name)), "Failed for {}", name);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Python module `moon_phase`, built as a wheel with maturin (pyproject.toml):
+//
+//     >>> import moon_phase
+//     >>> from datetime import datetime, timezone
+//     >>> moon = moon_phase.MoonPhase(datetime(2022, 1, 17, 23, 48, tzinfo=timezone.utc))
+//     >>> moon.phase_name, round(moon.illumination, 3)
+//     ('full', 1.0)
+//     >>> moon_phase.events(datetime(2022, 1, 1, tzinfo=timezone.utc), datetime(2022, 2, 1, tzinfo=timezone.utc))
+//     [PhaseEvent('new', 1641148377.2236347), ...]
+//
+// Times are datetimes or unix seconds. As with `datetime.timestamp`, naive
+// datetimes are taken as local time. Names are the keys of
+// `MoonPhase::to_json`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::almanac::{self, BlueMoonKind, Day, PhaseEvent};
+use crate::ephemeris::EARTH_RADIUS;
+use crate::json::{phase_key, zodiac_key};
+
+// Unix seconds of a datetime or a number.
+fn unix_seconds(time: &Bound<'_, PyAny>) -> PyResult<f64> {
+    if time.hasattr("timestamp")? {
+        time.call_method0("timestamp")?.extract()
+    } else {
+        time.extract()
+    }
+}
+
+#[pyclass(name = "MoonPhase", module = "moon_phase", frozen)]
+pub struct PyMoonPhase(crate::MoonPhase);
+
+#[pymethods]
+impl PyMoonPhase {
+    // ValueError for NaN and times outside of `VALID_FROM..VALID_UNTIL`.
+    #[new]
+    fn new(time: &Bound<'_, PyAny>) -> PyResult<Self> {
+        crate::MoonPhase::try_from_secs_float(unix_seconds(time)?)
+            .map(PyMoonPhase)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+
+    #[staticmethod]
+    fn now() -> Self {
+        PyMoonPhase(crate::MoonPhase::now())
+    }
+
+    // Unix seconds
+    #[getter]
+    fn timestamp(&self) -> f64 {
+        (self.0.j_date - 2440587.5) * 86400.
+    }
+
+    #[getter]
+    fn julian_date(&self) -> f64 {
+        self.0.j_date
+    }
+
+    // 0 - 1, 0.5 = full
+    #[getter]
+    fn phase(&self) -> f64 {
+        self.0.phase
+    }
+
+    // Days
+    #[getter]
+    fn age(&self) -> f64 {
+        self.0.age
+    }
+
+    // 0 - 1
+    #[getter]
+    fn illumination(&self) -> f64 {
+        self.0.illumination
+    }
+
+    #[getter]
+    fn phase_name(&self) -> &'static str {
+        phase_key(self.0.phase_name)
+    }
+
+    #[getter]
+    fn emoji(&self) -> String {
+        self.0.phase_name.emoji().to_string()
+    }
+
+    #[getter]
+    fn distance_km(&self) -> f64 {
+        self.0.distance * EARTH_RADIUS
+    }
+
+    #[getter]
+    fn distance_earth_radii(&self) -> f64 {
+        self.0.distance
+    }
+
+    // Ecliptic, degrees
+    #[getter]
+    fn latitude(&self) -> f64 {
+        self.0.latitude
+    }
+
+    // Ecliptic, degrees
+    #[getter]
+    fn longitude(&self) -> f64 {
+        self.0.longitude
+    }
+
+    #[getter]
+    fn zodiac(&self) -> &'static str {
+        zodiac_key(self.0.zodiac_name)
+    }
+
+    fn to_json(&self) -> String {
+        self.0.to_json()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("MoonPhase({})", self.timestamp())
+    }
+}
+
+#[pyclass(name = "PhaseEvent", module = "moon_phase", frozen, get_all)]
+#[derive(Clone)]
+pub struct PyPhaseEvent {
+    phase: &'static str,  // "new", "first_quarter", "full" or "last_quarter"
+    timestamp: f64,       // Unix seconds
+}
+
+#[pymethods]
+impl PyPhaseEvent {
+    fn __repr__(&self) -> String {
+        format!("PhaseEvent('{}', {:?})", self.phase, self.timestamp)
+    }
+}
+
+impl From<&PhaseEvent> for PyPhaseEvent {
+    fn from(event: &PhaseEvent) -> Self {
+        PyPhaseEvent {
+            phase: phase_key(event.phase.into()),
+            timestamp: event.secs,
+        }
+    }
+}
+
+#[pyclass(name = "Day", module = "moon_phase", frozen, get_all)]
+pub struct PyDay {
+    date: (i32, u32, u32),  // Local (year, month, day)
+    phase_name: &'static str,
+    illumination: f64,      // 0 - 1, at local noon
+    age: f64,               // Days
+    zodiac: &'static str,
+    events: Vec<PyPhaseEvent>,
+}
+
+impl From<&Day> for PyDay {
+    fn from(day: &Day) -> Self {
+        PyDay {
+            date: day.date,
+            phase_name: phase_key(day.phase_name),
+            illumination: day.illumination,
+            age: day.age,
+            zodiac: zodiac_key(day.zodiac),
+            events: day.events.iter().map(PyPhaseEvent::from).collect(),
+        }
+    }
+}
+
+// Supermoons and blue moons are lists of (phase, timestamp, extra), with the
+// distance in km and "monthly" or "seasonal" as extra.
+#[pyclass(name = "Year", module = "moon_phase", frozen, get_all)]
+pub struct PyYear {
+    events: Vec<PyPhaseEvent>,
+    supermoons: Vec<(&'static str, f64, f64)>,
+    blue_moons: Vec<(&'static str, f64, &'static str)>,
+}
+
+// New, full and quarter moons from `start` up to `end`.
+#[pyfunction]
+fn events(start: &Bound<'_, PyAny>, end: &Bound<'_, PyAny>) -> PyResult<Vec<PyPhaseEvent>> {
+    Ok(almanac::events(unix_seconds(start)?, unix_seconds(end)?).iter().map(PyPhaseEvent::from).collect())
+}
+
+#[cfg(feature="chrono")]
+fn offset(utc_offset: i32) -> PyResult<chrono::FixedOffset> {
+    chrono::FixedOffset::east_opt(utc_offset).ok_or_else(|| PyValueError::new_err("UTC offset out of range"))
+}
+
+// Every day of `month` (1 - 12) in a time zone `utc_offset` seconds ahead of
+// UTC.
+#[pyfunction]
+#[pyo3(signature = (year, month, utc_offset = 0))]
+fn month(year: i32, month: u32, utc_offset: i32) -> PyResult<Vec<PyDay>> {
+    if !(1..=12).contains(&month) {
+        return Err(PyValueError::new_err("month must be 1 - 12"));
+    }
+    #[cfg(feature="chrono")]
+    let days = almanac::month(year, month, &offset(utc_offset)?);
+    #[cfg(not(feature="chrono"))]
+    let days = almanac::month(year, month, utc_offset);
+    Ok(days.iter().map(PyDay::from).collect())
+}
+
+// The phase events, supermoons and blue moons of `year`.
+#[pyfunction]
+#[pyo3(signature = (year, utc_offset = 0))]
+fn year(year: i32, utc_offset: i32) -> PyResult<PyYear> {
+    #[cfg(feature="chrono")]
+    let year = almanac::year(year, &offset(utc_offset)?);
+    #[cfg(not(feature="chrono"))]
+    let year = almanac::year(year, utc_offset);
+    Ok(PyYear {
+        events: year.events.iter().map(PyPhaseEvent::from).collect(),
+        supermoons: year
+            .supermoons
+            .iter()
+            .map(|supermoon| (phase_key(supermoon.phase.into()), supermoon.secs, supermoon.distance))
+            .collect(),
+        blue_moons: year
+            .blue_moons
+            .iter()
+            .map(|blue_moon| {
+                let kind = match blue_moon.kind {
+                    BlueMoonKind::Monthly => "monthly",
+                    BlueMoonKind::Seasonal => "seasonal",
+                };
+                ("full", blue_moon.secs, kind)
+            })
+            .collect(),
+    })
+}
+
+#[pymodule]
+fn moon_phase(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyMoonPhase>()?;
+    module.add_class::<PyPhaseEvent>()?;
+    module.add_class::<PyDay>()?;
+    module.add_class::<PyYear>()?;
+    module.add_function(wrap_pyfunction!(events, module)?)?;
+    module.add_function(wrap_pyfunction!(self::month, module)?)?;
+    module.add_function(wrap_pyfunction!(self::year, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pyo3::types::PyDict;
+    use std::ffi::CString;
+
+    #[test]
+    fn module() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "moon_phase").unwrap();
+            moon_phase(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("moon_phase", module).unwrap();
+            let code = CString::new(
+                "from datetime import datetime, timezone\n\
+                 full = datetime(2022, 1, 17, 23, 48, tzinfo=timezone.utc)\n\
+                 moon = moon_phase.MoonPhase(full)\n\
+                 assert moon.phase_name == 'full', moon.phase_name\n\
+                 assert moon.timestamp == moon_phase.MoonPhase(1642463280).timestamp\n\
+                 assert moon.emoji == '🌕' and moon.illumination > 0.99\n\
+                 events = moon_phase.events(1640995200, 1643673600)\n\
+                 assert [event.phase for event in events][:2] == ['new', 'first_quarter'], events\n\
+                 days = moon_phase.month(2022, 1, 3600)\n\
+                 assert len(days) == 31 and days[0].date == (2022, 1, 1)\n\
+                 year = moon_phase.year(2023)\n\
+                 assert ('full', 'monthly') in [(moon[0], moon[2]) for moon in year.blue_moons], year.blue_moons\n\
+                 try:\n\
+                 \x20   moon_phase.MoonPhase(float('nan'))\n\
+                 \x20   raise AssertionError('no ValueError')\n\
+                 except ValueError:\n\
+                 \x20   pass\n",
+            )
+            .unwrap();
+            py.run(&code, Some(&globals), None).unwrap();
+        });
+    }
+}
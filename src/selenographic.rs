@@ -0,0 +1,214 @@
+// Where the sun stands over the moon, and the earth (the libration), after
+// Meeus, "Astronomical Algorithms" chapter 53. The colongitude tells where
+// the terminator lies: the sun rises at selenographic longitude L (east
+// positive) when the colongitude is 360 - L and sets when it is 180 - L. It
+// is about 0 at first quarter, 90 at full moon, 180 at last quarter and 270
+// at new moon, and grows by about 12.2 degrees a day.
+
+use crate::elements::MOON_SYNODIC_PERIOD;
+use crate::ephemeris::{AU, EARTH_RADIUS};
+use crate::events::zero_crossings;
+use crate::observer::Observer;
+use crate::sun::SunPosition;
+use crate::MoonPhase;
+
+const J2000: f64 = 2451545.0;
+// Inclination of the mean lunar equator to the ecliptic.
+const INCLINATION: f64 = 1.54242;
+// The colongitude moves about 3 degrees in that time.
+const STEP_SECS: f64 = 6. * 3600.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SelenographicSun {
+    pub longitude: f64,    // Selenographic longitude of the subsolar point, degrees east, 0 - 360
+    pub latitude: f64,     // Selenographic latitude of the subsolar point in degrees
+    pub colongitude: f64,  // 90 - longitude, 0 - 360
+}
+
+impl MoonPhase {
+    pub fn selenographic_sun(&self) -> SelenographicSun {
+        let sun = SunPosition::new(self.j_date);
+        // Heliocentric position of the moon, (53.4).
+        let distance_ratio = self.distance * EARTH_RADIUS / (sun.distance * AU);
+        let (moon_longitude, moon_latitude) = (self.longitude.to_radians(), self.latitude.to_radians());
+        let longitude = sun.longitude
+            + 180.
+            + (distance_ratio * moon_latitude.cos() * (sun.longitude.to_radians() - moon_longitude).sin()).to_degrees();
+        let latitude = distance_ratio * moon_latitude;
+        let (sub_longitude, sub_latitude) = sub_point(self.j_date, longitude, latitude.to_degrees());
+        SelenographicSun {
+            longitude: sub_longitude.rem_euclid(360.),
+            latitude: sub_latitude,
+            colongitude: (90. - sub_longitude).rem_euclid(360.),
+        }
+    }
+
+    // See the top of this file.
+    pub fn colongitude(&self) -> f64 {
+        self.selenographic_sun().colongitude
+    }
+}
+
+impl MoonPhase {
+    // Optical libration, the selenographic longitude and latitude of the
+    // center of the disk seen from the center of the earth. Positive
+    // longitudes tip the eastern (Mare Crisium) limb, positive latitudes the
+    // northern limb towards us. Up to about 8 and 7 degrees.
+    pub fn libration(&self) -> Libration {
+        let (longitude, latitude) = sub_point(self.j_date, self.longitude, self.latitude);
+        Libration {
+            longitude: (longitude + 180.).rem_euclid(360.) - 180.,
+            latitude,
+        }
+    }
+}
+
+impl Observer {
+    // `MoonPhase::libration` seen from the observer, which differs by up to
+    // a degree with the parallax.
+    pub fn libration(&self, moon_phase: &MoonPhase) -> Libration {
+        self.topocentric(moon_phase).libration()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Libration {
+    pub longitude: f64,  // Degrees, -180 - 180
+    pub latitude: f64,   // Degrees
+}
+
+// Selenographic longitude (unwrapped) and latitude in degrees of the point
+// the direction at ecliptic `longitude` and `latitude` (degrees) passes
+// through, after (53.1).
+fn sub_point(j_date: f64, longitude: f64, latitude: f64) -> (f64, f64) {
+    let t = (j_date - J2000) / 36525.;
+    // Argument of latitude of the moon and the longitude of the mean
+    // ascending node, (47.5) and (47.7).
+    let argument = 93.2720950 + 483202.0175233 * t - 0.0036539 * t * t;
+    let node = 125.0445479 - 1934.1362891 * t + 0.0020754 * t * t;
+
+    let w = (longitude - node).to_radians();
+    let latitude = latitude.to_radians();
+    let inclination = INCLINATION.to_radians();
+    let a = (w.sin() * latitude.cos() * inclination.cos() - latitude.sin() * inclination.sin())
+        .atan2(w.cos() * latitude.cos());
+    let sub_latitude = (-w.sin() * latitude.cos() * inclination.sin() - latitude.sin() * inclination.cos()).asin();
+    (a.to_degrees() - argument, sub_latitude.to_degrees())
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Terminator {
+    Morning,  // Sunrise, the feature comes out of the dark
+    Evening,  // Sunset
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TerminatorPass {
+    pub terminator: Terminator,
+    pub secs: f64,   // Unix seconds the terminator crosses the feature
+    pub start: f64,  // Unix seconds it comes within the tolerance
+    pub end: f64,    // Unix seconds it is further than the tolerance again
+}
+
+// When the terminator passes a feature at selenographic `longitude` (degrees,
+// east positive) in the lunation from `from` (unix seconds), in order. The
+// shadows are longest, and the relief best, while the sun stands within
+// `tolerance` degrees of the feature's horizon, a few degrees as a rule.
+pub fn terminator_passes(longitude: f64, from: f64, tolerance: f64) -> Vec<TerminatorPass> {
+    let until = from + MOON_SYNODIC_PERIOD * 86400.;
+    let colongitude = |secs| MoonPhase::from_secs_float(secs).colongitude();
+    let crossing = |target: f64, from: f64, until: f64| {
+        zero_crossings(|secs| colongitude(secs) - target, from, until, STEP_SECS)
+            .first()
+            .copied()
+    };
+    // The tolerance plus a margin, at the slowest the colongitude moves.
+    let search = (tolerance.abs() / 11. + 0.5) * 86400.;
+
+    let mut passes = Vec::new();
+    for (terminator, target) in [(Terminator::Morning, 360. - longitude), (Terminator::Evening, 180. - longitude)] {
+        for secs in zero_crossings(|secs| colongitude(secs) - target, from, until, STEP_SECS) {
+            passes.push(TerminatorPass {
+                terminator,
+                secs,
+                start: crossing(target - tolerance.abs(), secs - search, secs).unwrap_or(secs),
+                end: crossing(target + tolerance.abs(), secs, secs + search).unwrap_or(secs),
+            });
+        }
+    }
+    passes.sort_by(|a, b| a.secs.total_cmp(&b.secs));
+    passes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meeus_example() {
+        // Example 53.a, 1992 April 12 at 0h TD: l0 = 67.89, b0 = 1.46,
+        // c0 = 22.11.
+        let sun = MoonPhase::from_julian_date(2448724.5).selenographic_sun();
+        assert!((sun.longitude - 67.89).abs() < 0.5, "Failed for {:?}", sun);
+        assert!((sun.latitude - 1.46).abs() < 0.1, "Failed for {:?}", sun);
+        assert!((sun.colongitude - 22.11).abs() < 0.5, "Failed for {:?}", sun);
+    }
+
+    #[test]
+    fn phases() {
+        // First quarter on 2022-01-09T18:11:00+00:00, full moon on the 17th,
+        // last quarter on the 25th, give or take the libration in longitude.
+        for (secs, expected) in [(1641751860, 0.), (1642463280, 90.), (1643145180, 180.)] {
+            let colongitude = MoonPhase::from_secs(secs).colongitude();
+            let diff = (colongitude - expected + 180.).rem_euclid(360.) - 180.;
+            assert!(diff.abs() < 10., "Failed for {}", colongitude);
+        }
+        // About 12.2 degrees a day.
+        let rate = MoonPhase::from_secs(1642463280 + 86400).colongitude() - MoonPhase::from_secs(1642463280).colongitude();
+        assert!((rate - 12.2).abs() < 0.3, "Failed for {}", rate);
+    }
+
+    #[test]
+    fn libration() {
+        // Example 53.a: l = -1.206, b = 4.194.
+        let libration = MoonPhase::from_julian_date(2448724.5).libration();
+        assert!((libration.longitude + 1.206).abs() < 0.3, "Failed for {:?}", libration);
+        assert!((libration.latitude - 4.194).abs() < 0.1, "Failed for {:?}", libration);
+        // Never more than about 8 and 7 degrees over a year.
+        for day in 0..365 {
+            let libration = MoonPhase::from_secs(1640995200 + day * 86400).libration();
+            assert!(libration.longitude.abs() < 8.5, "Failed for {:?}", libration);
+            assert!(libration.latitude.abs() < 7.1, "Failed for {:?}", libration);
+        }
+        // Seen from Greenwich with the moon high in the south the parallax
+        // tips the northern limb towards the observer, by up to a degree.
+        let moon_phase = MoonPhase::from_secs(1642464000);
+        let topocentric = Observer::new(51.48, 0.).libration(&moon_phase);
+        let diff = topocentric.latitude - moon_phase.libration().latitude;
+        assert!((0.2..1.).contains(&diff), "Failed for {}", diff);
+    }
+
+    #[test]
+    fn terminator() {
+        // Copernicus at 20 degrees west sees sunrise a day or so after the
+        // first quarter, and sunset a day or so after the last.
+        let passes = terminator_passes(-20., 1641340800., 5.);
+        assert_eq!(passes.len(), 2, "Failed for {:?}", passes);
+        let (morning, evening) = (passes[0], passes[1]);
+        assert_eq!(morning.terminator, Terminator::Morning);
+        assert_eq!(evening.terminator, Terminator::Evening);
+        let days_after_quarter = (morning.secs - 1641751860.) / 86400.;
+        assert!((0.5..2.5).contains(&days_after_quarter), "Failed for {}", days_after_quarter);
+        let days_after_quarter = (evening.secs - 1643145180.) / 86400.;
+        assert!((0.5..2.5).contains(&days_after_quarter), "Failed for {}", days_after_quarter);
+
+        let colongitude = MoonPhase::from_secs_float(morning.secs).colongitude();
+        assert!((colongitude - 20.).abs() < 0.01, "Failed for {}", colongitude);
+        // 5 degrees on either side are about 10 hours.
+        for pass in &passes {
+            assert!(pass.start < pass.secs && pass.secs < pass.end);
+            let hours = (pass.end - pass.start) / 3600.;
+            assert!((18.0..22.).contains(&hours), "Failed for {}", hours);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Single precision variant of `MoonPhase`, for memory constrained targets or
+// when the results are fed into f32 pipelines (e.g. shaders) anyway.
+
+use crate::{julian_date_from_seconds, phase_name, Elements, Phase, Zodiac};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonPhaseF32 {
+    pub j_date: f64,
+    pub phase: f32,                // 0 - 1, 0.5 = full
+    pub age: f32,                  // Age in days of current cycle
+    #[deprecated(note = "wrong for the default model, use `illumination` instead")]
+    pub fraction: f32,             // Kept for compatibility, see `illumination`
+    pub illumination: f32,         // Fraction of illuminated disk, 0 - 1
+    pub distance: f32,             // Moon distance in earth radii
+    pub latitude: f32,             // Moon ecliptic latitude
+    pub longitude: f32,            // Moon ecliptic longitude
+    pub phase_name: Phase,          // New, Full, etc.
+    pub zodiac_name: Zodiac,        // Constellation
+}
+
+impl MoonPhaseF32 {
+    pub fn from_secs(secs: i64) -> Self {
+        Self::from_secs_float(secs as f64)
+    }
+
+    #[allow(deprecated)]
+    pub fn from_secs_float(secs: f64) -> Self {
+        let j_date = julian_date_from_seconds(secs);
+        let Elements {
+            phase,
+            age,
+            fraction,
+            illumination,
+            distance,
+            latitude,
+            longitude,
+        } = Elements::<f32>::new(j_date);
+        MoonPhaseF32 {
+            j_date,
+            phase,
+            age,
+            fraction,
+            illumination,
+            distance,
+            latitude,
+            longitude,
+            phase_name: phase_name(phase as f64),
+            zodiac_name: Zodiac::from_long(longitude as f64),
+        }
+    }
+
+    // Illuminated part of the disk in percent, 0 - 100.
+    pub fn percent_illuminated(&self) -> f32 {
+        self.illumination * 100.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MoonPhase;
+
+    #[test]
+    fn matches_double() {
+        for secs in (900_000_000..1_700_000_000).step_by(86_400 * 7) {
+            let single = MoonPhaseF32::from_secs(secs);
+            let double = MoonPhase::from_secs(secs);
+            assert!((single.phase as f64 - double.phase).abs() < 1e-5, "Failed for {}", secs);
+            assert!((single.distance as f64 - double.distance).abs() < 1e-3, "Failed for {}", secs);
+            assert!((single.latitude as f64 - double.latitude).abs() < 1e-3, "Failed for {}", secs);
+            assert_eq!(single.phase_name, double.phase_name, "Failed for {}", secs);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+// The phase events as an async stream for tokio, so services can react to
+// new and full moons without polling:
+//
+//     let mut events = phase_event_stream(SystemClock.now());
+//     while let Some(event) = events.next().await {
+//         println!("{:?} moon", event.phase);
+//     }
+//
+// Each event is yielded once the wall clock reaches it, events before the
+// current time right away. The waiting is done with tokio's timer, which
+// doesn't follow later adjustments of the system clock.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::time::{sleep, Sleep};
+
+use crate::almanac::{events, PhaseEvent};
+use crate::clock::{Clock, SystemClock};
+use crate::VALID_UNTIL;
+
+// Events are searched this far ahead at a time, enough for at least one.
+const SEARCH_SECS: f64 = 30. * 86400.;
+
+#[derive(Debug)]
+pub struct PhaseEventStream {
+    pending: VecDeque<PhaseEvent>,
+    searched_until: f64,              // Unix seconds
+    sleep: Option<Pin<Box<Sleep>>>,  // Until the first pending event
+}
+
+// The new, full and quarter moons from `start` (unix seconds) on, up to
+// `VALID_UNTIL`.
+pub fn phase_event_stream(start: f64) -> PhaseEventStream {
+    PhaseEventStream {
+        pending: VecDeque::new(),
+        searched_until: start,
+        sleep: None,
+    }
+}
+
+impl Stream for PhaseEventStream {
+    type Item = PhaseEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<PhaseEvent>> {
+        while self.pending.is_empty() {
+            if self.searched_until >= VALID_UNTIL {
+                return Poll::Ready(None);
+            }
+            let from = self.searched_until;
+            let until = (from + SEARCH_SECS).min(VALID_UNTIL);
+            self.pending.extend(events(from, until));
+            self.searched_until = until;
+        }
+
+        if self.sleep.is_none() {
+            let delay = self.pending[0].secs - SystemClock.now();
+            if delay <= 0. {
+                return Poll::Ready(self.pending.pop_front());
+            }
+            self.sleep = Some(Box::pin(sleep(Duration::from_secs_f64(delay))));
+        }
+        match self.sleep.as_mut().map(|sleep| sleep.as_mut().poll(cx)) {
+            Some(Poll::Ready(())) => {
+                self.sleep = None;
+                Poll::Ready(self.pending.pop_front())
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::naming::PrimaryPhase;
+    use std::future::poll_fn;
+
+    async fn next(stream: &mut PhaseEventStream) -> Option<PhaseEvent> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn past_events() {
+        // January 2022, long past, comes without waiting.
+        let mut stream = phase_event_stream(1640995200.);
+        let mut phases = Vec::new();
+        for _ in 0..5 {
+            phases.push(next(&mut stream).await.unwrap().phase);
+        }
+        assert_eq!(phases, [
+            PrimaryPhase::New,
+            PrimaryPhase::FirstQuarter,
+            PrimaryPhase::Full,
+            PrimaryPhase::LastQuarter,
+            PrimaryPhase::New,
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_events() {
+        let now = SystemClock.now();
+        let mut stream = phase_event_stream(now);
+        let start = tokio::time::Instant::now();
+        let event = next(&mut stream).await.unwrap();
+        assert!(event.secs >= now);
+        // The paused clock jumps ahead to the event.
+        let waited = start.elapsed().as_secs_f64();
+        assert!((waited - (event.secs - now)).abs() < 60., "Failed for {}", waited);
+    }
+
+    #[tokio::test]
+    async fn ends() {
+        let mut stream = phase_event_stream(VALID_UNTIL);
+        assert_eq!(next(&mut stream).await, None);
+    }
+}
@@ -0,0 +1,45 @@
+// Low precision solar coordinates, after chapter 25 of Jean Meeus,
+// "Astronomical Algorithms". Good to about 0.01 degrees.
+
+const J2000: f64 = 2451545.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SunPosition {
+    pub longitude: f64,  // Geometric ecliptic longitude in degrees, mean equinox of date
+    pub anomaly: f64,    // Mean anomaly in degrees
+    pub distance: f64,   // Distance in astronomical units
+}
+
+impl SunPosition {
+    pub fn new(j_date: f64) -> Self {
+        let t = (j_date - J2000) / 36525.;
+        let mean_longitude = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+        let anomaly = 357.52911 + 35999.05029 * t - 0.0001537 * t * t;
+        let eccentricity = 0.016708634 - 0.000042037 * t - 0.0000001267 * t * t;
+        let m = anomaly.to_radians();
+        let center = (1.914602 - 0.004817 * t - 0.000014 * t * t) * m.sin()
+            + (0.019993 - 0.000101 * t) * (2. * m).sin()
+            + 0.000289 * (3. * m).sin();
+        let true_anomaly = (anomaly + center).to_radians();
+        let distance = 1.000001018 * (1. - eccentricity * eccentricity)
+            / (1. + eccentricity * true_anomaly.cos());
+        SunPosition {
+            longitude: (mean_longitude + center).rem_euclid(360.),
+            anomaly: anomaly.rem_euclid(360.),
+            distance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meeus_example() {
+        // Example 25.a: 1992 October 13.0 TD
+        let sun = SunPosition::new(2448908.5);
+        assert!((sun.longitude - 199.90988).abs() < 1e-4, "{:?}", sun);
+        assert!((sun.distance - 0.99766).abs() < 1e-5, "{:?}", sun);
+    }
+}
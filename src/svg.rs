@@ -0,0 +1,255 @@
+// Printable month calendars as SVG: a grid of the days of the month, each
+// with the moon as seen from the northern hemisphere and its illumination at
+// local noon. The days come from `almanac::month`.
+
+use std::fmt::Write;
+
+use crate::almanac::Day;
+use crate::calendar::days_from_civil;
+use crate::MOON_SYNODIC_PERIOD;
+
+const CELL_WIDTH: f64 = 100.;
+const CELL_HEIGHT: f64 = 90.;
+const TITLE_HEIGHT: f64 = 50.;
+const HEADER_HEIGHT: f64 = 25.;
+const MOON_RADIUS: f64 = 22.;
+
+// Names for the title and column headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    pub months: [&'static str; 12],   // January first
+    pub weekdays: [&'static str; 7],  // Monday first
+    pub first_weekday: usize,         // Column of the first day, 0 = Monday
+}
+
+impl Locale {
+    pub const ENGLISH: Locale = Locale {
+        months: [
+            "January", "February", "March", "April", "May", "June",
+            "July", "August", "September", "October", "November", "December",
+        ],
+        weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        first_weekday: 6,
+    };
+
+    pub const GERMAN: Locale = Locale {
+        months: [
+            "Januar", "Februar", "März", "April", "Mai", "Juni",
+            "Juli", "August", "September", "Oktober", "November", "Dezember",
+        ],
+        weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        first_weekday: 0,
+    };
+
+    pub const FRENCH: Locale = Locale {
+        months: [
+            "janvier", "février", "mars", "avril", "mai", "juin",
+            "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+        ],
+        weekdays: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        first_weekday: 0,
+    };
+
+    pub const SPANISH: Locale = Locale {
+        months: [
+            "enero", "febrero", "marzo", "abril", "mayo", "junio",
+            "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+        ],
+        weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        first_weekday: 0,
+    };
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::ENGLISH
+    }
+}
+
+// Any SVG color, e.g. "#1b1f3b" or "white".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    pub background: String,
+    pub text: String,
+    pub grid: String,
+    pub lit: String,   // Illuminated part of the moon
+    pub dark: String,  // Unlit part of the moon
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Colors {
+            background: "white".to_string(),
+            text: "black".to_string(),
+            grid: "#bbbbbb".to_string(),
+            lit: "#f4e9b8".to_string(),
+            dark: "#2b2b3a".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CalendarStyle {
+    pub colors: Colors,
+    pub locale: Locale,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Path of the lit part of a moon centered on (x, y). The terminator is an
+// ellipse, which bulges to the dark side for a crescent and to the lit side
+// for a gibbous moon.
+fn lit_path(x: f64, y: f64, illumination: f64, waxing: bool) -> String {
+    let illumination = illumination.clamp(0., 1.);
+    let terminator = MOON_RADIUS * (1. - 2. * illumination).abs();
+    let limb_sweep = waxing as u8;
+    let terminator_sweep = (waxing == (illumination > 0.5)) as u8;
+    format!(
+        "M{x:.2},{top:.2}A{r},{r} 0 0 {limb_sweep} {x:.2},{bottom:.2}A{t:.2},{r} 0 0 {terminator_sweep} {x:.2},{top:.2}Z",
+        x = x,
+        top = y - MOON_RADIUS,
+        bottom = y + MOON_RADIUS,
+        r = MOON_RADIUS,
+        t = terminator,
+        limb_sweep = limb_sweep,
+        terminator_sweep = terminator_sweep,
+    )
+}
+
+// A calendar of the month of `days`, which should be all days of one month
+// as returned by `almanac::month`.
+pub fn month_svg(days: &[Day], style: &CalendarStyle) -> String {
+    let colors = &style.colors;
+    let (year, month) = days.first().map_or((1970, 1), |day| (day.date.0, day.date.1));
+    // 1970-01-01 was a Thursday.
+    let weekday = (days_from_civil(year as i64, month, 1) + 3).rem_euclid(7) as usize;
+    let offset = (weekday + 7 - style.locale.first_weekday % 7) % 7;
+    let rows = (offset + days.len()).div_ceil(7).max(1);
+    let width = 7. * CELL_WIDTH;
+    let height = TITLE_HEIGHT + HEADER_HEIGHT + rows as f64 * CELL_HEIGHT;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, width, height, escape(&colors.background)).unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="26" text-anchor="middle" fill="{}">{} {}</text>"#,
+        width / 2.,
+        TITLE_HEIGHT - 15.,
+        escape(&colors.text),
+        escape(style.locale.months[(month as usize + 11) % 12]),
+        year
+    )
+    .unwrap();
+    for column in 0..7 {
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="14" text-anchor="middle" fill="{}">{}</text>"#,
+            (column as f64 + 0.5) * CELL_WIDTH,
+            TITLE_HEIGHT + HEADER_HEIGHT - 8.,
+            escape(&colors.text),
+            escape(style.locale.weekdays[(column + style.locale.first_weekday) % 7])
+        )
+        .unwrap();
+    }
+    for (i, day) in days.iter().enumerate() {
+        let cell = offset + i;
+        let left = (cell % 7) as f64 * CELL_WIDTH;
+        let top = TITLE_HEIGHT + HEADER_HEIGHT + (cell / 7) as f64 * CELL_HEIGHT;
+        let (x, y) = (left + CELL_WIDTH / 2., top + CELL_HEIGHT / 2. - 2.);
+        writeln!(svg, "<g>").unwrap();
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}"/>"#,
+            left,
+            top,
+            CELL_WIDTH,
+            CELL_HEIGHT,
+            escape(&colors.grid)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="14" fill="{}">{}</text>"#,
+            left + 6.,
+            top + 18.,
+            escape(&colors.text),
+            day.date.2
+        )
+        .unwrap();
+        writeln!(svg, r#"<circle cx="{}" cy="{:.2}" r="{}" fill="{}"/>"#, x, y, MOON_RADIUS, escape(&colors.dark)).unwrap();
+        let waxing = day.age < MOON_SYNODIC_PERIOD / 2.;
+        writeln!(svg, r#"<path d="{}" fill="{}"/>"#, lit_path(x, y, day.illumination, waxing), escape(&colors.lit)).unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="12" text-anchor="middle" fill="{}">{:.0}%</text>"#,
+            x,
+            top + CELL_HEIGHT - 6.,
+            escape(&colors.text),
+            day.illumination * 100.
+        )
+        .unwrap();
+        writeln!(svg, "</g>").unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::almanac::month;
+
+    #[cfg(feature="chrono")]
+    fn january_2022() -> Vec<Day> {
+        month(2022, 1, &chrono::Utc)
+    }
+
+    #[cfg(not(feature="chrono"))]
+    fn january_2022() -> Vec<Day> {
+        month(2022, 1, 0)
+    }
+
+    #[test]
+    fn calendar() {
+        let days = january_2022();
+        let svg = month_svg(&days, &CalendarStyle::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"700\" height=\"615\""), "Failed for {}", svg);
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">January 2022</text>"));
+        assert_eq!(svg.matches("<path ").count(), 31);
+        // 2022-01-01 was a Saturday, the last column with Sunday first.
+        assert!(svg.contains(r#"<rect x="600" y="75" width="100" height="90""#));
+        assert!(svg.contains(">100%</text>"));
+
+        let style = CalendarStyle {
+            colors: Colors {
+                lit: "gold".to_string(),
+                ..Colors::default()
+            },
+            locale: Locale::GERMAN,
+        };
+        let svg = month_svg(&days, &style);
+        assert!(svg.contains(">Januar 2022</text>"));
+        assert!(svg.contains(r#"<rect x="500" y="75" width="100" height="90""#));
+        assert!(svg.contains(r#"fill="gold""#));
+    }
+
+    #[test]
+    fn phases() {
+        // Full and new moon are a circle and nothing.
+        assert_eq!(lit_path(0., 0., 1., true), "M0.00,-22.00A22,22 0 0 1 0.00,22.00A22.00,22 0 0 1 0.00,-22.00Z");
+        assert_eq!(lit_path(0., 0., 0., true), "M0.00,-22.00A22,22 0 0 1 0.00,22.00A22.00,22 0 0 0 0.00,-22.00Z");
+        // The waning quarter is lit on the left.
+        assert_eq!(lit_path(0., 0., 0.5, false), "M0.00,-22.00A22,22 0 0 0 0.00,22.00A0.00,22 0 0 1 0.00,-22.00Z");
+        assert_eq!(escape("<a & b>"), "&lt;a &amp; b&gt;");
+    }
+}
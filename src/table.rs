@@ -0,0 +1,87 @@
+// Daily values precomputed by the build script, for devices where even
+// evaluating the trig functions is too heavy. Values in between are linearly
+// interpolated, which is accurate to a few thousandths for the phase and
+// fraction and a few tenths of a degree for the position. The deprecated
+// `fraction` has a column of its own, so no trig is evaluated at all.
+
+use crate::single::MoonPhaseF32;
+use crate::{julian_date_from_seconds, phase_name, Zodiac, MOON_SYNODIC_PERIOD};
+
+include!(concat!(env!("OUT_DIR"), "/table.rs"));
+
+// Returns None if `secs` lies outside of the years the table was built for.
+#[allow(deprecated)]
+pub fn lookup(secs: i64) -> Option<MoonPhaseF32> {
+    let elapsed = secs.checked_sub(TABLE_START_SECS)?;
+    let day = elapsed.div_euclid(86400);
+    if day < 0 || day as usize + 1 >= TABLE.len() {
+        return None;
+    }
+    let today = &TABLE[day as usize];
+    let tomorrow = &TABLE[day as usize + 1];
+    let t = elapsed.rem_euclid(86400) as f32 / 86400.;
+    let interpolate = |i: usize, wrap: f32| {
+        let mut next = tomorrow[i];
+        if next < today[i] && wrap > 0. {
+            next += wrap;
+        }
+        let value = today[i] + (next - today[i]) * t;
+        if wrap > 0. && value >= wrap {
+            value - wrap
+        } else {
+            value
+        }
+    };
+
+    let phase = interpolate(0, 1.);
+    let longitude = interpolate(4, 360.);
+    Some(MoonPhaseF32 {
+        j_date: julian_date_from_seconds(secs as f64),
+        phase,
+        age: phase * MOON_SYNODIC_PERIOD as f32,
+        fraction: interpolate(5, 0.),
+        illumination: interpolate(1, 0.),
+        distance: interpolate(2, 0.),
+        latitude: interpolate(3, 0.),
+        longitude,
+        phase_name: phase_name(phase as f64),
+        zodiac_name: Zodiac::from_long(longitude as f64),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MoonPhase;
+
+    #[test]
+    fn matches_calculation() {
+        // Every 5 hours over the table, or its first ten years.
+        let end = TABLE_START_SECS + (TABLE.len() as i64 - 1).min(3653) * 86400;
+        for secs in (TABLE_START_SECS..end).step_by(18_000) {
+            let table = lookup(secs).unwrap();
+            let exact = MoonPhase::from_secs(secs);
+            let diff = (table.phase as f64 - exact.phase).rem_euclid(1.);
+            assert!(diff.min(1. - diff) < 1e-3, "Failed for {}", secs);
+            assert!((table.illumination as f64 - exact.illumination).abs() < 0.02, "Failed for {}", secs);
+            #[allow(deprecated)]
+            let fraction_diff = (table.fraction as f64 - exact.fraction).abs();
+            assert!(fraction_diff < 0.01, "Failed for {}", secs);
+            assert!((table.distance as f64 - exact.distance).abs() < 0.1, "Failed for {}", secs);
+            assert!((table.latitude as f64 - exact.latitude).abs() < 0.1, "Failed for {}", secs);
+            let diff = (table.longitude as f64 - exact.longitude).rem_euclid(360.);
+            assert!(diff.min(360. - diff) < 0.5, "Failed for {}", secs);
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let end = TABLE_START_SECS + (TABLE.len() as i64 - 1) * 86400;
+        assert!(lookup(TABLE_START_SECS - 1).is_none());
+        assert!(lookup(TABLE_START_SECS).is_some());
+        assert!(lookup(end - 1).is_some());
+        assert!(lookup(end).is_none());
+        assert!(lookup(i64::MIN).is_none());
+        assert!(lookup(i64::MAX).is_none());
+    }
+}
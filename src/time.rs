@@ -0,0 +1,230 @@
+// Time scales. The lunar theories are expressed in Terrestrial Time (TT),
+// while timestamps are usually UTC. The difference, ΔT, was about a minute in
+// 2000 but grows to hours for ancient dates.
+
+use std::num::ParseIntError;
+
+use crate::elements::ut_to_tt;
+use crate::{julian_date_from_seconds, MoonPhase};
+
+pub use crate::elements::delta_t;
+
+const J2000: f64 = 2451545.0;
+const TT_MINUS_TAI: f64 = 32.184;
+// Seconds between the NTP epoch (1900) used by IERS and the unix epoch.
+const NTP_UNIX_OFFSET: i64 = 2_208_988_800;
+
+// Unix time at which TAI - UTC changed, and the new value in seconds.
+const LEAP_SECONDS: [(i64, i32); 28] = [
+    (63072000, 10), // 1972-01-01
+    (78796800, 11), // 1972-07-01
+    (94694400, 12), // 1973-01-01
+    (126230400, 13), // 1974-01-01
+    (157766400, 14), // 1975-01-01
+    (189302400, 15), // 1976-01-01
+    (220924800, 16), // 1977-01-01
+    (252460800, 17), // 1978-01-01
+    (283996800, 18), // 1979-01-01
+    (315532800, 19), // 1980-01-01
+    (362793600, 20), // 1981-07-01
+    (394329600, 21), // 1982-07-01
+    (425865600, 22), // 1983-07-01
+    (489024000, 23), // 1985-07-01
+    (567993600, 24), // 1988-01-01
+    (631152000, 25), // 1990-01-01
+    (662688000, 26), // 1991-01-01
+    (709948800, 27), // 1992-07-01
+    (741484800, 28), // 1993-07-01
+    (773020800, 29), // 1994-07-01
+    (820454400, 30), // 1996-01-01
+    (867715200, 31), // 1997-07-01
+    (915148800, 32), // 1999-01-01
+    (1136073600, 33), // 2006-01-01
+    (1230768000, 34), // 2009-01-01
+    (1341100800, 35), // 2012-07-01
+    (1435708800, 36), // 2015-07-01
+    (1483228800, 37), // 2017-01-01
+];
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum TimeScale {
+    // Civil time, converted to TT using `delta_t`. This is what `from_secs`
+    // and the other constructors taking unix seconds or a Julian date do.
+    #[default]
+    Utc,
+    // Terrestrial Time, used as is.
+    Tt,
+    // Barycentric Dynamical Time, differs from TT by less than 2 ms.
+    Tdb,
+}
+
+impl TimeScale {
+    // Converts a Julian date in this time scale to a Julian date in TT.
+    pub fn to_tt(self, j_date: f64) -> f64 {
+        match self {
+            TimeScale::Utc => ut_to_tt(j_date),
+            TimeScale::Tt => j_date,
+            TimeScale::Tdb => {
+                let g = (357.53 + 0.98560028 * (j_date - J2000)).to_radians();
+                j_date - (0.001657 * g.sin() + 0.000014 * (2. * g).sin()) / 86400.
+            }
+        }
+    }
+}
+
+// Table of TAI - UTC, for exact conversions of UTC timestamps since 1972.
+// The built in table is current as of 2017; later leap seconds can be added
+// with `insert` or by parsing IERS' leap-seconds.list.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LeapSeconds {
+    entries: Vec<(i64, i32)>,
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl LeapSeconds {
+    pub fn builtin() -> Self {
+        LeapSeconds {
+            entries: LEAP_SECONDS.to_vec(),
+        }
+    }
+
+    // `secs` is the unix time from which on TAI - UTC is `tai_minus_utc`.
+    pub fn insert(&mut self, secs: i64, tai_minus_utc: i32) {
+        match self.entries.binary_search_by_key(&secs, |(start, _)| *start) {
+            Ok(i) => self.entries[i].1 = tai_minus_utc,
+            Err(i) => self.entries.insert(i, (secs, tai_minus_utc)),
+        }
+    }
+
+    // Parses the format of https://hpiers.obspm.fr/iers/bul/bulc/ntp/leap-seconds.list
+    // (NTP timestamp and TAI - UTC per line, `#` starts a comment).
+    pub fn parse_iers(list: &str) -> Result<Self, ParseIntError> {
+        let mut leap_seconds = LeapSeconds { entries: Vec::new() };
+        for line in list.lines() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut fields = line.split_whitespace();
+            if let (Some(ntp), Some(offset)) = (fields.next(), fields.next()) {
+                leap_seconds.insert(ntp.parse::<i64>()? - NTP_UNIX_OFFSET, offset.parse()?);
+            }
+        }
+        Ok(leap_seconds)
+    }
+
+    // TAI - UTC in seconds, None before the first entry (1972).
+    pub fn tai_minus_utc(&self, secs: i64) -> Option<i32> {
+        let i = self.entries.partition_point(|(start, _)| *start <= secs);
+        i.checked_sub(1).map(|i| self.entries[i].1)
+    }
+
+    // Converts a unix timestamp to seconds since the unix epoch in TT.
+    pub fn to_tt(&self, secs: f64) -> Option<f64> {
+        let tai_minus_utc = self.tai_minus_utc(secs.floor() as i64)?;
+        Some(secs + tai_minus_utc as f64 + TT_MINUS_TAI)
+    }
+}
+
+impl MoonPhase {
+    // Like `with_time_scale` for UTC, but exact since 1972 thanks to the leap
+    // second table. Earlier dates fall back to `delta_t`.
+    pub fn with_leap_seconds(secs: i64, leap_seconds: &LeapSeconds) -> Self {
+        let secs = secs as f64;
+        match leap_seconds.to_tt(secs) {
+            Some(tt) => Self::_new_tt(julian_date_from_seconds(secs), julian_date_from_seconds(tt)),
+            None => Self::from_secs_float_with_time_scale(secs, TimeScale::Utc),
+        }
+    }
+
+    // Like `from_secs`, but `secs` is read in the given time scale and
+    // converted to TT before evaluating the model. `j_date` keeps the
+    // unconverted date.
+    pub fn with_time_scale(secs: i64, time_scale: TimeScale) -> Self {
+        Self::from_secs_float_with_time_scale(secs as f64, time_scale)
+    }
+
+    pub fn from_secs_float_with_time_scale(secs: f64, time_scale: TimeScale) -> Self {
+        let j_date = julian_date_from_seconds(secs);
+        Self::_new_tt(j_date, time_scale.to_tt(j_date))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn delta_t_values() {
+        // Observed values, the polynomials are good to a second or two here.
+        for (year, expected) in &[(1900., -2.7), (1950., 29.1), (1990., 56.9), (2000., 63.8), (2005., 64.7)] {
+            assert!((delta_t(*year) - expected).abs() < 2., "Failed for {}", year);
+        }
+        // Continuous at the boundaries between the polynomials.
+        for year in &[1700., 1800., 1860., 1900., 1920., 1941., 1961., 1986., 2005., 2050.] {
+            assert!((delta_t(year - 1e-6) - delta_t(*year)).abs() < 1., "Failed for {}", year);
+        }
+    }
+
+    #[test]
+    fn leap_seconds() {
+        let leap_seconds = LeapSeconds::builtin();
+        assert_eq!(leap_seconds.tai_minus_utc(0), None);
+        assert_eq!(leap_seconds.tai_minus_utc(63072000), Some(10));
+        assert_eq!(leap_seconds.tai_minus_utc(1483228799), Some(36));
+        assert_eq!(leap_seconds.tai_minus_utc(1700000000), Some(37));
+        assert_eq!(leap_seconds.to_tt(1700000000.), Some(1700000069.184));
+
+        let list = "# File expires on 28 December 2025\n\
+                    2272060800\t10\t# 1 Jan 1972\n\
+                    3692217600\t37\t# 1 Jan 2017\n";
+        let parsed = LeapSeconds::parse_iers(list).unwrap();
+        assert_eq!(parsed.tai_minus_utc(1700000000), Some(37));
+        assert_eq!(parsed.tai_minus_utc(1000000000), Some(10));
+        assert!(LeapSeconds::parse_iers("abc 10").is_err());
+    }
+
+    #[test]
+    fn leap_seconds_close_to_delta_t() {
+        let secs = 1642291200; // 2022-01-16T00:00:00+00:00
+        let exact = MoonPhase::with_leap_seconds(secs, &LeapSeconds::default());
+        let approximate = MoonPhase::with_time_scale(secs, TimeScale::Utc);
+        assert_eq!(exact.j_date, approximate.j_date);
+        // The ΔT polynomial is a few seconds off by now.
+        assert!((exact.age - approximate.age).abs() * 86400. < 10.);
+        assert_eq!(
+            MoonPhase::with_leap_seconds(0, &LeapSeconds::default()),
+            MoonPhase::with_time_scale(0, TimeScale::Utc)
+        );
+    }
+
+    #[test]
+    fn utc_applies_delta_t() {
+        let secs = -2208988800; // 1900-01-01T00:00:00+00:00
+        let utc = MoonPhase::with_time_scale(secs, TimeScale::Utc);
+        let tt = MoonPhase::with_time_scale(secs, TimeScale::Tt);
+        assert_eq!(utc.j_date, tt.j_date);
+        assert_eq!(utc, MoonPhase::from_secs(secs));
+        let shift = (utc.age - tt.age) * 86400.;
+        assert!((shift - delta_t(1900.)).abs() < 0.01, "{}", shift);
+        let tdb = MoonPhase::with_time_scale(secs, TimeScale::Tdb);
+        assert!((tdb.age - tt.age).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_secs_is_utc() {
+        // 1000-01-01T00:00:00+00:00, when ΔT was about 26 minutes.
+        let secs = -30_610_224_000;
+        assert!((delta_t(1000.) - 1574.2).abs() < 1e-9);
+        let utc = MoonPhase::from_secs(secs);
+        assert_eq!(utc, MoonPhase::with_time_scale(secs, TimeScale::Utc));
+        // The model sees the same instant as a TT date ΔT later.
+        let tt = MoonPhase::with_time_scale(secs + 1574, TimeScale::Tt);
+        assert!((utc.age - tt.age).abs() * 86400. < 1., "{} {}", utc.age, tt.age);
+        let shift = (utc.age - MoonPhase::with_time_scale(secs, TimeScale::Tt).age) * 86400.;
+        assert!((shift - delta_t(1000.)).abs() < 1., "{}", shift);
+        assert_eq!(TimeScale::default(), TimeScale::Utc);
+    }
+}
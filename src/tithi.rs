@@ -0,0 +1,116 @@
+// The tithi of the Hindu calendar: the time it takes the elongation of the
+// moon from the sun to grow by 12 degrees, so 30 to a lunation. They last
+// from about 19 to 26 hours and start at any time of day.
+
+use crate::ephemeris::Ephemeris;
+use crate::events::zero_crossings;
+use crate::julian_date_from_seconds;
+
+const TITHI_DEGREES: f64 = 12.;
+// Longest a tithi lasts, with some margin.
+const MAX_TITHI_SECS: f64 = 30. * 3600.;
+const STEP_SECS: f64 = 3600.;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Paksha {
+    Shukla,   // Bright half, waxing
+    Krishna,  // Dark half, waning
+}
+
+const NAMES: [&str; 15] = [
+    "Pratipada",
+    "Dwitiya",
+    "Tritiya",
+    "Chaturthi",
+    "Panchami",
+    "Shashthi",
+    "Saptami",
+    "Ashtami",
+    "Navami",
+    "Dashami",
+    "Ekadashi",
+    "Dwadashi",
+    "Trayodashi",
+    "Chaturdashi",
+    "Purnima",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tithi {
+    pub number: u32,  // 1 - 30, 1 starting at the new moon
+    pub start: f64,   // Unix seconds
+    pub end: f64,     // Unix seconds
+}
+
+impl Tithi {
+    pub fn paksha(&self) -> Paksha {
+        if self.number <= 15 {
+            Paksha::Shukla
+        } else {
+            Paksha::Krishna
+        }
+    }
+
+    // 1 - 15 within the paksha.
+    pub fn day(&self) -> u32 {
+        (self.number - 1) % 15 + 1
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.number {
+            30 => "Amavasya",
+            number => NAMES[(number as usize - 1) % 15],
+        }
+    }
+}
+
+// The tithi at `secs` (unix seconds).
+pub fn tithi<E: Ephemeris + ?Sized>(secs: f64, ephemeris: &E) -> Tithi {
+    let elongation = |secs| {
+        let j_date = julian_date_from_seconds(secs);
+        ephemeris.moon(j_date).longitude - ephemeris.sun_longitude(j_date)
+    };
+    let index = (elongation(secs).rem_euclid(360.) / TITHI_DEGREES) as u32;
+    let start_angle = index as f64 * TITHI_DEGREES;
+    let end_angle = start_angle + TITHI_DEGREES;
+    let start = zero_crossings(|secs| elongation(secs) - start_angle, secs - MAX_TITHI_SECS, secs, STEP_SECS);
+    let end = zero_crossings(|secs| elongation(secs) - end_angle, secs, secs + MAX_TITHI_SECS, STEP_SECS);
+    Tithi {
+        number: index + 1,
+        start: start.last().copied().unwrap_or(secs),
+        end: end.first().copied().unwrap_or(secs),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+
+    #[test]
+    fn tithi_around_new_moon() {
+        // New moon on 2022-01-02T18:33:00+00:00.
+        let new_moon = 1641148380.;
+        let first = tithi(new_moon + 3600., &Meeus);
+        assert_eq!((first.number, first.name(), first.paksha()), (1, "Pratipada", Paksha::Shukla));
+        assert!((first.start - new_moon).abs() < 1800., "Failed for {:?}", first);
+        let last = tithi(new_moon - 3600., &Meeus);
+        assert_eq!((last.number, last.name(), last.day()), (30, "Amavasya", 15));
+        assert!((last.end - first.start).abs() < 1e-2);
+    }
+
+    #[test]
+    fn consecutive() {
+        let mut previous = tithi(1640995200., &Meeus);
+        for _ in 0..60 {
+            let next = tithi(previous.end + 1., &Meeus);
+            assert_eq!(next.number, previous.number % 30 + 1, "Failed for {:?}", next);
+            assert!((next.start - previous.end).abs() < 1e-2);
+            let hours = (next.end - next.start) / 3600.;
+            assert!(hours > 19. && hours < 27., "Failed for {:?}", next);
+            previous = next;
+        }
+        assert_eq!(Tithi { number: 15, start: 0., end: 0. }.name(), "Purnima");
+        assert_eq!(Tithi { number: 20, start: 0., end: 0. }.day(), 5);
+    }
+}
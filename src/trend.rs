@@ -0,0 +1,106 @@
+// Whether the illuminated part of the moon is growing or shrinking.
+
+use crate::{MoonPhase, Phase, MOON_SYNODIC_PERIOD, TAU};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Trend {
+    Waxing,
+    Waning,
+    // At new or full moon, where the illumination turns around.
+    Turning,
+}
+
+impl Phase {
+    pub fn trend(self) -> Trend {
+        match self {
+            Phase::New | Phase::Full => Trend::Turning,
+            Phase::WaxingCrescent | Phase::FirstQuarter | Phase::WaxingGibbous => Trend::Waxing,
+            Phase::WaningGibbous | Phase::LastQuarter | Phase::WaningCrescent => Trend::Waning,
+        }
+    }
+
+    pub fn is_waxing(self) -> bool {
+        self.trend() == Trend::Waxing
+    }
+
+    pub fn is_waning(self) -> bool {
+        self.trend() == Trend::Waning
+    }
+}
+
+impl MoonPhase {
+    // Unlike `Phase::trend`, this is only `Turning` at the exact instant of
+    // new or full moon, a new moon an hour old is already waxing.
+    pub fn trend(&self) -> Trend {
+        let phase = self.phase.rem_euclid(1.);
+        if phase == 0. || phase == 0.5 {
+            Trend::Turning
+        } else if phase < 0.5 {
+            Trend::Waxing
+        } else {
+            Trend::Waning
+        }
+    }
+
+    pub fn is_waxing(&self) -> bool {
+        self.trend() == Trend::Waxing
+    }
+
+    pub fn is_waning(&self) -> bool {
+        self.trend() == Trend::Waning
+    }
+
+    // Phase angle (sun - moon - earth) in degrees, 0 at full moon and 180 at
+    // new moon. Negative while waxing and positive while waning, the usual
+    // convention in lunar photometry.
+    pub fn phase_angle(&self) -> f64 {
+        let angle = (2. * self.illumination - 1.).clamp(-1., 1.).acos().to_degrees();
+        if self.is_waxing() {
+            -angle
+        } else {
+            angle
+        }
+    }
+
+    // Change of the illuminated fraction per day, positive while waxing. The
+    // derivative of (1 - cos(TAU * phase)) / 2, at most about 0.106 per day
+    // around the quarters.
+    pub fn illumination_rate(&self) -> f64 {
+        TAU / 2. * (TAU * self.phase).sin() / MOON_SYNODIC_PERIOD
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn phase_trend() {
+        assert_eq!(Phase::New.trend(), Trend::Turning);
+        assert_eq!(Phase::Full.trend(), Trend::Turning);
+        assert!(Phase::WaxingCrescent.is_waxing());
+        assert!(Phase::FirstQuarter.is_waxing());
+        assert!(Phase::LastQuarter.is_waning());
+        assert!(!Phase::New.is_waxing() && !Phase::New.is_waning());
+    }
+
+    #[test]
+    fn moon_phase_trend() {
+        // An hour after the new moon of 2000-01-06T18:13:00+00:00.
+        let new = MoonPhase::from_secs(947182380 + 3600);
+        assert!(new.is_waxing());
+        assert!(new.illumination_rate() > 0. && new.illumination_rate() < 0.01);
+        let first_quarter = MoonPhase::from_secs(947856840);
+        assert!((first_quarter.illumination_rate() - 0.106).abs() < 0.005);
+        let last_quarter = MoonPhase::from_secs(949046160);
+        assert!(last_quarter.is_waning());
+        assert!((last_quarter.illumination_rate() + 0.106).abs() < 0.005);
+        assert!((first_quarter.phase_angle() + 90.).abs() < 15.);
+        assert!((last_quarter.phase_angle() - 90.).abs() < 15.);
+        assert!(new.phase_angle() < -170.);
+        let turning = MoonPhase { phase: 0.5, ..first_quarter };
+        assert_eq!(turning.trend(), Trend::Turning);
+        let full = MoonPhase::from_secs(948429600); // 2000-01-21T04:40:00+00:00
+        assert!(full.phase_angle().abs() < 10.);
+    }
+}
@@ -0,0 +1,292 @@
+// Compares the models against reference data, so the accuracy can be checked
+// for the dates that matter to the caller instead of taken on faith.
+//
+// The bundled references are instants of the principal phases as published
+// by timeanddate.com (minute resolution, UTC) and the worked position example
+// of Meeus, "Astronomical Algorithms" (from ELP-2000/82). The new moon of
+// 2000-01-06 is left out, the simple model's offset was fitted to it.
+//
+// No JPL Horizons samples are bundled. They are read from an export with
+// `parse_horizons`, see `HORIZONS_QUERY`, and checked with
+// `validate_positions`.
+
+use crate::algorithm::Algorithm;
+use crate::calendar::days_from_civil;
+use crate::{MoonPhase, ParseError, Phase, MOON_SYNODIC_PERIOD};
+
+// Parameters of a Horizons API request (https://ssd.jpl.nasa.gov/api/horizons.api)
+// whose result `parse_horizons` reads: geocentric ecliptic longitude and
+// latitude of date, distance and illuminated fraction of the moon in CSV,
+// daily over the years the models are valid for. Times are UT.
+pub const HORIZONS_QUERY: &str = "format=text&COMMAND='301'&EPHEM_TYPE='OBSERVER'\
+&CENTER='500@399'&START_TIME='1900-01-01'&STOP_TIME='2100-01-01'&STEP_SIZE='1d'\
+&QUANTITIES='10,20,31'&CSV_FORMAT='YES'&ANG_FORMAT='DEG'";
+
+// One astronomical unit in km, Horizons gives the distance in au.
+const AU: f64 = 149_597_870.7;
+
+pub const PHASE_REFERENCES: [(i64, Phase); 8] = [
+    ( 915245340, Phase::Full),          // 1999-01-02T02:49:00+00:00
+    ( 932461200, Phase::FirstQuarter),  // 1999-07-20T09:00:00+00:00
+    ( 947856840, Phase::FirstQuarter),  // 2000-01-14T13:34:00+00:00
+    ( 948429600, Phase::Full),          // 2000-01-21T04:40:00+00:00
+    ( 949046160, Phase::LastQuarter),   // 2000-01-28T07:56:00+00:00
+    ( 977764860, Phase::New),           // 2000-12-25T17:21:00+00:00
+    (1641148380, Phase::New),           // 2022-01-02T18:33:00+00:00
+    (1642463280, Phase::Full),          // 2022-01-17T23:48:00+00:00
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PositionReference {
+    pub secs: f64,        // UTC
+    pub longitude: f64,   // Geocentric ecliptic longitude in degrees, mean equinox of date
+    pub latitude: f64,    // Degrees
+    pub distance: f64,    // km
+    pub illumination: Option<f64>,  // 0 - 1
+}
+
+pub const POSITION_REFERENCES: [PositionReference; 1] = [
+    // Examples 47.a and 48.a, 1992 April 12 0h TD.
+    PositionReference {
+        secs: 703036800. - 58.,
+        longitude: 133.162655,
+        latitude: -3.229126,
+        distance: 368409.7,
+        illumination: Some(0.6786),
+    },
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Report {
+    pub samples: usize,
+    pub max_error: f64,
+    pub mean_error: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PositionReport {
+    pub longitude: Report,  // Degrees
+    pub latitude: Report,   // Degrees
+    pub distance: Report,   // km
+    pub illumination: Option<Report>,  // None if no reference has it
+}
+
+// Timing error in minutes of the bundled principal phases between `start`
+// and `end` (unix seconds), None if there are no references in that range.
+pub fn validate(algorithm: Algorithm, start: i64, end: i64) -> Option<Report> {
+    let references = PHASE_REFERENCES
+        .iter()
+        .filter(|(secs, _)| start <= *secs && *secs <= end);
+    report(references.map(|(secs, phase)| phase_error(algorithm, *secs, *phase)))
+}
+
+// Errors against caller supplied positions, None if `references` is empty.
+pub fn validate_positions(
+    algorithm: Algorithm,
+    references: &[PositionReference],
+) -> Option<PositionReport> {
+    let moon_phases: Vec<_> = references
+        .iter()
+        .map(|reference| (reference, calculate(algorithm, reference.secs)))
+        .collect();
+    let earth_radius = crate::ephemeris::EARTH_RADIUS;
+    Some(PositionReport {
+        longitude: report(moon_phases.iter().map(|(reference, moon_phase)| {
+            let diff = (moon_phase.longitude - reference.longitude).rem_euclid(360.);
+            diff.min(360. - diff)
+        }))?,
+        latitude: report(
+            moon_phases
+                .iter()
+                .map(|(reference, moon_phase)| (moon_phase.latitude - reference.latitude).abs()),
+        )?,
+        distance: report(moon_phases.iter().map(|(reference, moon_phase)| {
+            (moon_phase.distance * earth_radius - reference.distance).abs()
+        }))?,
+        illumination: report(moon_phases.iter().filter_map(|(reference, moon_phase)| {
+            Some((moon_phase.illumination - reference.illumination?).abs())
+        })),
+    })
+}
+
+// Reads the rows of a Horizons export made with `HORIZONS_QUERY`, either the
+// whole response or only the lines from the column names to `$$EOE`. The
+// columns are found by their names, so further quantities may be requested.
+pub fn parse_horizons(text: &str) -> Result<Vec<PositionReference>, ParseError> {
+    let mut lines = text.lines().map(str::trim);
+    let mut header = None;
+    for line in &mut lines {
+        if line == "$$SOE" {
+            break;
+        }
+        if line.starts_with("Date__") {
+            header = Some(line);
+        }
+    }
+    // Without `$$SOE` the lines are used up.
+    let columns: Vec<_> = header
+        .filter(|_| text.contains("$$SOE"))
+        .ok_or(ParseError::InvalidFormat)?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    if !columns[0].starts_with("Date__(UT)") {
+        return Err(ParseError::InvalidFormat);
+    }
+    let column = |name: &str| {
+        columns
+            .iter()
+            .position(|column| *column == name)
+            .ok_or(ParseError::InvalidFormat)
+    };
+    let (longitude, latitude, distance, illumination) =
+        (column("ObsEcLon")?, column("ObsEcLat")?, column("delta")?, column("Illu%")?);
+
+    let mut references = Vec::new();
+    for line in lines.take_while(|line| *line != "$$EOE") {
+        let fields: Vec<_> = line.split(',').map(str::trim).collect();
+        let number = |i: usize| -> Result<f64, ParseError> { field(fields.get(i).copied()) };
+        let secs = horizons_time(fields[0])?;
+        references.push(PositionReference {
+            secs,
+            longitude: number(longitude)?,
+            latitude: number(latitude)?,
+            distance: number(distance)? * AU,
+            illumination: Some(number(illumination)? / 100.),
+        });
+    }
+    Ok(references)
+}
+
+// Unix seconds of a Horizons time like `2000-Jan-01 00:00` or
+// `2000-Jan-01 00:00:00.000`.
+fn horizons_time(time: &str) -> Result<f64, ParseError> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (date, time) = time.split_once(' ').ok_or(ParseError::InvalidFormat)?;
+    let mut date = date.split('-');
+    let mut time = time.split(':');
+    let year: i64 = field(date.next())?;
+    let month = date.next();
+    let month = MONTHS
+        .iter()
+        .position(|name| Some(*name) == month)
+        .ok_or(ParseError::InvalidFormat)? as u32
+        + 1;
+    let day: u32 = field(date.next())?;
+    let hour: u32 = field(time.next())?;
+    let minute: u32 = field(time.next())?;
+    let second: f64 = time.next().map_or(Ok(0.), |second| field(Some(second)))?;
+    if date.next().is_some() || time.next().is_some() {
+        return Err(ParseError::InvalidFormat);
+    }
+    if day == 0 || day > 31 || hour > 23 || minute > 59 || !(0. ..60.).contains(&second) {
+        return Err(ParseError::OutOfRange);
+    }
+    let days = days_from_civil(year, month, day);
+    Ok((days * 86400 + (hour * 3600 + minute * 60) as i64) as f64 + second)
+}
+
+fn field<T: std::str::FromStr>(field: Option<&str>) -> Result<T, ParseError> {
+    field
+        .and_then(|field| field.parse().ok())
+        .ok_or(ParseError::InvalidFormat)
+}
+
+// The references are UTC, as the models expect.
+fn calculate(algorithm: Algorithm, secs: f64) -> MoonPhase {
+    MoonPhase::from_secs_float_with_algorithm(secs, algorithm)
+}
+
+// Minutes between the reference instant and the time the model reaches the
+// phase, positive if the model is late.
+fn phase_error(algorithm: Algorithm, secs: i64, phase: Phase) -> f64 {
+    let target = match phase {
+        Phase::New => 0.,
+        Phase::FirstQuarter => 0.25,
+        Phase::Full => 0.5,
+        Phase::LastQuarter => 0.75,
+        _ => unreachable!("only principal phases are used as references"),
+    };
+    let moon_phase = calculate(algorithm, secs as f64);
+    let diff = (target - moon_phase.phase + 0.5).rem_euclid(1.) - 0.5;
+    diff * MOON_SYNODIC_PERIOD * 1440.
+}
+
+fn report<I: Iterator<Item = f64>>(errors: I) -> Option<Report> {
+    let mut samples = 0;
+    let mut max_error: f64 = 0.;
+    let mut sum = 0.;
+    for error in errors {
+        samples += 1;
+        max_error = max_error.max(error.abs());
+        sum += error.abs();
+    }
+    if samples == 0 {
+        return None;
+    }
+    Some(Report {
+        samples,
+        max_error,
+        mean_error: sum / samples as f64,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bundled_references() {
+        let simple = validate(Algorithm::Simple, i64::MIN, i64::MAX).unwrap();
+        assert_eq!(simple.samples, PHASE_REFERENCES.len());
+        assert!(simple.max_error < Algorithm::Simple.accuracy(2451545.).phase_time);
+        let meeus = validate(Algorithm::Meeus, i64::MIN, i64::MAX).unwrap();
+        assert!(meeus.max_error < Algorithm::Meeus.accuracy(2451545.).phase_time, "{:?}", meeus);
+        assert!(meeus.mean_error < simple.mean_error);
+        assert_eq!(validate(Algorithm::Simple, 0, 1000), None);
+    }
+
+    #[test]
+    fn positions() {
+        let report = validate_positions(Algorithm::Meeus, &POSITION_REFERENCES).unwrap();
+        assert_eq!(report.longitude.samples, 1);
+        assert!(report.longitude.max_error < 0.2, "{:?}", report);
+        assert!(report.distance.max_error < 1000., "{:?}", report);
+        assert!(report.illumination.unwrap().max_error < 0.01, "{:?}", report);
+        assert_eq!(validate_positions(Algorithm::Meeus, &[]), None);
+    }
+
+    #[test]
+    fn horizons_export() {
+        // The Meeus example in the layout of a Horizons export, not Horizons data.
+        let export = "\
+*******************************************************************************
+ Date__(UT)__HR:MN:SS, , , Illu%, delta, deldot, ObsEcLon, ObsEcLat,
+*******************************************************************************
+$$SOE
+ 1992-Apr-11 23:59:02, , , 67.86000, 0.00246266673634, -0.0000000, 133.1626550, -3.2291260,
+$$EOE
+*******************************************************************************";
+        let references = parse_horizons(export).unwrap();
+        assert_eq!(references.len(), 1);
+        let reference = references[0];
+        assert_eq!(reference.secs, POSITION_REFERENCES[0].secs);
+        assert_eq!(reference.longitude, POSITION_REFERENCES[0].longitude);
+        assert!((reference.distance - POSITION_REFERENCES[0].distance).abs() < 1e-3);
+        assert!((reference.illumination.unwrap() - 0.6786).abs() < 1e-9);
+        let report = validate_positions(Algorithm::Meeus, &references).unwrap();
+        assert!(report.longitude.max_error < 0.2, "{:?}", report);
+        // Fractions of a second are kept.
+        let fraction = parse_horizons(&export.replace("23:59:02", "23:59:02.250")).unwrap();
+        assert_eq!(fraction[0].secs, POSITION_REFERENCES[0].secs + 0.25);
+
+        assert_eq!(parse_horizons(""), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_horizons(&export.replace("$$SOE", "")), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_horizons(&export.replace("ObsEcLat", "RA")), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_horizons(&export.replace("Apr-11", "Apr-41")), Err(ParseError::OutOfRange));
+        assert_eq!(parse_horizons(&export.replace("Apr", "April")), Err(ParseError::InvalidFormat));
+        assert_eq!(parse_horizons(&export.replace("Date__(UT)", "Date__(TT)")), Err(ParseError::InvalidFormat));
+    }
+}
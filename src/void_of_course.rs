@@ -0,0 +1,116 @@
+// Void-of-course periods, as used in electional astrology: the time from the
+// moon's last major aspect in a sign until it enters the next sign. Only the
+// aspects to the sun are considered (see `aspect`), and the signs are the
+// tropical ones.
+
+use crate::aspect::sun_aspects;
+use crate::ephemeris::Ephemeris;
+use crate::zodiac::ZodiacScheme;
+use crate::Zodiac;
+
+// Longest a moon stays in one sign, with some margin.
+const MAX_SIGN_SECS: f64 = 3. * 86400.;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VoidOfCourse {
+    pub start: f64,    // Unix seconds of the last aspect, or the ingress if there was none
+    pub end: f64,      // Unix seconds of the ingress into the next sign
+    pub sign: Zodiac,  // The sign the moon is leaving
+}
+
+// All void-of-course periods overlapping from..until (unix seconds).
+pub fn void_of_course<E: Ephemeris + ?Sized>(from: f64, until: f64, ephemeris: &E) -> Vec<VoidOfCourse> {
+    let ingresses = ZodiacScheme::tropical().ingresses(
+        from - MAX_SIGN_SECS,
+        until + MAX_SIGN_SECS,
+        ephemeris,
+    );
+    let mut periods = Vec::new();
+    for window in ingresses.windows(2) {
+        let (entry, exit) = (window[0].secs, window[1].secs);
+        // Skips signs the period can't overlap without searching for aspects.
+        if exit <= from || entry >= until {
+            continue;
+        }
+        let last_aspect = sun_aspects(entry, exit, ephemeris)
+            .last()
+            .map_or(entry, |event| event.secs);
+        if last_aspect >= until {
+            continue;
+        }
+        periods.push(VoidOfCourse {
+            start: last_aspect,
+            end: exit,
+            sign: window[1].from,
+        });
+    }
+    periods
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithm::Meeus;
+    use crate::ephemeris::MoonPosition;
+    use crate::julian_date_from_seconds;
+    use crate::zodiac::sign;
+
+    // Moon moving 12 degrees a day from 0 at the epoch, sun fixed at 10.
+    struct Uniform;
+
+    impl Ephemeris for Uniform {
+        fn moon(&self, j_date: f64) -> MoonPosition {
+            MoonPosition {
+                longitude: ((j_date - 2440587.5) * 12.).rem_euclid(360.),
+                latitude: 0.,
+                distance: 384400.,
+            }
+        }
+
+        fn sun_longitude(&self, _j_date: f64) -> f64 {
+            10.
+        }
+    }
+
+    #[test]
+    fn uniform() {
+        let day = 86400.;
+        let periods = void_of_course(day, 7. * day, &Uniform);
+        // Sign boundaries every 2.5 days, the first one overlapping is Aries.
+        assert_eq!(periods.len(), 3);
+        let expected = [
+            // Conjunction at 10 degrees.
+            (10. / 12. * day, 2.5 * day, Zodiac::Aries),
+            // No aspect at all in Taurus.
+            (2.5 * day, 5. * day, Zodiac::Taurus),
+            // Sextile at 70 degrees.
+            (70. / 12. * day, 7.5 * day, Zodiac::Gemini),
+        ];
+        for (period, (start, end, sign)) in periods.iter().zip(&expected) {
+            assert!((period.start - start).abs() < 1., "{:?}", period);
+            assert!((period.end - end).abs() < 1., "{:?}", period);
+            assert_eq!(period.sign, *sign);
+        }
+
+        // In Gemini, but before the moon is void of course there.
+        assert!(void_of_course(5.1 * day, 5.5 * day, &Uniform).is_empty());
+        let periods = void_of_course(5.1 * day, 6. * day, &Uniform);
+        assert_eq!(periods.len(), 1);
+        assert_eq!(periods[0].sign, Zodiac::Gemini);
+    }
+
+    #[test]
+    fn meeus() {
+        // A month from 2022-01-01T00:00:00+00:00.
+        let from = 1640995200.;
+        let periods = void_of_course(from, from + 30. * 86400., &Meeus);
+        assert!((11..=15).contains(&periods.len()), "{}", periods.len());
+        for pair in periods.windows(2) {
+            assert!(pair[0].start <= pair[0].end);
+            assert!(pair[0].end <= pair[1].start);
+            // One sign after the other.
+            let after = Meeus.moon(julian_date_from_seconds(pair[0].end + 60.));
+            assert_eq!(sign(after.longitude), pair[1].sign);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// JavaScript bindings. In JavaScript the class is called `MoonPhase`:
+//
+//     import init, { MoonPhase } from "./pkg/moon_phase.js";
+//     await init();
+//     const moon = new MoonPhase(Date.now());
+//     moon.emoji + " " + moon.phaseTitle + ", " + moon.illuminationPercent.toFixed(0) + "%";
+//     JSON.parse(moon.toJson());
+//
+// Times are milliseconds since the epoch like `Date.now()`, names are the
+// keys of `MoonPhase::to_json`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::ephemeris::EARTH_RADIUS;
+use crate::json::{phase_key, zodiac_key};
+
+#[wasm_bindgen(js_name = MoonPhase)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonPhase(crate::MoonPhase);
+
+#[wasm_bindgen(js_class = MoonPhase)]
+impl MoonPhase {
+    // Throws for NaN and times outside of `VALID_FROM..VALID_UNTIL`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(millis: f64) -> Result<MoonPhase, JsError> {
+        crate::MoonPhase::try_from_secs_float(millis / 1000.)
+            .map(MoonPhase)
+            .map_err(|error| JsError::new(&error.to_string()))
+    }
+
+    pub fn now() -> MoonPhase {
+        MoonPhase(crate::MoonPhase::now())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn timestamp(&self) -> f64 {
+        (self.0.j_date - 2440587.5) * 86400000.
+    }
+
+    #[wasm_bindgen(getter, js_name = julianDate)]
+    pub fn julian_date(&self) -> f64 {
+        self.0.j_date
+    }
+
+    // 0 - 1, 0.5 = full
+    #[wasm_bindgen(getter)]
+    pub fn phase(&self) -> f64 {
+        self.0.phase
+    }
+
+    // Days
+    #[wasm_bindgen(getter)]
+    pub fn age(&self) -> f64 {
+        self.0.age
+    }
+
+    // 0 - 1
+    #[wasm_bindgen(getter)]
+    pub fn illumination(&self) -> f64 {
+        self.0.illumination
+    }
+
+    #[wasm_bindgen(getter, js_name = illuminationPercent)]
+    pub fn illumination_percent(&self) -> f64 {
+        self.0.illumination * 100.
+    }
+
+    // "new", "waxing_crescent", ...
+    #[wasm_bindgen(getter, js_name = phaseName)]
+    pub fn phase_name(&self) -> String {
+        phase_key(self.0.phase_name).to_string()
+    }
+
+    // "New Moon", "Waxing Crescent", ...
+    #[wasm_bindgen(getter, js_name = phaseTitle)]
+    pub fn phase_title(&self) -> String {
+        self.0.phase_name.to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn emoji(&self) -> String {
+        self.0.phase_name.emoji().to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = distanceKm)]
+    pub fn distance_km(&self) -> f64 {
+        self.0.distance * EARTH_RADIUS
+    }
+
+    #[wasm_bindgen(getter, js_name = distanceEarthRadii)]
+    pub fn distance_earth_radii(&self) -> f64 {
+        self.0.distance
+    }
+
+    // Ecliptic, degrees
+    #[wasm_bindgen(getter)]
+    pub fn latitude(&self) -> f64 {
+        self.0.latitude
+    }
+
+    // Ecliptic, degrees
+    #[wasm_bindgen(getter)]
+    pub fn longitude(&self) -> f64 {
+        self.0.longitude
+    }
+
+    // "pisces", "aries", ...
+    #[wasm_bindgen(getter)]
+    pub fn zodiac(&self) -> String {
+        zodiac_key(self.0.zodiac_name).to_string()
+    }
+
+    // See `MoonPhase::to_json`.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        self.0.to_json()
+    }
+}
+
+impl From<crate::MoonPhase> for MoonPhase {
+    fn from(moon_phase: crate::MoonPhase) -> Self {
+        MoonPhase(moon_phase)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn getters() {
+        // Full moon on 2022-01-17T23:48:00+00:00.
+        let moon_phase = MoonPhase::new(1642463280000.).unwrap();
+        assert_eq!(moon_phase.0, crate::MoonPhase::from_secs(1642463280));
+        assert!((moon_phase.timestamp() - 1642463280000.).abs() < 1., "Failed for {}", moon_phase.timestamp());
+        assert_eq!(moon_phase.phase_name(), "full");
+        assert_eq!(moon_phase.phase_title(), "Full Moon");
+        assert_eq!(moon_phase.emoji(), "🌕");
+        assert!(moon_phase.illumination_percent() > 99.);
+        assert_eq!(moon_phase.to_json(), crate::MoonPhase::from_secs(1642463280).to_json());
+    }
+}
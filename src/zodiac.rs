@@ -0,0 +1,382 @@
+// Different ways of dividing the ecliptic into the twelve signs. The default,
+// used for `MoonPhase::zodiac_name`, are the unequal constellations of the
+// original algorithm. Astrology uses equal signs of 30 degrees instead,
+// counted from the vernal equinox (tropical) or, in Vedic astrology, from a
+// point fixed to the stars (sidereal).
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::ephemeris::Ephemeris;
+use crate::events::zero_crossings;
+use crate::{julian_date_from_seconds, Error, MoonPhase, Zodiac, ZODIAC_ANGLES};
+
+const J2000: f64 = 2451545.0;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum ZodiacMode {
+    // The constellations, as in `Zodiac::from_long`. The boundaries are
+    // fixed to the equinox, which drifts against the stars by about a
+    // degree every 72 years, so this is only right for dates near J2000.
+    #[default]
+    Constellations,
+    // Same constellations, with the boundaries moved along with the
+    // equinox of date. Slightly slower, but valid for any date.
+    PrecessedConstellations,
+    // Equal signs of 30 degrees, starting with Aries at the vernal equinox.
+    Tropical,
+    // Equal signs of 30 degrees, shifted back by the ayanamsa.
+    Sidereal(Ayanamsa),
+}
+
+// Offset between the tropical and sidereal zodiac. The variants differ in
+// the star the sidereal zodiac is anchored to.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Ayanamsa {
+    // Official in India, anchored to Spica at 180 degrees.
+    #[default]
+    Lahiri,
+    // Western sidereal astrology.
+    FaganBradley,
+    Raman,
+    Krishnamurti,
+}
+
+impl Ayanamsa {
+    // The ayanamsa in degrees at the given Julian date. The values at J2000
+    // are the ones used by the Swiss Ephemeris, they grow with precession.
+    pub fn degrees(self, j_date: f64) -> f64 {
+        let at_j2000 = match self {
+            Ayanamsa::Lahiri => 23.857092,
+            Ayanamsa::FaganBradley => 24.740300,
+            Ayanamsa::Raman => 22.410791,
+            Ayanamsa::Krishnamurti => 23.760240,
+        };
+        at_j2000 + precession(j_date)
+    }
+}
+
+// General precession in longitude since J2000 in degrees, after Lieske (1977).
+pub(crate) fn precession(j_date: f64) -> f64 {
+    let t = (j_date - J2000) / 36525.;
+    (5029.0966 * t + 1.11113 * t * t) / 3600.
+}
+
+// Signs in the order they follow the vernal equinox.
+const SIGNS: [Zodiac; 12] = [
+    Zodiac::Aries,
+    Zodiac::Taurus,
+    Zodiac::Gemini,
+    Zodiac::Cancer,
+    Zodiac::Leo,
+    Zodiac::Virgo,
+    Zodiac::Libra,
+    Zodiac::Scorpio,
+    Zodiac::Sagittarius,
+    Zodiac::Capricorn,
+    Zodiac::Aquarius,
+    Zodiac::Pisces,
+];
+
+impl ZodiacMode {
+    // Sign for an ecliptic longitude in degrees (equinox of date) at the
+    // given Julian date.
+    pub fn zodiac(self, longitude: f64, j_date: f64) -> Zodiac {
+        match self {
+            ZodiacMode::Constellations => Zodiac::from_long(longitude),
+            ZodiacMode::PrecessedConstellations => Zodiac::from_long(longitude - precession(j_date)),
+            ZodiacMode::Tropical => sign(longitude),
+            ZodiacMode::Sidereal(ayanamsa) => sign(longitude - ayanamsa.degrees(j_date)),
+        }
+    }
+}
+
+// Where in its sign a longitude lies, e.g. 17°23′ Taurus.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SignPosition {
+    pub zodiac: Zodiac,
+    pub degrees: f64,  // Since the start of the sign, 0 - 30 for equal signs
+}
+
+impl SignPosition {
+    // Whole degrees and minutes, rounded down.
+    pub fn degrees_minutes(&self) -> (u32, u32) {
+        let minutes = (self.degrees * 60.).floor() as u32;
+        (minutes / 60, minutes % 60)
+    }
+}
+
+impl fmt::Display for SignPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (degrees, minutes) = self.degrees_minutes();
+        write!(f, "{}°{:02}′ {:?}", degrees, minutes, self.zodiac)
+    }
+}
+
+impl ZodiacMode {
+    pub fn position(self, longitude: f64, j_date: f64) -> SignPosition {
+        let longitude = match self {
+            ZodiacMode::PrecessedConstellations => {
+                return ZodiacMode::Constellations.position(longitude - precession(j_date), j_date)
+            }
+            ZodiacMode::Constellations => {
+                let (zodiac, degrees) = ZodiacScheme::constellations().position(longitude);
+                return SignPosition { zodiac, degrees };
+            }
+            ZodiacMode::Tropical => longitude,
+            ZodiacMode::Sidereal(ayanamsa) => longitude - ayanamsa.degrees(j_date),
+        };
+        SignPosition {
+            zodiac: sign(longitude),
+            degrees: longitude.rem_euclid(30.),
+        }
+    }
+}
+
+// The tropical sign of a longitude.
+pub(crate) fn sign(longitude: f64) -> Zodiac {
+    SIGNS[(longitude.rem_euclid(360.) / 30.) as usize % 12]
+}
+
+// A division of the ecliptic, given by the longitudes (equinox of date) at
+// which each part ends, in ascending order. The part after the last end
+// wraps around to the first one, as Pisces does for the constellations.
+// Parts can be labelled with anything, not just `Zodiac`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZodiacScheme<T: Clone + 'static = Zodiac> {
+    ends: Cow<'static, [(f64, T)]>,
+}
+
+// Moon entering a new part of a `ZodiacScheme`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ingress<T = Zodiac> {
+    pub secs: f64,  // Unix seconds
+    pub from: T,
+    pub to: T,
+}
+
+// The moon moves about 13 degrees a day, so at most 1.1 degrees per step.
+const INGRESS_STEP_SECS: f64 = 7200.;
+
+const TROPICAL: [(f64, Zodiac); 12] = [
+    (30., Zodiac::Aries),
+    (60., Zodiac::Taurus),
+    (90., Zodiac::Gemini),
+    (120., Zodiac::Cancer),
+    (150., Zodiac::Leo),
+    (180., Zodiac::Virgo),
+    (210., Zodiac::Libra),
+    (240., Zodiac::Scorpio),
+    (270., Zodiac::Sagittarius),
+    (300., Zodiac::Capricorn),
+    (330., Zodiac::Aquarius),
+    (360., Zodiac::Pisces),
+];
+
+impl ZodiacScheme {
+    // The constellations of `Zodiac::from_long`.
+    pub fn constellations() -> Self {
+        ZodiacScheme::from_static(&ZODIAC_ANGLES)
+    }
+
+    // Equal signs of 30 degrees, as in `ZodiacMode::Tropical`.
+    pub fn tropical() -> Self {
+        ZodiacScheme::from_static(&TROPICAL)
+    }
+}
+
+impl<T: Clone + 'static> ZodiacScheme<T> {
+    // For tables that are known to be valid, without copying them.
+    pub(crate) const fn from_static(ends: &'static [(f64, T)]) -> Self {
+        ZodiacScheme {
+            ends: Cow::Borrowed(ends),
+        }
+    }
+
+    pub fn new(ends: Vec<(f64, T)>) -> Result<Self, Error> {
+        let ascending = ends.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        let in_range = ends.iter().all(|(end, _)| *end > 0. && *end <= 360.);
+        if ends.is_empty() || !ascending || !in_range {
+            return Err(Error::InvalidScheme);
+        }
+        Ok(ZodiacScheme {
+            ends: Cow::Owned(ends),
+        })
+    }
+
+    fn index(&self, longitude: f64) -> usize {
+        let longitude = longitude.rem_euclid(360.);
+        self.ends
+            .iter()
+            .position(|(end, _)| longitude < *end)
+            .unwrap_or(0)
+    }
+
+    // Longitudes outside of 0 - 360 degrees are wrapped around, NaN and
+    // infinite ones give the part at 0 degrees.
+    pub fn find(&self, longitude: f64) -> T {
+        self.ends[self.index(longitude)].1.clone()
+    }
+
+    // The part and how many degrees into it the longitude lies.
+    pub fn position(&self, longitude: f64) -> (T, f64) {
+        let index = self.index(longitude);
+        let start = match index {
+            0 => self.ends[self.ends.len() - 1].0 - 360.,
+            i => self.ends[i - 1].0,
+        };
+        let degrees = (longitude - start).rem_euclid(360.);
+        (self.ends[index].1.clone(), degrees)
+    }
+
+    // All times in from..until (unix seconds) the moon passes into another
+    // part, in order.
+    pub fn ingresses<E: Ephemeris + ?Sized>(&self, from: f64, until: f64, ephemeris: &E) -> Vec<Ingress<T>> {
+        let moon = |secs| ephemeris.moon(julian_date_from_seconds(secs)).longitude;
+        let mut ingresses = Vec::new();
+        for (i, (end, part)) in self.ends.iter().enumerate() {
+            let next = &self.ends[(i + 1) % self.ends.len()].1;
+            for secs in zero_crossings(|secs| moon(secs) - end, from, until, INGRESS_STEP_SECS) {
+                ingresses.push(Ingress {
+                    secs,
+                    from: part.clone(),
+                    to: next.clone(),
+                });
+            }
+        }
+        ingresses.sort_by(|a, b| a.secs.partial_cmp(&b.secs).unwrap());
+        ingresses
+    }
+}
+
+impl MoonPhase {
+    pub fn zodiac(&self, mode: ZodiacMode) -> Zodiac {
+        mode.zodiac(self.longitude, self.j_date)
+    }
+
+    pub fn sign_position(&self, mode: ZodiacMode) -> SignPosition {
+        mode.position(self.longitude, self.j_date)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tropical() {
+        let tropical = |longitude| ZodiacMode::Tropical.zodiac(longitude, 2451545.);
+        assert_eq!(tropical(0.), Zodiac::Aries);
+        assert_eq!(tropical(29.99), Zodiac::Aries);
+        assert_eq!(tropical(30.), Zodiac::Taurus);
+        assert_eq!(tropical(45.), Zodiac::Taurus);
+        assert_eq!(tropical(359.), Zodiac::Pisces);
+        assert_eq!(tropical(-1.), Zodiac::Pisces);
+        // The constellation of Aries starts only at 33.18 degrees.
+        assert_eq!(ZodiacMode::Constellations.zodiac(20., 2451545.), Zodiac::Pisces);
+    }
+
+    #[test]
+    fn sidereal() {
+        assert!((Ayanamsa::Lahiri.degrees(J2000) - 23.857).abs() < 1e-3);
+        // About 50 arc seconds a year.
+        let after_century = Ayanamsa::Lahiri.degrees(J2000 + 36525.);
+        assert!((after_century - 23.857 - 1.397).abs() < 1e-3);
+        let lahiri = ZodiacMode::Sidereal(Ayanamsa::Lahiri);
+        assert_eq!(lahiri.zodiac(10., J2000), Zodiac::Pisces);
+        assert_eq!(lahiri.zodiac(24., J2000), Zodiac::Aries);
+        assert_eq!(ZodiacMode::Sidereal(Ayanamsa::FaganBradley).zodiac(54., J2000), Zodiac::Aries);
+        assert_eq!(ZodiacMode::Sidereal(Ayanamsa::Raman).zodiac(54., J2000), Zodiac::Taurus);
+    }
+
+    #[test]
+    fn precessed() {
+        let precessed = ZodiacMode::PrecessedConstellations;
+        for longitude in 0..360 {
+            let longitude = longitude as f64 + 0.5;
+            assert_eq!(precessed.zodiac(longitude, J2000), Zodiac::from_long(longitude));
+        }
+        // A thousand years later the equinox has moved by almost 14 degrees.
+        let j_date = J2000 + 365250.;
+        assert_eq!(ZodiacMode::Constellations.zodiac(40., j_date), Zodiac::Aries);
+        assert_eq!(precessed.zodiac(40., j_date), Zodiac::Pisces);
+        let position = precessed.position(60., j_date);
+        assert_eq!(position.zodiac, Zodiac::Aries);
+        assert!((position.degrees - (60. - precession(j_date) - 33.18)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn position() {
+        let position = ZodiacMode::Tropical.position(47.39, J2000);
+        assert_eq!(position.zodiac, Zodiac::Taurus);
+        assert_eq!(position.degrees_minutes(), (17, 23));
+        assert_eq!(position.to_string(), "17°23′ Taurus");
+        let position = ZodiacMode::Sidereal(Ayanamsa::Lahiri).position(30., J2000);
+        assert_eq!(position.zodiac, Zodiac::Aries);
+        assert!((position.degrees - 6.143).abs() < 1e-3);
+        let position = ZodiacMode::Constellations.position(60., J2000);
+        assert_eq!(position.zodiac, Zodiac::Taurus);
+        assert!((position.degrees - 8.84).abs() < 1e-9);
+        // Pisces wraps around the equinox.
+        let position = ZodiacMode::Constellations.position(10., J2000);
+        assert_eq!(position.zodiac, Zodiac::Pisces);
+        assert!((position.degrees - 21.42).abs() < 1e-9);
+        let position = ZodiacMode::Constellations.position(350., J2000);
+        assert!((position.degrees - 1.42).abs() < 1e-9);
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        assert_eq!(moon_phase.sign_position(ZodiacMode::default()).zodiac, moon_phase.zodiac_name);
+    }
+
+    #[test]
+    fn scheme() {
+        let constellations = ZodiacScheme::constellations();
+        let tropical = ZodiacScheme::tropical();
+        for longitude in -360..720 {
+            let longitude = longitude as f64 + 0.5;
+            assert_eq!(constellations.find(longitude), Zodiac::from_long(longitude));
+            assert_eq!(tropical.find(longitude), sign(longitude));
+            let (zodiac, degrees) = tropical.position(longitude);
+            let position = ZodiacMode::Tropical.position(longitude, J2000);
+            assert_eq!(zodiac, position.zodiac);
+            assert!((degrees - position.degrees).abs() < 1e-9);
+        }
+
+        // Custom labels, e.g. the four quarters of the circle.
+        let quarters = ZodiacScheme::new(vec![(45., 'E'), (135., 'N'), (225., 'W'), (315., 'S')]).unwrap();
+        assert_eq!(quarters.find(0.), 'E');
+        assert_eq!(quarters.find(320.), 'E');
+        assert_eq!(quarters.position(320.), ('E', 5.));
+        assert_eq!(quarters.find(200.), 'W');
+        assert_eq!(ZodiacScheme::<char>::new(vec![]), Err(Error::InvalidScheme));
+        assert_eq!(ZodiacScheme::new(vec![(90., 'a'), (45., 'b')]), Err(Error::InvalidScheme));
+        assert_eq!(ZodiacScheme::new(vec![(400., 'a')]), Err(Error::InvalidScheme));
+    }
+
+    #[test]
+    fn ingresses() {
+        use crate::algorithm::Meeus;
+        // A month from 2022-01-01T00:00:00+00:00, the moon passes every sign once.
+        let from = 1640995200.;
+        let ingresses = ZodiacScheme::tropical().ingresses(from, from + 27.3 * 86400., &Meeus);
+        assert_eq!(ingresses.len(), 12);
+        for pair in ingresses.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from);
+            assert!(pair[1].secs - pair[0].secs < 3. * 86400.);
+        }
+        for ingress in &ingresses {
+            let before = Meeus.moon(julian_date_from_seconds(ingress.secs - 1.)).longitude;
+            let after = Meeus.moon(julian_date_from_seconds(ingress.secs + 1.)).longitude;
+            assert_eq!(sign(before), ingress.from);
+            assert_eq!(sign(after), ingress.to);
+        }
+    }
+
+    #[test]
+    fn moon_phase_zodiac() {
+        let moon_phase = MoonPhase::from_secs(1642291200);
+        assert_eq!(moon_phase.zodiac(ZodiacMode::default()), moon_phase.zodiac_name);
+        let sign = moon_phase.zodiac(ZodiacMode::Tropical);
+        assert_eq!(sign, SIGNS[(moon_phase.longitude / 30.) as usize]);
+    }
+}